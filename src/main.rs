@@ -6,26 +6,68 @@ use anyhow::Result;
 use reqwest::Client;
 use serde_json::{json, Value};
 use tracing::{error, info};
-use crate::sprint_summary::{SprintCommandParser, events::MapToSprintEvents};
+use crate::sprint_summary::{SprintCommandParser, TicketSummary, events::MapToSprintEvents};
 use crate::utils::s3::create_json_storage_client;
-use crate::utils::slack_output::TeamCommunicationClient;
-use crate::sprint_summary::sprint_records::{DailyTicketContextClient, ActiveSprintContextClient, CumulativeSprintContextClient, SprintMemberClient};
-use crate::sprint_summary::ticket_sources::TicketSummaryClient;
+use crate::utils::config::{Config, TicketSource};
+use crate::utils::slack_output::{SlackClient, TeamCommunicationClient};
+use crate::sprint_summary::sprint_records::{DailyTicketContextClient, ActiveSprintContextClient, CumulativeSprintContextClient, SprintMemberClient, ProcessedTriggerClient, ProcessingLockClient, PreviewCacheClient};
+use crate::sprint_summary::ticket_sources::{GithubIssuesClient, TicketClient, TicketHistory, TicketSummaryClient, TrelloCredentialsResolver, TrelloTicketClient};
+use crate::sprint_summary::footer_links::FooterLinksClient;
+use crate::utils::localization::{Label, Localization, LocalizationClient};
+use crate::utils::slack_components::section_block;
 use sprint_summary::sprint_records::{CumulativeSprintContexts,DailyTicketContexts};
 
 #[cfg(not(test))]
-async fn function_handler(event: LambdaEvent<Value>) -> Result<Value, Error> {
-    use std::collections::{HashMap, VecDeque};
+async fn function_handler(event: LambdaEvent<Value>, config: &Config) -> Result<Value, Error> {
+    use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 
-    use sprint_summary::{events::SprintEvents, SprintCommand};
-    use utils::eventbridge::create_eventbridge_client;
+    use sprint_summary::{events::SprintEvents, fallback_summary_text, CommandContext, SprintCommand};
+    use std::time::Duration;
+    use utils::{date::{current_hour, current_timestamp}, eventbridge::create_eventbridge_client, secrets::create_secrets_client, slack_users::DisplayNameResolver, time_budget::{SystemClock, TimeBudget}};
+
+    //covers clock drift between lambda and the lock's storage
+    const PROCESSING_LOCK_MARGIN_SECONDS: i64 = 30;
+    const TIME_BUDGET_RESERVE_SECONDS: u64 = 5;
+
+    let time_budget = TimeBudget::new(Box::new(SystemClock::start()), Duration::from_secs(config.time_budget_seconds), Duration::from_secs(TIME_BUDGET_RESERVE_SECONDS));
 
     info!("Input is: {:?}", event);
 
-    // Try to execute the function logic and catch any error
-    let result: Result<Value, Error> = (|| async {
-        let sprint_client = create_json_storage_client().await;
+    //requests that fail verification are turned away before they can hold the lock
+    let sprint_events = match event.try_into_sprint_events(config) {
+        Ok(sprint_events) => sprint_events,
+        Err(e) => {
+            error!("Rejected request: {:?}", e);
+            return Ok(json!(format!("Error processing command: {:?}", e)));
+        }
+    };
 
+    let sprint_client = create_json_storage_client(config).await;
+    let lock_holder = event.context.request_id.clone();
+    //the invocation can't run past its deadline, so its lock is only honored until then
+    let lock_expires_at = (event.context.deadline / 1000) as i64 + PROCESSING_LOCK_MARGIN_SECONDS;
+
+    if !sprint_client.acquire_processing_lock(&lock_holder, current_timestamp(), lock_expires_at).await? {
+        info!("Another invocation is processing sprint state, backing off");
+
+        //whoever ran the command is told why nothing happened, scheduled triggers just back off
+        if let SprintEvents::MessageTrigger { channel_id, response_url: Some(response_url), .. } = &sprint_events {
+            let localization = match &config.summary_locale {
+                Some(locale) => sprint_client.get_localization(locale).await.ok().flatten().unwrap_or_default(),
+                None => Localization::default(),
+            };
+
+            let fetch_client = Client::new();
+            if let Err(send_error) = SlackClient::new(&fetch_client, &config.slack_oauth).send_teams_message(channel_id, &vec![section_block(&localization.text(Label::SummaryInProgress))], Some(response_url.clone())).await {
+                error!("Failed to tell the user a summary is in progress: {:?}", send_error);
+            }
+        }
+
+        return Ok(json!("Skipped, another invocation is in progress"));
+    }
+
+    // Try to execute the function logic and catch any error
+    let result: Result<Value, Error> = sprint_client.release_processing_lock_after(&lock_holder, (|| async {
         let active_sprint_context = sprint_client.get_sprint_data().await?;
         let previous_ticket_data = sprint_client.get_ticket_data().await?.unwrap_or(DailyTicketContexts {
             tickets: VecDeque::new(),
@@ -35,32 +77,144 @@ async fn function_handler(event: LambdaEvent<Value>) -> Result<Value, Error> {
             history: Vec::new(),
         });
 
-        let sprint_events = event.try_into_sprint_events().expect("Failed to parse sprint events");
+        let localization = match &config.summary_locale {
+            Some(locale) => sprint_client.get_localization(locale).await?.unwrap_or_default(),
+            None => Localization::default(),
+        };
+
+        if let Some(trigger_id) = sprint_events.trigger_id() {
+            if !sprint_client.claim_trigger(trigger_id, current_timestamp(), config.trigger_dedupe_window_seconds).await? {
+                info!("Skipping already processed trigger: {}", trigger_id);
+                return Ok(json!("Skipped duplicate trigger"));
+            }
+        }
 
         let (channel_id, response_url) = match &sprint_events {
             SprintEvents::MessageTrigger { channel_id, response_url, .. } => (channel_id.clone(), response_url.clone()),
             _ => (active_sprint_context.as_ref().unwrap().channel_id.clone(), None)
         };
 
-        let sprint_command_result = sprint_events.try_into_sprint_command(&active_sprint_context, &cumulative_sprint_contexts).await;
+        let sprint_command_result = sprint_events.try_into_sprint_command(&active_sprint_context, &cumulative_sprint_contexts, config).await;
 
         match sprint_command_result {
             Ok(sprint_command) => {
                 info!("Sprint event is valid: {:?}", sprint_command);
 
+                if sprint_command.is_throttled(&sprint_client, current_timestamp(), config).await? {
+                    info!("Skipping summary, one was already posted in the last {:?} seconds", config.summary_throttle_seconds);
+                    return Ok(json!("Skipped, a summary was posted recently"));
+                }
+
                 let fetch_client = Client::new();
+                let slack_client = SlackClient::new(&fetch_client, &config.slack_oauth);
                 let name = match &sprint_command {
-                    SprintCommand::SprintPreview { sprint_name, .. } | SprintCommand::SprintKickoff { sprint_name, .. } => sprint_name,
-                    _ => &active_sprint_context.as_ref().unwrap().name,
+                    SprintCommand::SprintPreview { sprint_name, .. } | SprintCommand::SprintKickoff { sprint_name, .. } => sprint_name.as_str(),
+                    //member mappings and board hygiene don't need an active sprint
+                    _ => active_sprint_context.as_ref().map_or("", |context| context.name.as_str()),
                 };
 
-                let mut ticket_summary = fetch_client.fetch_ticket_summary(name, &cumulative_sprint_contexts, &previous_ticket_data, user_mapping).await?;
-                let notification_client = create_eventbridge_client().await;
+                let manual_deferrals = active_sprint_context.as_ref().map(|context| context.manual_deferrals.clone()).unwrap_or(BTreeSet::new());
 
-                let sprint_message = sprint_command.create_sprint_message(&ticket_summary, &active_sprint_context, &cumulative_sprint_contexts, &previous_ticket_data).await.expect("should generate sprint message");
-                sprint_command.save_sprint_state(&mut ticket_summary, &active_sprint_context, &mut cumulative_sprint_contexts, &sprint_client, &notification_client).await.expect("should update sprint state");
+                let preview_summary = match (&sprint_command, config.preview_cache_seconds) {
+                    (SprintCommand::SprintKickoff { preview_token: Some(preview_token), .. }, Some(max_age_seconds)) => {
+                        sprint_client.take_preview_summary(preview_token, current_timestamp(), max_age_seconds).await?
+                    },
+                    _ => None,
+                };
+
+                let mut ticket_summary = match preview_summary {
+                    Some(preview_summary) => {
+                        info!("Reusing ticket summary from sprint preview");
+                        preview_summary
+                    },
+                    //commands that only change stored sprint state don't need the ticket source or its credentials
+                    None if !sprint_command.fetches_tickets() => TicketSummary::from_tickets(vec![], false),
+                    None => {
+                        let ticket_client = match config.ticket_source {
+                            TicketSource::Trello => {
+                                let secrets_client = create_secrets_client().await;
+                                let trello_credentials = TrelloCredentialsResolver::new(&secrets_client, config).resolve().await?;
+                                TicketClient::Trello(TrelloTicketClient::new(&fetch_client, config, trello_credentials, name, active_sprint_context.as_ref().map(|context| context.start_date.as_str())))
+                            },
+                            TicketSource::GithubIssues => TicketClient::GithubIssues(GithubIssuesClient::new(&fetch_client, config, active_sprint_context.as_ref().map(|context| context.start_date.as_str()))),
+                        };
+
+                        ticket_client.fetch_ticket_summary(name, TicketHistory { historical_records: &cumulative_sprint_contexts, previous_ticket_data: &previous_ticket_data, manual_deferrals: &manual_deferrals }, user_mapping, &time_budget, config).await?
+                    },
+                };
+                if config.include_member_display_names {
+                    let display_name_resolver = DisplayNameResolver::new(&slack_client);
+                    //the summary still posts with raw member ids if Slack can't resolve their names
+                    ticket_summary.member_display_names = display_name_resolver.resolve_all(ticket_summary.member_ids()).await.unwrap_or_else(|resolve_error| {
+                        error!("Failed to resolve member display names: {:?}", resolve_error);
+                        BTreeMap::new()
+                    });
+                }
+                let notification_client = create_eventbridge_client(config).await;
 
-                fetch_client.send_teams_message(&channel_id, &sprint_message, response_url).await?;
+                let mut outcome = sprint_command.execute(&channel_id, CommandContext {
+                    ticket_summary: &mut ticket_summary,
+                    active_sprint_context: &active_sprint_context,
+                    cumulative_sprint_contexts: &mut cumulative_sprint_contexts,
+                    daily_ticket_contexts: &previous_ticket_data,
+                }, &localization, &sprint_client, &notification_client, config).await?;
+                info!("Sprint command changed: {:?}", outcome.state_changes);
+                outcome.blocks.extend(sprint_client.get_footer_links(config.summary_footer_links.as_deref()).await?.into_slack_blocks());
+                if let Some(quiet_hours) = &config.quiet_hours {
+                    outcome.blocks = quiet_hours.apply(current_hour(config.summary_timezone), outcome.blocks, &ticket_summary.member_display_names);
+                    for (_, alert_blocks) in outcome.alerts.iter_mut() {
+                        *alert_blocks = quiet_hours.apply(current_hour(config.summary_timezone), std::mem::take(alert_blocks), &ticket_summary.member_display_names);
+                    }
+                }
+
+                //the channel still gets the summary if the canvas it was meant to replace couldn't be updated
+                let canvas_updated = match sprint_command.update_summary_canvas(&outcome.blocks, &active_sprint_context, &slack_client, &sprint_client, &localization, config).await {
+                    Ok(canvas_state_changes) => {
+                        info!("Summary canvas changed: {:?}", canvas_state_changes);
+                        true
+                    },
+                    Err(canvas_error) => {
+                        error!("Failed to update summary canvas: {:?}", canvas_error);
+                        false
+                    },
+                };
+
+                if !(canvas_updated && sprint_command.skips_channel_message(config)) {
+                    let send_result = if sprint_command.updates_in_place(config) {
+                        sprint_command.update_daily_summary_in_place(&outcome.blocks, &active_sprint_context, &slack_client, &sprint_client, config).await
+                            .map(|message_state_changes| info!("Daily summary message changed: {:?}", message_state_changes))
+                    } else if sprint_command.posts_ephemeral_preview(config) {
+                        sprint_command.post_ephemeral_preview(&outcome.channel_id, sprint_events.user_id(), &outcome.blocks, &slack_client).await
+                    } else if sprint_command.tracks_preview_message(config) {
+                        sprint_command.post_preview_message(&outcome.channel_id, &outcome.blocks, &slack_client, &sprint_client).await
+                            .map(|message_state_changes| info!("Preview message changed: {:?}", message_state_changes))
+                    } else if sprint_command.threads_message(config) {
+                        sprint_command.post_threaded_message(&outcome.channel_id, &outcome.blocks, &active_sprint_context, &slack_client, &sprint_client).await
+                            .map(|message_state_changes| info!("Threaded message changed: {:?}", message_state_changes))
+                    } else {
+                        let fallback_text = fallback_summary_text(&localization, &ticket_summary, config);
+                        slack_client.send_teams_message_or_fallback(&outcome.channel_id, &outcome.blocks, &fallback_text, response_url).await
+                            .map(|ts| info!("Posted message: {:?}", ts))
+                    };
+                    if let Err(send_error) = send_result {
+                        if config.pause_on_unavailable_channel && sprint_command.pause_on_unavailable_channel(&send_error, &active_sprint_context, &notification_client).await? {
+                            return Ok(json!("Paused daily summaries for unavailable channel"));
+                        }
+                        return Err(send_error.into());
+                    }
+                }
+                if let Err(record_error) = sprint_command.record_post(&sprint_client, current_timestamp(), config).await {
+                    error!("Failed to record summary post: {:?}", record_error);
+                }
+                match sprint_command.archive_kickoff_preview(&slack_client, &sprint_client, &localization, config).await {
+                    Ok(preview_state_changes) => info!("Kickoff preview changed: {:?}", preview_state_changes),
+                    Err(preview_error) => error!("Failed to archive kickoff preview: {:?}", preview_error),
+                }
+                for (alert_channel_id, alert_blocks) in &outcome.alerts {
+                    if let Err(alert_error) = slack_client.send_teams_message(alert_channel_id, alert_blocks, None).await {
+                        error!("Failed to post alert to {}: {:?}", alert_channel_id, alert_error);
+                    }
+                }
                 Ok(json!("Processed command successfully"))
             },
             Err(e) => {
@@ -68,7 +222,7 @@ async fn function_handler(event: LambdaEvent<Value>) -> Result<Value, Error> {
                 Ok(json!(format!("Error: Failed to convert event to sprint event: {:?}", e)))
             }
         }
-    })().await;
+    })()).await;
 
     // If an error occurred, handle it by sending the error details to Teams
     match result {
@@ -79,10 +233,11 @@ async fn function_handler(event: LambdaEvent<Value>) -> Result<Value, Error> {
 
             // Attempt to send the error message to Teams
             let fetch_client = Client::new();
+            let slack_client = SlackClient::new(&fetch_client, &config.slack_oauth);
             let error_message = format!("Lambda function encountered an error: {:?}", e);
 
             if let Some(response_url) = event.payload["response_url"].as_str() {
-                if let Err(send_error) = fetch_client.send_teams_message(
+                if let Err(send_error) = slack_client.send_teams_message(
                     "C06RRR7NBAB",
                     &error_message,
                     Some(response_url.to_string())
@@ -104,8 +259,10 @@ async fn main() -> Result<(), Error> {
     tracing::init_default_subscriber();
 
     //from here, pass validation to function handler?
+    let config = Config::from_env()?;
+    let config = &config;
 
-    run(service_fn(function_handler)).await
+    run(service_fn(move |event| async move { function_handler(event, config).await })).await
 }
 
 // #[cfg(test)]
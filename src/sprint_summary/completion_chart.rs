@@ -0,0 +1,77 @@
+use anyhow::{Result, anyhow};
+use reqwest::Url;
+use serde_json::{json, Value};
+use crate::utils::localization::{Label, Localization};
+use crate::utils::slack_components::image_block;
+use super::sprint_records::CumulativeSprintContexts;
+
+//a Chart.js config rendered by an image service like https://quickchart.io/chart, since slack can only show images by url
+pub fn completion_chart_url(chart_service_url: &str, history: &CumulativeSprintContexts, current_sprint: Option<(&str, f64)>) -> Result<Url> {
+    let (labels, percentages): (Vec<&str>, Vec<f64>) = history.history.iter()
+        .map(|record| (record.name.as_str(), record.percent_complete))
+        .chain(current_sprint)
+        .map(|(name, percent_complete)| (name, (percent_complete * 100.0).round() / 100.0))
+        .unzip();
+
+    let chart = json!({
+        "type": "bar",
+        "data": {
+            "labels": labels,
+            "datasets": [{ "label": "% complete", "data": percentages }]
+        },
+        "options": { "scales": { "y": { "min": 0, "max": 100 } } }
+    });
+
+    Url::parse_with_params(chart_service_url, &[("c", chart.to_string())])
+        .map_err(|e| anyhow!("Invalid chart service url {}: {}", chart_service_url, e))
+}
+
+pub fn completion_chart_block(localization: &Localization, chart_service_url: &str, history: &CumulativeSprintContexts, current_sprint: Option<(&str, f64)>) -> Result<Value> {
+    let chart_url = completion_chart_url(chart_service_url, history, current_sprint)?;
+    let alt_text = match current_sprint {
+        Some((name, percent_complete)) => localization.format(Label::CompletionChartAltText, &[&name, &format!("{:.2}", percent_complete)]),
+        None => localization.text(Label::CompletionChartTitle),
+    };
+
+    Ok(image_block(chart_url.as_str(), &alt_text, Some(&localization.text(Label::CompletionChartTitle))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sprint_summary::sprint_records::CumulativeSprintContext;
+
+    fn history() -> CumulativeSprintContexts {
+        CumulativeSprintContexts {
+            history: vec![
+                CumulativeSprintContext { name: "Pascal".to_string(), percent_complete: 80.0, ..CumulativeSprintContext::default() },
+                CumulativeSprintContext { name: "Turing".to_string(), percent_complete: 66.666, ..CumulativeSprintContext::default() },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_completion_chart_plots_history_and_current_sprint() {
+        let chart_url = completion_chart_url("https://quickchart.io/chart", &history(), Some(("Lovelace", 50.0))).unwrap();
+
+        let (_, chart) = chart_url.query_pairs().find(|(key, _)| key == "c").unwrap();
+        let chart: Value = serde_json::from_str(&chart).unwrap();
+        assert_eq!(chart["data"]["labels"], json!(["Pascal", "Turing", "Lovelace"]));
+        assert_eq!(chart["data"]["datasets"][0]["data"], json!([80.0, 66.67, 50.0]));
+    }
+
+    #[test]
+    fn test_completion_chart_block_shape() {
+        let block = completion_chart_block(&Localization::default(), "https://quickchart.io/chart", &history(), Some(("Lovelace", 50.0))).unwrap();
+
+        assert_eq!(block["type"], "image");
+        assert!(block["image_url"].as_str().unwrap().starts_with("https://quickchart.io/chart?c="));
+        assert_eq!(block["alt_text"], "Sprint Lovelace is 50.00% complete");
+        assert_eq!(block["title"], json!({ "type": "plain_text", "text": "Completion by sprint", "emoji": true }));
+    }
+
+    #[test]
+    fn test_invalid_chart_service_url() {
+        assert!(completion_chart_url("not a url", &history(), None).is_err());
+    }
+}
@@ -1,74 +1,130 @@
 mod slack_events;
 
-use chrono::NaiveDate;
+use chrono::Duration;
+use chrono_tz::Tz;
 use lambda_runtime::LambdaEvent;
 use serde_json::Value;
+use crate::utils::config::Config;
+use crate::utils::date::{current_date, current_timestamp, days_between, normalize_date, parse_flexible_date, print_current_date};
 use crate::utils::http::HttpRequest;
 use anyhow::{anyhow, Error, Result};
 use super::{sprint_records::{ActiveSprintContext, CumulativeSprintContexts}, SprintCommand, SprintCommandParser};
 
 pub enum SprintEvents {
-    MessageTrigger{command: String, args: Vec<String>, channel_id: String, response_url: Option<String>},
+    MessageTrigger{command: String, args: Vec<String>, channel_id: String, response_url: Option<String>, trigger_id: Option<String>, user_id: Option<String>},
     ScheduledTrigger,
 }
 
+impl SprintEvents {
+    pub fn trigger_id(&self) -> Option<&str> {
+        match self {
+            SprintEvents::MessageTrigger { trigger_id, .. } => trigger_id.as_deref(),
+            SprintEvents::ScheduledTrigger => None,
+        }
+    }
+
+    //whoever ran the slash command, button clicks and scheduled triggers don't carry one
+    pub fn user_id(&self) -> Option<&str> {
+        match self {
+            SprintEvents::MessageTrigger { user_id, .. } => user_id.as_deref(),
+            SprintEvents::ScheduledTrigger => None,
+        }
+    }
+}
+
 impl SprintCommandParser for SprintEvents {
     async fn try_into_sprint_command(
         &self, 
         active_sprint_context: &Option<ActiveSprintContext>,
         cumulative_sprint_contexts: &CumulativeSprintContexts,
+        config: &Config,
     ) -> Result<SprintCommand> {
         match active_sprint_context {
             Some(active_sprint_record) => {
                 match self {
-                    SprintEvents::MessageTrigger { command, args: _, channel_id: _,  response_url: _ } => {
+                    SprintEvents::MessageTrigger { command, args, channel_id: _,  response_url: _, trigger_id: _, user_id: _ } => {
                         match command.as_str() {
-                            "/sprint-kickoff" | "/sprint-kickoff-confirm" => {
+                            "/sprint-kickoff" | "/sprint-kickoff-confirm" | "/sprint-preview" => {
                                 Err(anyhow!("Sprint {} already in progress", active_sprint_record.name))
                             },
                             "/sprint-cancel" => Ok(SprintCommand::SprintCancel),
                             "/sprint-end" => Ok(SprintCommand::SprintEnd),
-                            "/sprint-check-in" => Ok(SprintCommand::SprintCheckIn),
+                            //reviewing by hand archives the sprint like the scheduled review, without the "ended early" header
+                            "/sprint-review" => Ok(SprintCommand::SprintReview),
+                            "/sprint-check-in" => Ok(SprintCommand::SprintCheckIn { force: args.first().is_some_and(|arg| arg == "force") }),
+                            "/sprint-review-preview" => Ok(SprintCommand::SprintReviewPreview),
+                            "/sprint-status" => Ok(SprintCommand::SprintStatus),
+                            "/daily-trigger" => Ok(scheduled_sprint_command(active_sprint_record, config.summary_timezone)),
+                            "/sprint-defer" => {
+                                match args.first() {
+                                    Some(ticket_id) => Ok(SprintCommand::SprintDefer { ticket_id: ticket_id.clone() }),
+                                    None => Err(anyhow!("A ticket id is required to defer a ticket")),
+                                }
+                            },
+                            "/sprint-map-user" => parse_sprint_map_user(args),
+                            "/sprint-hygiene" => Ok(SprintCommand::SprintHygiene),
+                            "/sprint-config" => Ok(SprintCommand::SprintConfig),
+                            "/sprint-backfill" => Ok(SprintCommand::SprintBackfill),
+                            "/sprint-velocity" => parse_sprint_velocity(args, config),
+                            "/sprint-extend" => parse_sprint_extend(args, active_sprint_record),
                             _ => Err(anyhow!("Invalid command")),
                         }
                     },
-                    SprintEvents::ScheduledTrigger => {
-                        if active_sprint_record.days_until_end() <= 0 {
-                            Ok(SprintCommand::SprintReview)
-                        } else {
-                            Ok(SprintCommand::DailySummary)
-                        }
+                    //holidays push the daily summary, or the review, to the next scheduled trigger
+                    SprintEvents::ScheduledTrigger if config.holidays.contains(&current_date(config.summary_timezone)) => {
+                        Err(anyhow!("Skipping scheduled summary on a holiday"))
                     },
+                    SprintEvents::ScheduledTrigger => Ok(scheduled_sprint_command(active_sprint_record, config.summary_timezone)),
                 }
             },
             None => {
                 match self {
-                    SprintEvents::MessageTrigger { command, args, channel_id, response_url: _ } => {
+                    SprintEvents::MessageTrigger { command, args, channel_id, response_url: _, trigger_id, user_id: _ } => {
                         match command.as_str() {
-                            "/sprint-cancel" | "/sprint-end" | "/sprint-check-in" => {
+                            "/sprint-cancel" | "/sprint-end" | "/sprint-review" | "/sprint-check-in" | "/daily-trigger" | "/sprint-defer" | "/sprint-review-preview" | "/sprint-extend" | "/sprint-status" => {
                                 Err(anyhow!("No sprint in progress"))
                             },
-                            "/sprint-kickoff" | "/sprint-kickoff-confirm" => {
+                            "/sprint-map-user" => parse_sprint_map_user(args),
+                            "/sprint-hygiene" => Ok(SprintCommand::SprintHygiene),
+                            "/sprint-config" => Ok(SprintCommand::SprintConfig),
+                            "/sprint-backfill" => Ok(SprintCommand::SprintBackfill),
+                            "/sprint-velocity" => parse_sprint_velocity(args, config),
+                            "/sprint-kickoff" | "/sprint-kickoff-confirm" | "/sprint-preview" => {
                                 if args.len() < 2 {
                                     return Err(anyhow!("Text field does not contain enough parts"));
                                 }
                                 
-                                let _ = NaiveDate::parse_from_str(&args[0], "%m/%d/%y")
-                                    .map_err(|e| format!("Failed to parse date: {}", e));
+                                let end_date = normalize_date(&args[0])?;
+                                let (sprint_name, options) = parse_sprint_name(&args[1..]);
+                                if sprint_name.is_empty() {
+                                    return Err(anyhow!("Text field does not contain enough parts"));
+                                }
+
+                                let (options, goal_text) = parse_goal_text(options);
+                                let capacity = parse_capacity(options)?;
+                                let theme_emoji = parse_theme_emoji(options)?;
 
-                                if cumulative_sprint_contexts.was_sprint_name_used(&args[1]) {
-                                    Err(anyhow!("Sprint name {} was already used", &args[1]))
+                                if cumulative_sprint_contexts.was_sprint_name_used(&sprint_name, config.sprint_name_matching) {
+                                    Err(anyhow!("Sprint name {} was already used", sprint_name))
                                 } else if command.as_str() == "/sprint-kickoff-confirm" {
                                     Ok(SprintCommand::SprintKickoff {
-                                        end_date: args[0].clone(),
-                                        sprint_name: args[1].clone(),
-                                        channel_id: channel_id.clone()
+                                        end_date,
+                                        sprint_name: sprint_name.clone(),
+                                        channel_id: channel_id.clone(),
+                                        capacity,
+                                        preview_token: parse_preview_token(options),
+                                        theme_emoji,
+                                        goal_text,
                                     })
                                 } else {
                                     Ok(SprintCommand::SprintPreview {
-                                        end_date: args[0].clone(),
-                                        sprint_name: args[1].clone(),
-                                        channel_id: channel_id.clone()
+                                        end_date,
+                                        sprint_name: sprint_name.clone(),
+                                        channel_id: channel_id.clone(),
+                                        capacity,
+                                        preview_token: trigger_id.clone(),
+                                        theme_emoji,
+                                        goal_text,
                                     })
                                 }
                             },
@@ -82,16 +138,138 @@ impl SprintCommandParser for SprintEvents {
     }
 }
 
+//compared as signed days so a review skipped on its end date still runs the day after
+fn scheduled_sprint_command(active_sprint_record: &ActiveSprintContext, timezone: Tz) -> SprintCommand {
+    if days_between(&print_current_date(timezone), &active_sprint_record.end_date).expect("Days until end should be parseable") <= 0 {
+        SprintCommand::SprintReview
+    } else {
+        SprintCommand::DailySummary
+    }
+}
+
+//+N pushes the current end date back by N days, anything else is taken as the new end date
+fn parse_sprint_extend(args: &[String], active_sprint_record: &ActiveSprintContext) -> Result<SprintCommand> {
+    let arg = args.first()
+        .ok_or(anyhow!("Use /sprint-extend +N to push the end date back by N days, or /sprint-extend mm/dd/yy to set it"))?;
+
+    let new_end_date = match arg.strip_prefix('+') {
+        Some(days) => {
+            let days = days.parse::<i64>()
+                .ok()
+                .filter(|days| *days > 0)
+                .ok_or(anyhow!("Sprint extension {} should be a positive number of days", days))?;
+
+            parse_flexible_date(&active_sprint_record.end_date)?.checked_add_signed(Duration::days(days))
+                .ok_or(anyhow!("Sprint extension {} is too far in the future", days))?
+        },
+        None => parse_flexible_date(arg)?,
+    };
+
+    if new_end_date < parse_flexible_date(&active_sprint_record.start_date)? {
+        return Err(anyhow!("Sprint {} can't end before it started on {}", active_sprint_record.name, active_sprint_record.start_date));
+    }
+
+    Ok(SprintCommand::SprintExtend { new_end_date: new_end_date.format("%m/%d/%y").to_string() })
+}
+
+//without a count the configured velocity window is used
+fn parse_sprint_velocity(args: &[String], config: &Config) -> Result<SprintCommand> {
+    let sprint_count = match args.first() {
+        Some(count) => count.parse::<usize>().ok().filter(|count| *count > 0).ok_or(anyhow!("{} is not a number of sprints, expected something like /sprint-velocity 5", count))?,
+        None => config.velocity_window,
+    };
+
+    Ok(SprintCommand::SprintVelocity { sprint_count })
+}
+
+fn parse_sprint_map_user(args: &[String]) -> Result<SprintCommand> {
+    match args {
+        [trello_id, mention] => {
+            let slack_user_id = parse_slack_mention(mention).ok_or(anyhow!("{} is not a Slack user mention, expected something like <@U123ABC>", mention))?;
+            Ok(SprintCommand::SprintMapUser { trello_id: trello_id.clone(), slack_user_id })
+        },
+        _ => Err(anyhow!("A Trello member id and a Slack user mention are required to map a user")),
+    }
+}
+
+//slack escapes mentions in command text as <@U123ABC> or <@U123ABC|name>
+fn parse_slack_mention(mention: &str) -> Option<String> {
+    let user_id = mention.strip_prefix("<@")?.strip_suffix('>')?.split('|').next()?;
+    let is_user_id = user_id.starts_with(['U', 'W']) && user_id.len() > 1 && user_id.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+
+    is_user_id.then(|| user_id.to_string())
+}
+
+const SPRINT_OPTION_PREFIXES: [&str; 4] = ["capacity=", "theme=", "preview=", "goal="];
+
+//the sprint name runs from after the end date up to the first option, so it can span several words
+fn parse_sprint_name(args: &[String]) -> (String, &[String]) {
+    let name_length = args.iter()
+        .position(|arg| SPRINT_OPTION_PREFIXES.iter().any(|prefix| arg.starts_with(prefix)))
+        .unwrap_or(args.len());
+
+    (args[..name_length].join(" "), &args[name_length..])
+}
+
+//the goal is free text, so everything after goal= belongs to it and only the options before it are parsed
+fn parse_goal_text(options: &[String]) -> (&[String], Option<String>) {
+    match options.iter().position(|option| option.starts_with("goal=")) {
+        Some(goal_index) => {
+            let goal_words = options[goal_index..].join(" ");
+            let goal_text = goal_words.trim_start_matches("goal=").trim().to_string();
+            (&options[..goal_index], Some(goal_text).filter(|goal_text| !goal_text.is_empty()))
+        },
+        None => (options, None),
+    }
+}
+
+fn parse_capacity(options: &[String]) -> Result<Option<u32>> {
+    options.iter()
+        .find_map(|option| option.strip_prefix("capacity="))
+        .map(|capacity| capacity.parse::<u32>().map_err(|e| anyhow!("Failed to parse capacity {}: {}", capacity, e)))
+        .transpose()
+}
+
+//a :shortcode: or a single emoji, which can span several chars with skin tones or joiners
+fn parse_theme_emoji(options: &[String]) -> Result<Option<String>> {
+    let Some(theme_emoji) = options.iter().find_map(|option| option.strip_prefix("theme=")) else {
+        return Ok(None);
+    };
+
+    let is_shortcode = theme_emoji.len() > 2
+        && theme_emoji.starts_with(':')
+        && theme_emoji.ends_with(':')
+        && theme_emoji[1..theme_emoji.len() - 1].chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "_+-".contains(c));
+    let is_emoji = !theme_emoji.is_empty()
+        && theme_emoji.chars().count() <= 8
+        && !theme_emoji.chars().next().is_some_and(|c| c.is_ascii())
+        && !theme_emoji.chars().any(|c| c.is_alphanumeric() || c.is_whitespace());
+
+    if is_shortcode || is_emoji {
+        Ok(Some(theme_emoji.to_string()))
+    } else {
+        Err(anyhow!("Theme {} should be a single emoji like 🦄 or :unicorn_face:", theme_emoji))
+    }
+}
+
+fn parse_preview_token(options: &[String]) -> Option<String> {
+    options.iter()
+        .find_map(|option| option.strip_prefix("preview="))
+        .map(str::to_string)
+}
+
 pub trait MapToSprintEvents {
-    fn try_into_sprint_events(&self) -> Result<SprintEvents, Error>;
+    fn try_into_sprint_events(&self, config: &Config) -> Result<SprintEvents, Error>;
 }
 
 impl MapToSprintEvents for LambdaEvent<Value> {
-    fn try_into_sprint_events(&self) -> Result<SprintEvents, Error> {
+    fn try_into_sprint_events(&self, config: &Config) -> Result<SprintEvents, Error> {
         let request_result: Result<HttpRequest, Error> = self.try_into();
 
         match request_result {
             Ok(request) => {
+                request.verify_slack_request(&config.slack_signing_secret, current_timestamp())?;
+                slack_events::verify_slack_app(&request, &config.allowed_slack_app_ids)?;
                 Ok((&request).try_into().expect("should convert into SprintEvents"))
             },
             Err(_) => {
@@ -103,7 +281,8 @@ impl MapToSprintEvents for LambdaEvent<Value> {
 
 #[cfg(test)]
 mod sprint_event_tests {
-    use crate::{sprint_summary::sprint_records::CumulativeSprintContext, utils::date::print_current_date};
+    use crate::sprint_summary::sprint_records::{CumulativeSprintContext, SprintNameMatching};
+    use std::collections::BTreeSet;
     use super::*;
 
     #[tokio::test]
@@ -123,9 +302,11 @@ mod sprint_event_tests {
             args: vec!["01/20/22".to_string(), "New Sprint".to_string()],
             channel_id: "C123456".to_string(),
             response_url: None,
+            trigger_id: None,
+            user_id: None,
         };
 
-        let result = event.try_into_sprint_command(&mock_client, &cumulative_contexts).await;
+        let result = event.try_into_sprint_command(&mock_client, &cumulative_contexts, &Config::default()).await;
         assert!(result.is_err());
     }
 
@@ -146,10 +327,12 @@ mod sprint_event_tests {
             args: vec![],
             channel_id: "C123456".to_string(),
             response_url: None,
+            trigger_id: None,
+            user_id: None,
         };
 
-        let result = event.try_into_sprint_command(&mock_client, &cumulative_contexts).await;
-        assert!(matches!(result, Ok(SprintCommand::SprintCheckIn)));
+        let result = event.try_into_sprint_command(&mock_client, &cumulative_contexts, &Config::default()).await;
+        assert!(matches!(result, Ok(SprintCommand::SprintCheckIn { force: false })));
     }
 
     #[tokio::test]
@@ -166,9 +349,11 @@ mod sprint_event_tests {
             args: vec!["02/01/22".to_string(), "Sprint 1".to_string()],
             channel_id: "C789123".to_string(),
             response_url: None,
+            trigger_id: None,
+            user_id: None,
         };
 
-        let result = event.try_into_sprint_command(&None, &cumulative_contexts).await;
+        let result = event.try_into_sprint_command(&None, &cumulative_contexts, &Config::default()).await;
         assert!(result.is_err());
         match result {
             Err(e) => assert_eq!(e.to_string(), "Sprint name Sprint 1 was already used", "Unexpected error message: {}", e),
@@ -185,11 +370,164 @@ mod sprint_event_tests {
             args: vec!["02/01/22".to_string(), "New Sprint".to_string()],
             channel_id: "C789123".to_string(),
             response_url: None,
+            trigger_id: None,
+            user_id: None,
         };
 
-        let result = event.try_into_sprint_command(&None, &cumulative_contexts).await;
+        let result = event.try_into_sprint_command(&None, &cumulative_contexts, &Config::default()).await;
         assert!(matches!(result, Ok(SprintCommand::SprintKickoff { .. })));
     }
+
+    async fn kickoff_with_end_date(end_date: &str) -> Result<SprintCommand> {
+        let event = SprintEvents::MessageTrigger {
+            command: "/sprint-kickoff".to_string(),
+            args: vec![end_date.to_string(), "New Sprint".to_string()],
+            channel_id: "C789123".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        event.try_into_sprint_command(&None, &CumulativeSprintContexts { history: vec![] }, &Config::default()).await
+    }
+
+    #[tokio::test]
+    async fn test_sprint_kickoff_with_malformed_date() {
+        let result = kickoff_with_end_date("banana").await;
+        assert!(result.unwrap_err().to_string().starts_with("Failed to parse date:"));
+    }
+
+    #[tokio::test]
+    async fn test_sprint_kickoff_with_empty_date() {
+        let result = kickoff_with_end_date("").await;
+        assert!(result.unwrap_err().to_string().starts_with("Failed to parse date:"));
+    }
+
+    #[tokio::test]
+    async fn test_sprint_kickoff_with_valid_date() {
+        let result = kickoff_with_end_date("02/01/22").await;
+        assert!(matches!(result, Ok(SprintCommand::SprintPreview { ref end_date, .. }) if end_date == "02/01/22"));
+    }
+
+    #[tokio::test]
+    async fn test_sprint_kickoff_normalizes_four_digit_year() {
+        let result = kickoff_with_end_date("02/01/2022").await;
+        assert!(matches!(result, Ok(SprintCommand::SprintPreview { ref end_date, .. }) if end_date == "02/01/22"));
+    }
+
+    async fn kickoff_sprint_name(args: &[&str]) -> String {
+        let event = SprintEvents::MessageTrigger {
+            command: "/sprint-kickoff-confirm".to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            channel_id: "C789123".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        match event.try_into_sprint_command(&None, &CumulativeSprintContexts { history: vec![] }, &Config::default()).await {
+            Ok(SprintCommand::SprintKickoff { sprint_name, .. }) => sprint_name,
+            result => panic!("Expected a sprint kickoff but got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sprint_kickoff_one_word_name() {
+        assert_eq!(kickoff_sprint_name(&["09/20/25", "Pascal", "capacity=10"]).await, "Pascal");
+    }
+
+    #[tokio::test]
+    async fn test_sprint_kickoff_three_word_name() {
+        assert_eq!(kickoff_sprint_name(&["09/20/2025", "Q3", "Payments", "Push", "theme=🦄", "goal=Ship it"]).await, "Q3 Payments Push");
+    }
+
+    #[tokio::test]
+    async fn test_sprint_kickoff_name_with_emoji() {
+        assert_eq!(kickoff_sprint_name(&["09/20/25", "Rocket", "🚀", "Sprint"]).await, "Rocket 🚀 Sprint");
+    }
+
+    #[tokio::test]
+    async fn test_sprint_kickoff_options_without_name() {
+        let event = SprintEvents::MessageTrigger {
+            command: "/sprint-kickoff".to_string(),
+            args: vec!["09/20/25".to_string(), "capacity=10".to_string()],
+            channel_id: "C789123".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        let result = event.try_into_sprint_command(&None, &CumulativeSprintContexts { history: vec![] }, &Config::default()).await;
+        assert_eq!(result.unwrap_err().to_string(), "Text field does not contain enough parts");
+    }
+
+    #[tokio::test]
+    async fn test_sprint_preview_command_without_active_sprint() {
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+
+        let event = SprintEvents::MessageTrigger {
+            command: "/sprint-preview".to_string(),
+            args: vec!["02/01/22".to_string(), "New Sprint".to_string()],
+            channel_id: "C789123".to_string(),
+            response_url: None,
+            trigger_id: Some("trigger123".to_string()),
+            user_id: None,
+        };
+
+        let result = event.try_into_sprint_command(&None, &cumulative_contexts, &Config::default()).await;
+        assert!(matches!(result, Ok(SprintCommand::SprintPreview { ref sprint_name, .. }) if sprint_name == "New Sprint"));
+    }
+    
+    #[tokio::test]
+    async fn test_sprint_kickoff_with_capacity() {
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+
+        let event = SprintEvents::MessageTrigger {
+            command: "/sprint-kickoff-confirm".to_string(),
+            args: vec!["02/01/22".to_string(), "New Sprint".to_string(), "capacity=12".to_string()],
+            channel_id: "C789123".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        let result = event.try_into_sprint_command(&None, &cumulative_contexts, &Config::default()).await;
+        assert!(matches!(result, Ok(SprintCommand::SprintKickoff { capacity: Some(12), .. })));
+    }
+
+    #[tokio::test]
+    async fn test_sprint_kickoff_confirm_with_preview_token() {
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+
+        let event = SprintEvents::MessageTrigger {
+            command: "/sprint-kickoff-confirm".to_string(),
+            args: vec!["02/01/22".to_string(), "New Sprint".to_string(), "capacity=12".to_string(), "preview=trigger123".to_string()],
+            channel_id: "C789123".to_string(),
+            response_url: None,
+            trigger_id: Some("trigger456".to_string()),
+            user_id: None,
+        };
+
+        let result = event.try_into_sprint_command(&None, &cumulative_contexts, &Config::default()).await;
+        assert!(matches!(result, Ok(SprintCommand::SprintKickoff { capacity: Some(12), preview_token: Some(token), .. }) if token == "trigger123"));
+    }
+
+    #[tokio::test]
+    async fn test_sprint_kickoff_with_invalid_capacity() {
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+
+        let event = SprintEvents::MessageTrigger {
+            command: "/sprint-kickoff".to_string(),
+            args: vec!["02/01/22".to_string(), "New Sprint".to_string(), "capacity=lots".to_string()],
+            channel_id: "C789123".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        let result = event.try_into_sprint_command(&None, &cumulative_contexts, &Config::default()).await;
+        assert!(result.is_err(), "Kickoff should fail with a non-numeric capacity");
+    }
     
     #[tokio::test]
     async fn test_daily_summary_with_no_active_sprint() {
@@ -198,7 +536,7 @@ mod sprint_event_tests {
 
         let event = SprintEvents::ScheduledTrigger;
 
-        let result = event.try_into_sprint_command(&mock_client, &cumulative_contexts).await;
+        let result = event.try_into_sprint_command(&mock_client, &cumulative_contexts, &Config::default()).await;
         assert!(result.is_err(), "Daily summary should fail without an active sprint");
     }
 
@@ -207,7 +545,7 @@ mod sprint_event_tests {
         let active_context = ActiveSprintContext {
             name: "Sprint 1".to_string(),
             start_date: "01/01/22".to_string(),
-            end_date: print_current_date(),
+            end_date: print_current_date(Config::default().summary_timezone),
             channel_id: "C123456".to_string(),
             ..ActiveSprintContext::default()
         };
@@ -216,7 +554,7 @@ mod sprint_event_tests {
 
         let event = SprintEvents::ScheduledTrigger;
 
-        let result = event.try_into_sprint_command(&mock_client, &cumulative_contexts).await;
+        let result = event.try_into_sprint_command(&mock_client, &cumulative_contexts, &Config::default()).await;
         assert!(result.is_ok(), "Scheduled trigger failed");
         assert_eq!(result.unwrap(), SprintCommand::SprintReview, "Sprint review should be triggered on the last day");
     }
@@ -226,7 +564,7 @@ mod sprint_event_tests {
         let active_context = ActiveSprintContext {
             name: "Sprint 1".to_string(),
             start_date: "01/01/22".to_string(),
-            end_date: "12/31/22".to_string(),
+            end_date: (current_date(Config::default().summary_timezone) + Duration::days(30)).format("%m/%d/%y").to_string(),
             channel_id: "C123456".to_string(),
             ..ActiveSprintContext::default()
         };
@@ -235,7 +573,7 @@ mod sprint_event_tests {
 
         let event = SprintEvents::ScheduledTrigger;
 
-        let result = event.try_into_sprint_command(&mock_client, &cumulative_contexts).await;
+        let result = event.try_into_sprint_command(&mock_client, &cumulative_contexts, &Config::default()).await;
         assert!(result.is_ok(), "Scheduled trigger failed");
         assert_eq!(result.unwrap(), SprintCommand::DailySummary, "Daily summary should be generated for active sprints not due for review");
     }
@@ -256,9 +594,11 @@ mod sprint_event_tests {
             args: vec![],
             channel_id: "C123456".to_string(),
             response_url: None,
+            trigger_id: None,
+            user_id: None,
         };
 
-        let result = event.try_into_sprint_command(&active_context, &cumulative_contexts).await;
+        let result = event.try_into_sprint_command(&active_context, &cumulative_contexts, &Config::default()).await;
         assert!(result.is_err(), "Unrecognized commands should return an error");
     }
 
@@ -272,12 +612,68 @@ mod sprint_event_tests {
             args: vec![],
             channel_id: "C789123".to_string(),
             response_url: None,
+            trigger_id: None,
+            user_id: None,
         };
 
-        let result = event.try_into_sprint_command(&mock_client, &cumulative_contexts).await;
+        let result = event.try_into_sprint_command(&mock_client, &cumulative_contexts, &Config::default()).await;
         assert!(result.is_err(), "Ending a sprint should fail without an active sprint");
     }
 
+    #[tokio::test]
+    async fn test_manual_sprint_review() {
+        let active_context = Some(ActiveSprintContext::default());
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+
+        let event = SprintEvents::MessageTrigger {
+            command: "/sprint-review".to_string(),
+            args: vec![],
+            channel_id: "C789123".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        assert_eq!(event.try_into_sprint_command(&active_context, &cumulative_contexts, &Config::default()).await.unwrap(), SprintCommand::SprintReview);
+        assert!(event.try_into_sprint_command(&None, &cumulative_contexts, &Config::default()).await.is_err(), "Reviewing a sprint should fail without an active sprint");
+    }
+
+    #[tokio::test]
+    async fn test_sprint_status_requires_active_sprint() {
+        let active_context = Some(ActiveSprintContext::default());
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+
+        let event = SprintEvents::MessageTrigger {
+            command: "/sprint-status".to_string(),
+            args: vec![],
+            channel_id: "C789123".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        assert_eq!(event.try_into_sprint_command(&active_context, &cumulative_contexts, &Config::default()).await.unwrap(), SprintCommand::SprintStatus);
+        assert!(event.try_into_sprint_command(&None, &cumulative_contexts, &Config::default()).await.is_err(), "Sprint status should fail without an active sprint");
+    }
+
+    #[tokio::test]
+    async fn test_sprint_velocity_window() {
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+        let velocity_event = |args: &[&str]| SprintEvents::MessageTrigger {
+            command: "/sprint-velocity".to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            channel_id: "C789123".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        assert_eq!(velocity_event(&["5"]).try_into_sprint_command(&Some(ActiveSprintContext::default()), &cumulative_contexts, &Config::default()).await.unwrap(), SprintCommand::SprintVelocity { sprint_count: 5 });
+        assert_eq!(velocity_event(&[]).try_into_sprint_command(&None, &cumulative_contexts, &Config::default()).await.unwrap(), SprintCommand::SprintVelocity { sprint_count: 3 });
+        assert!(velocity_event(&["0"]).try_into_sprint_command(&None, &cumulative_contexts, &Config::default()).await.is_err());
+        assert!(velocity_event(&["many"]).try_into_sprint_command(&None, &cumulative_contexts, &Config::default()).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_sprint_checkin_without_active_sprint() {
         let mock_client = None; // No active sprint
@@ -288,9 +684,275 @@ mod sprint_event_tests {
             args: vec![],
             channel_id: "C789123".to_string(),
             response_url: None,
+            trigger_id: None,
+            user_id: None,
         };
 
-        let result = event.try_into_sprint_command(&mock_client, &cumulative_contexts).await;
+        let result = event.try_into_sprint_command(&mock_client, &cumulative_contexts, &Config::default()).await;
         assert!(result.is_err(), "Check-in should fail without an active sprint");
     }
+
+    #[tokio::test]
+    async fn test_daily_trigger_command_with_active_sprint() {
+        let active_context = Some(ActiveSprintContext {
+            name: "Sprint 1".to_string(),
+            start_date: "01/01/22".to_string(),
+            end_date: "12/31/60".to_string(),
+            channel_id: "C123456".to_string(),
+            ..ActiveSprintContext::default()
+        });
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+
+        let event = SprintEvents::MessageTrigger {
+            command: "/daily-trigger".to_string(),
+            args: vec![],
+            channel_id: "C123456".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        let result = event.try_into_sprint_command(&active_context, &cumulative_contexts, &Config::default()).await;
+        assert_eq!(result.unwrap(), SprintCommand::DailySummary, "A manual daily trigger should behave like the scheduled trigger");
+    }
+
+    #[tokio::test]
+    async fn test_daily_trigger_command_without_active_sprint() {
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+
+        let event = SprintEvents::MessageTrigger {
+            command: "/daily-trigger".to_string(),
+            args: vec![],
+            channel_id: "C123456".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        let result = event.try_into_sprint_command(&None, &cumulative_contexts, &Config::default()).await;
+        assert!(result.is_err(), "Daily trigger should fail without an active sprint");
+    }
+
+    #[tokio::test]
+    async fn test_sprint_defer_with_active_sprint() {
+        let active_context = Some(ActiveSprintContext::default());
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+
+        let event = SprintEvents::MessageTrigger {
+            command: "/sprint-defer".to_string(),
+            args: vec!["abc123".to_string()],
+            channel_id: "C123456".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        let result = event.try_into_sprint_command(&active_context, &cumulative_contexts, &Config::default()).await;
+        assert_eq!(result.unwrap(), SprintCommand::SprintDefer { ticket_id: "abc123".to_string() });
+    }
+
+    #[tokio::test]
+    async fn test_sprint_defer_without_ticket_id() {
+        let active_context = Some(ActiveSprintContext::default());
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+
+        let event = SprintEvents::MessageTrigger {
+            command: "/sprint-defer".to_string(),
+            args: vec![],
+            channel_id: "C123456".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        let result = event.try_into_sprint_command(&active_context, &cumulative_contexts, &Config::default()).await;
+        assert!(result.is_err(), "Deferring should fail without a ticket id");
+    }
+
+    #[tokio::test]
+    async fn test_sprint_review_preview_with_active_sprint() {
+        let active_context = Some(ActiveSprintContext::default());
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+
+        let event = SprintEvents::MessageTrigger {
+            command: "/sprint-review-preview".to_string(),
+            args: vec![],
+            channel_id: "C123456".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        let result = event.try_into_sprint_command(&active_context, &cumulative_contexts, &Config::default()).await;
+        assert_eq!(result.unwrap(), SprintCommand::SprintReviewPreview);
+    }
+
+    #[tokio::test]
+    async fn test_sprint_map_user_without_active_sprint() {
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+
+        let event = SprintEvents::MessageTrigger {
+            command: "/sprint-map-user".to_string(),
+            args: vec!["trello_user3".to_string(), "<@U0123ABC|jay>".to_string()],
+            channel_id: "C123456".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        let result = event.try_into_sprint_command(&None, &cumulative_contexts, &Config::default()).await;
+        assert_eq!(result.unwrap(), SprintCommand::SprintMapUser { trello_id: "trello_user3".to_string(), slack_user_id: "U0123ABC".to_string() });
+    }
+
+    #[tokio::test]
+    async fn test_sprint_map_user_rejects_malformed_mention() {
+        let active_context = Some(ActiveSprintContext::default());
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+
+        for mention in ["@jay", "U0123ABC", "<@jay>", "<#C0123ABC>"] {
+            let event = SprintEvents::MessageTrigger {
+                command: "/sprint-map-user".to_string(),
+                args: vec!["trello_user3".to_string(), mention.to_string()],
+                channel_id: "C123456".to_string(),
+                response_url: None,
+                trigger_id: None,
+                user_id: None,
+            };
+
+            let result = event.try_into_sprint_command(&active_context, &cumulative_contexts, &Config::default()).await;
+            assert!(result.is_err(), "{} should not be accepted as a Slack mention", mention);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sprint_kickoff_with_used_name_in_different_case() {
+        let cumulative_contexts = CumulativeSprintContexts {
+            history: vec![CumulativeSprintContext { name: "Pascal".to_string(), ..CumulativeSprintContext::default() }],
+        };
+
+        let event = SprintEvents::MessageTrigger {
+            command: "/sprint-kickoff-confirm".to_string(),
+            args: vec!["02/01/22".to_string(), "pascal".to_string()],
+            channel_id: "C789123".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        assert!(event.try_into_sprint_command(&None, &cumulative_contexts, &Config::default()).await.is_ok());
+
+        let config = Config { sprint_name_matching: SprintNameMatching::CaseInsensitive, ..Config::default() };
+        let result = event.try_into_sprint_command(&None, &cumulative_contexts, &config).await;
+        assert_eq!(result.unwrap_err().to_string(), "Sprint name pascal was already used");
+    }
+
+    #[tokio::test]
+    async fn test_sprint_kickoff_with_theme_emoji() {
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+        let kickoff = |theme: &str| SprintEvents::MessageTrigger {
+            command: "/sprint-kickoff-confirm".to_string(),
+            args: vec!["02/01/22".to_string(), "Unicorn".to_string(), format!("theme={}", theme)],
+            channel_id: "C789123".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        for theme in ["🦄", ":unicorn_face:", "👍🏽"] {
+            let result = kickoff(theme).try_into_sprint_command(&None, &cumulative_contexts, &Config::default()).await;
+            assert!(matches!(result, Ok(SprintCommand::SprintKickoff { theme_emoji: Some(theme_emoji), .. }) if theme_emoji == theme));
+        }
+
+        for theme in ["unicorn", "🦄🦄🦄🦄🦄🦄🦄🦄🦄", ":two words:", ""] {
+            let result = kickoff(theme).try_into_sprint_command(&None, &cumulative_contexts, &Config::default()).await;
+            assert!(result.is_err(), "{} should not be accepted as a theme emoji", theme);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sprint_kickoff_with_goal_text() {
+        let event = SprintEvents::MessageTrigger {
+            command: "/sprint-kickoff-confirm".to_string(),
+            args: ["02/01/22", "Onboarding", "capacity=12", "goal=Ship", "the", "new", "capacity=flow"].iter().map(|arg| arg.to_string()).collect(),
+            channel_id: "C789123".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        let result = event.try_into_sprint_command(&None, &CumulativeSprintContexts { history: vec![] }, &Config::default()).await.unwrap();
+        assert!(matches!(result, SprintCommand::SprintKickoff { capacity: Some(12), goal_text: Some(goal_text), .. } if goal_text == "Ship the new capacity=flow"));
+    }
+
+    #[tokio::test]
+    async fn test_sprint_extend_relative_to_end_date() {
+        let active_context = Some(ActiveSprintContext { end_date: "02/27/24".to_string(), ..ActiveSprintContext::default() });
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+        let extend = |arg: &str| SprintEvents::MessageTrigger {
+            command: "/sprint-extend".to_string(),
+            args: vec![arg.to_string()],
+            channel_id: "C123456".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        let result = extend("+3").try_into_sprint_command(&active_context, &cumulative_contexts, &Config::default()).await;
+        assert_eq!(result.unwrap(), SprintCommand::SprintExtend { new_end_date: "03/01/24".to_string() });
+
+        for arg in ["+0", "+-2", "3", "+three"] {
+            let result = extend(arg).try_into_sprint_command(&active_context, &cumulative_contexts, &Config::default()).await;
+            assert!(result.is_err(), "{} should not be accepted as an extension", arg);
+        }
+
+        let result = extend("+3").try_into_sprint_command(&None, &cumulative_contexts, &Config::default()).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sprint_extend_to_absolute_date() {
+        let active_context = Some(ActiveSprintContext { start_date: "02/20/24".to_string(), end_date: "02/27/24".to_string(), ..ActiveSprintContext::default() });
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+        let extend = |arg: &str| SprintEvents::MessageTrigger {
+            command: "/sprint-extend".to_string(),
+            args: vec![arg.to_string()],
+            channel_id: "C123456".to_string(),
+            response_url: None,
+            trigger_id: None,
+            user_id: None,
+        };
+
+        let result = extend("03/05/2024").try_into_sprint_command(&active_context, &cumulative_contexts, &Config::default()).await;
+        assert_eq!(result.unwrap(), SprintCommand::SprintExtend { new_end_date: "03/05/24".to_string() });
+
+        let result = extend("02/19/24").try_into_sprint_command(&active_context, &cumulative_contexts, &Config::default()).await;
+        assert_eq!(result.unwrap_err().to_string(), "Sprint Sprint 1 can't end before it started on 02/20/24");
+    }
+
+    #[tokio::test]
+    async fn test_daily_summary_suppressed_on_holiday() {
+        let today = current_date(Config::default().summary_timezone);
+        let active_context = Some(ActiveSprintContext {
+            start_date: (today - Duration::days(3)).format("%m/%d/%y").to_string(),
+            end_date: (today + Duration::days(5)).format("%m/%d/%y").to_string(),
+            ..ActiveSprintContext::default()
+        });
+        let cumulative_contexts = CumulativeSprintContexts { history: vec![] };
+        let holiday_config = Config { holidays: BTreeSet::from([today]), ..Config::default() };
+
+        let result = SprintEvents::ScheduledTrigger.try_into_sprint_command(&active_context, &cumulative_contexts, &holiday_config).await;
+        assert_eq!(result.unwrap_err().to_string(), "Skipping scheduled summary on a holiday");
+
+        let result = SprintEvents::ScheduledTrigger.try_into_sprint_command(&active_context, &cumulative_contexts, &Config::default()).await;
+        assert_eq!(result.unwrap(), SprintCommand::DailySummary);
+    }
+
+    #[tokio::test]
+    async fn test_review_skipped_on_holiday_runs_the_next_day() {
+        let yesterday = current_date(Config::default().summary_timezone) - Duration::days(1);
+        let active_context = Some(ActiveSprintContext { end_date: yesterday.format("%m/%d/%y").to_string(), ..ActiveSprintContext::default() });
+
+        let result = SprintEvents::ScheduledTrigger.try_into_sprint_command(&active_context, &CumulativeSprintContexts { history: vec![] }, &Config::default()).await;
+        assert_eq!(result.unwrap(), SprintCommand::SprintReview);
+    }
 }
@@ -34,12 +34,14 @@ impl From<SlackSlashCommandBody> for SprintEvents {
         let response_url = Some(item.response_url);
 
         match item.command.as_str() {
-            "/sprint-kickoff" | "/sprint-check-in" | "/sprint-end" | "/sprint-cancel" => {
+            "/sprint-kickoff" | "/sprint-preview" | "/sprint-check-in" | "/sprint-end" | "/sprint-review" | "/sprint-cancel" | "/daily-trigger" | "/sprint-defer" | "/sprint-review-preview" | "/sprint-map-user" | "/sprint-hygiene" | "/sprint-backfill" | "/sprint-extend" | "/sprint-config" | "/sprint-status" | "/sprint-velocity" => {
                 SprintEvents::MessageTrigger {
                     command: item.command,
                     args,
                     response_url,
-                    channel_id: item.channel_id
+                    channel_id: item.channel_id,
+                    trigger_id: Some(item.trigger_id),
+                    user_id: Some(item.user_id),
                 }
             },
             _ => unimplemented!("This command is not supported yet")
@@ -99,12 +101,29 @@ impl From<SlackBlockActionPayload> for SprintEvents {
         let args: Vec<String> = item.actions[0].value.split_whitespace().map(String::from).collect::<Vec<String>>();
 
         match item.actions[0].action_id.as_str() {
-            "/sprint-kickoff-confirm" => SprintEvents::MessageTrigger{command: item.actions[0].action_id.clone(), args, response_url: None, channel_id: item.channel.id},
+            "/sprint-kickoff-confirm" => SprintEvents::MessageTrigger{command: item.actions[0].action_id.clone(), args, response_url: None, channel_id: item.channel.id, trigger_id: Some(item.trigger_id), user_id: None},
             _ => unimplemented!("This command is not supported yet"),
         }
     }
 }
 
+//an empty allowlist accepts requests from any slack app
+pub fn verify_slack_app(request: &HttpRequest, allowed_app_ids: &[String]) -> Result<()> {
+    if allowed_app_ids.is_empty() {
+        return Ok(());
+    }
+
+    let api_app_id = SlackSlashCommandBody::try_from(request)
+        .map(|body| body.api_app_id)
+        .or_else(|_| SlackBlockActionPayload::try_from(request).map(|payload| payload.api_app_id))?;
+
+    if allowed_app_ids.contains(&api_app_id) {
+        Ok(())
+    } else {
+        Err(anyhow!("Rejected request from unrecognized Slack app {}", api_app_id))
+    }
+}
+
 impl TryFrom<&HttpRequest> for SprintEvents {
     type Error = anyhow::Error;
 
@@ -119,4 +138,41 @@ impl TryFrom<&HttpRequest> for SprintEvents {
                 Err(anyhow!("Failed to parse HttpRequest into any known Slack payload type"))
             })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slash_command_request(api_app_id: &str) -> HttpRequest {
+        HttpRequest {
+            http_method: "POST".to_string(),
+            body: format!("token=abc&channel_id=C123&user_id=U123&command=%2Fsprint-check-in&text=&api_app_id={}&response_url=https%3A%2F%2Fexample.com&trigger_id=T123", api_app_id),
+            headers: None,
+        }
+    }
+
+    #[test]
+    fn test_allowed_slack_app_passes() {
+        let allowed_app_ids = vec!["A_PROD".to_string(), "A_DEV".to_string()];
+
+        assert!(verify_slack_app(&slash_command_request("A_DEV"), &allowed_app_ids).is_ok());
+        assert!(verify_slack_app(&slash_command_request("A_OTHER"), &[]).is_ok());
+    }
+
+    #[test]
+    fn test_disallowed_slack_app_is_rejected() {
+        let allowed_app_ids = vec!["A_PROD".to_string()];
+
+        let result = verify_slack_app(&slash_command_request("A_OTHER"), &allowed_app_ids);
+
+        assert!(result.unwrap_err().to_string().contains("A_OTHER"));
+    }
+    #[test]
+    fn test_slash_command_keeps_invoking_user() {
+        let event = SprintEvents::try_from(&slash_command_request("A_PROD")).unwrap();
+
+        assert_eq!(event.user_id(), Some("U123"));
+        assert_eq!(event.trigger_id(), Some("T123"));
+    }
+}
@@ -0,0 +1,89 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{from_value, Value};
+use crate::utils::s3::JsonStorageClient;
+use crate::utils::slack_components::context_block;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FooterLink {
+    pub label: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct FooterLinks {
+    pub links: Vec<FooterLink>,
+}
+
+impl FooterLinks {
+    pub fn into_slack_blocks(&self) -> Vec<Value> {
+        if self.links.is_empty() {
+            return vec![];
+        }
+
+        let link_text = self.links.iter()
+            .map(|link| format!("<{}|{}>", link.url, link.label))
+            .collect::<Vec<String>>()
+            .join(" | ");
+
+        vec![context_block(&link_text)]
+    }
+}
+
+#[async_trait(?Send)]
+pub trait FooterLinksClient {
+    async fn get_footer_links(&self, configured_links: Option<&str>) -> Result<FooterLinks>;
+}
+
+//SUMMARY_FOOTER_LINKS takes precedence over the stored footer links
+#[async_trait(?Send)]
+impl<T> FooterLinksClient for T where T: JsonStorageClient, {
+    async fn get_footer_links(&self, configured_links: Option<&str>) -> Result<FooterLinks> {
+        let json_value = match configured_links {
+            Some(links) => Some(serde_json::from_str::<Value>(links).context("Failed to parse SUMMARY_FOOTER_LINKS")?),
+            None => self.get_json("footer_links.json").await?,
+        };
+
+        json_value
+            .map(|json_value| {
+                from_value::<Vec<FooterLink>>(json_value)
+                    .map(|links| FooterLinks { links })
+                    .context("Failed to deserialize footer links")
+            })
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_footer_links_render_as_context_block() {
+        let footer_links = FooterLinks {
+            links: vec![
+                FooterLink { label: "Retro Doc".to_string(), url: "https://example.com/retro".to_string() },
+                FooterLink { label: "Metrics".to_string(), url: "https://example.com/metrics".to_string() },
+            ],
+        };
+
+        let blocks = footer_links.into_slack_blocks();
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0]["type"], "context");
+        assert_eq!(blocks[0]["elements"][0]["text"], "<https://example.com/retro|Retro Doc> | <https://example.com/metrics|Metrics>");
+    }
+
+    #[test]
+    fn test_no_footer_blocks_when_unconfigured() {
+        assert!(FooterLinks::default().into_slack_blocks().is_empty());
+    }
+
+    #[test]
+    fn test_deserialize_footer_links() {
+        let links: Vec<FooterLink> = serde_json::from_str(r#"[{"label": "Planning Board", "url": "https://example.com/board"}]"#).unwrap();
+        assert_eq!(links[0].label, "Planning Board");
+    }
+}
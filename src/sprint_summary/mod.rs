@@ -5,27 +5,46 @@ pub mod sprint_records;
 pub mod events;
 pub mod ticket_state;
 pub mod ticket_label;
-use std::env;
+pub mod footer_links;
+mod completion_chart;
+mod validation;
+use std::collections::BTreeSet;
 use std::ops::Deref;
 use anyhow::{Result, anyhow};
+use chrono_tz::Tz;
 use serde_json::Value;
-use crate::utils::date::{days_between, print_current_date};
+use crate::utils::config::{Config, HeaderDateStyle, PreviewArchive, SummaryCanvas};
+use crate::utils::date::{business_days_between, current_timestamp, days_between, print_current_date};
 use crate::utils::eventbridge::NotificationClient;
+use crate::utils::localization::{Label, Localization};
+use crate::utils::slack_canvas::{blocks_to_markdown, CanvasClient};
 use crate::utils::slack_components::{context_block, header_block, primary_button_block, section_block};
+use crate::utils::slack_output::{SlackApiError, TeamCommunicationClient};
+use crate::tracing::info;
 use self::sprint_records::{
-    ActiveSprintContext, CumulativeSprintContext, CumulativeSprintContexts, DailyTicketContexts, SprintClient
+    ActiveSprintContext, CumulativeSprintContext, CumulativeSprintContexts, DailyTicketContext, DailyTicketContexts, LastPostContext, PreviewCache, PreviewMessageContext, SprintArchive, SprintClient
 };
-use self::ticket_summary::TicketSummary;
+pub use self::ticket_summary::{SummarySection, TicketSummary};
+use self::completion_chart::completion_chart_block;
 
 #[derive(PartialEq, Debug)]
 pub enum SprintCommand {
-    SprintPreview{sprint_name: String, end_date: String, channel_id: String},
-    SprintKickoff{sprint_name: String, end_date: String, channel_id: String},
-    SprintCheckIn,
+    SprintPreview{sprint_name: String, end_date: String, channel_id: String, capacity: Option<u32>, preview_token: Option<String>, theme_emoji: Option<String>, goal_text: Option<String>},
+    SprintKickoff{sprint_name: String, end_date: String, channel_id: String, capacity: Option<u32>, preview_token: Option<String>, theme_emoji: Option<String>, goal_text: Option<String>},
+    SprintCheckIn{force: bool},
+    SprintStatus,
     SprintEnd,
     SprintCancel,
     DailySummary,
     SprintReview,
+    SprintReviewPreview,
+    SprintDefer{ticket_id: String},
+    SprintMapUser{trello_id: String, slack_user_id: String},
+    SprintHygiene,
+    SprintBackfill,
+    SprintExtend{new_end_date: String},
+    SprintConfig,
+    SprintVelocity{sprint_count: usize},
 }
 
 pub trait SprintCommandParser {
@@ -33,30 +52,38 @@ pub trait SprintCommandParser {
         &self, 
         active_sprint_context: &Option<ActiveSprintContext>,
         cumulative_sprint_contexts: &CumulativeSprintContexts,
+        config: &Config,
     ) -> Result<SprintCommand>;
 }
 
 impl ActiveSprintContext {
-    pub fn days_until_end(&self) -> u32 {
-        days_between(None, &self.end_date).expect("Days until end should be parseable") as u32
+    pub fn days_until_end(&self, timezone: Tz) -> u32 {
+        days_between(&print_current_date(timezone), &self.end_date).expect("Days until end should be parseable") as u32
     }
 
-    pub fn total_days_elapsed(&self) -> u32 {
-        days_between(Some(&self.start_date), &print_current_date()).expect("Total days should be parseable") as u32
+    //weekends don't count toward the time left to work on the sprint
+    pub fn business_days_until_end(&self, timezone: Tz) -> u32 {
+        business_days_between(&print_current_date(timezone), &self.end_date).expect("Days until end should be parseable").max(0) as u32
+    }
+
+    pub fn total_days_elapsed(&self, timezone: Tz) -> u32 {
+        days_between(&self.start_date, &print_current_date(timezone)).expect("Total days should be parseable") as u32
     }
     
     pub fn sprint_length(&self) -> u32 {
-        days_between(Some(&self.start_date), &self.end_date).expect("Total days should be parseable") as u32
+        days_between(&self.start_date, &self.end_date).expect("Total days should be parseable") as u32
     }
     
-    pub fn remaining_time_indicator(&self) -> &str {
-        let days_left = self.days_until_end() as f32;
-        let total_days = days_between(Some(&self.start_date), &self.end_date).expect("Days should be parseable") as f32;
-        if total_days == 0.0 {
-            return "🌕";
-        }
-        
-        let ratio = days_left / total_days;
+    pub fn remaining_time_indicator(&self, timezone: Tz) -> &str {
+        let days_left = days_between(&print_current_date(timezone), &self.end_date).expect("Days until end should be parseable") as f32;
+        let total_days = days_between(&self.start_date, &self.end_date).expect("Days should be parseable") as f32;
+
+        //same-day or inverted sprints have no span to divide by, so they're either all remaining or all elapsed
+        let ratio = if total_days <= 0.0 {
+            if days_left > 0.0 { 1.0 } else { 0.0 }
+        } else {
+            (days_left / total_days).clamp(0.0, 1.0)
+        };
         let emoji_index = (1.0 - ratio) * 4.0;
         
         match emoji_index.round() as i32 {
@@ -67,62 +94,274 @@ impl ActiveSprintContext {
             4 | _ => "🌑",
         }
     }
+
+    //a new moon reads as "done", so the last days can swap it for something more urgent
+    pub fn daily_header_indicator(&self, urgent_last_day: bool, timezone: Tz) -> &str {
+        if !urgent_last_day {
+            return self.remaining_time_indicator(timezone);
+        }
+
+        match days_between(&print_current_date(timezone), &self.end_date).expect("Days until end should be parseable") {
+            days_left if days_left <= 0 => "🚨",
+            1 => "⏳",
+            _ => self.remaining_time_indicator(timezone),
+        }
+    }
 }
 
-pub fn count_difference(num1: i32, num2: i32) -> String {
+pub fn count_difference(localization: &Localization, num1: i32, num2: i32) -> String {
     let diff = num1 - num2;
     if diff != 0 {
         let added_label = if diff < 0 {
-            "removed from"
+            Label::TicketsRemovedFrom
         } else {
-            "added to"
+            Label::TicketsAddedTo
         };
 
-        format!("{} tickets {}", diff.abs(), added_label)
+        localization.format(added_label, &[&diff.abs()])
+    } else {
+        localization.text(Label::NoTicketsAddedTo)
+    }
+}
+
+//only shown once some ticket in the sprint has been estimated
+pub fn points_completed(localization: &Localization, ticket_summary: &TicketSummary) -> Option<String> {
+    if ticket_summary.total_points == 0 {
+        return None;
+    }
+
+    Some(localization.format(Label::PointsCompleted, &[&ticket_summary.completed_points, &ticket_summary.total_points]))
+}
+
+//e.g. "ends in 3 days" or "ended yesterday", counted from today
+pub fn relative_end_date(localization: &Localization, days_until_end: i64) -> String {
+    match days_until_end {
+        0 => localization.text(Label::EndsToday),
+        1 => localization.text(Label::EndsTomorrow),
+        -1 => localization.text(Label::EndedYesterday),
+        days if days > 1 => localization.format(Label::EndsInDays, &[&days]),
+        days => localization.format(Label::EndedDaysAgo, &[&days.abs()]),
+    }
+}
+
+fn header_date(localization: &Localization, active_sprint_context: &ActiveSprintContext, header_date_style: HeaderDateStyle, timezone: Tz) -> Result<String> {
+    let relative = || -> Result<String> { Ok(relative_end_date(localization, days_between(&print_current_date(timezone), &active_sprint_context.end_date)?)) };
+
+    Ok(match header_date_style {
+        HeaderDateStyle::Absolute => print_current_date(timezone),
+        HeaderDateStyle::Relative => relative()?,
+        HeaderDateStyle::Both => format!("{} · {}", print_current_date(timezone), relative()?),
+    })
+}
+
+//swaps a header's leading emoji for the sprint's theme emoji
+fn themed_header(header: String, theme_emoji: Option<&str>) -> String {
+    let Some(theme_emoji) = theme_emoji else {
+        return header;
+    };
+
+    match header.split_once(' ') {
+        Some((prefix, title)) if !prefix.chars().any(char::is_alphanumeric) => format!("{} {}", theme_emoji, title),
+        _ => format!("{} {}", theme_emoji, header),
+    }
+}
+
+pub fn carryover_line(localization: &Localization, carried_over_count: usize, hide_when_empty: bool) -> Option<String> {
+    match carried_over_count {
+        0 if hide_when_empty => None,
+        0 => Some(localization.text(Label::NoTicketsCarriedOver)),
+        1 => Some(localization.text(Label::OneTicketCarriedOver)),
+        _ => Some(localization.format(Label::CarriedOverTickets, &[&carried_over_count])),
+    }
+}
+
+pub fn cancel_message(localization: &Localization, config: &Config) -> String {
+    match &config.sprint_cancel_message {
+        Some(message) => message.clone(),
+        None if config.record_cancelled_sprints => localization.text(Label::CancelledSprintRecorded),
+        None => localization.text(Label::ProgressNotSaved),
+    }
+}
+
+pub fn capacity_warning(localization: &Localization, planned_ticket_count: u32, capacity: Option<u32>) -> Option<String> {
+    match capacity {
+        Some(capacity) if planned_ticket_count > capacity => Some(localization.format(Label::OverCapacity, &[&planned_ticket_count, &capacity])),
+        _ => None,
+    }
+}
+
+//plain text stand-in for when Slack rejects the summary blocks
+pub fn fallback_summary_text(localization: &Localization, ticket_summary: &TicketSummary, config: &Config) -> String {
+    let board_link = config.board_url().map(|board_url| format!("<{}|{}>", board_url, localization.text(Label::ViewSprintBoard))).unwrap_or_default();
+
+    localization.format(Label::FallbackSummary, &[
+        &ticket_summary.open_ticket_count,
+        &ticket_summary.sprint_ticket_count,
+        &format!("{:.2}", ticket_summary.completed_percentage),
+        &board_link,
+    ]).trim_end().to_string()
+}
+
+pub fn scope_change_alert(localization: &Localization, sprint_ticket_count: u32, in_scope_tickets_count_beginning: u32, threshold_percent: Option<f64>) -> Option<String> {
+    let threshold_percent = threshold_percent?;
+    if in_scope_tickets_count_beginning == 0 {
+        return None;
+    }
+
+    let growth_percent = (sprint_ticket_count as f64 - in_scope_tickets_count_beginning as f64) / in_scope_tickets_count_beginning as f64 * 100.0;
+    if growth_percent > threshold_percent {
+        Some(localization.format(Label::ScopeChangeAlert, &[&format!("{:.0}", growth_percent), &in_scope_tickets_count_beginning, &sprint_ticket_count]))
     } else {
-        "No tickets added to".to_string()
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateChange {
+    SprintData,
+    SprintCleared,
+    TicketData,
+    HistoricalData,
+    SprintArchive,
+    SprintMembers,
+    PreviewCache,
+    PreviewMessage,
+    TriggerRule,
+    HistoryBackfilled { backfilled_count: usize, sprint_count: usize },
+}
+
+impl StateChange {
+    //some results are only known once the state is saved, like how many sprints a backfill recomputed
+    fn result_block(&self, localization: &Localization) -> Option<Value> {
+        match self {
+            StateChange::HistoryBackfilled { backfilled_count, sprint_count } => Some(section_block(&localization.format(Label::BackfillFinished, &[backfilled_count, sprint_count]))),
+            _ => None,
+        }
     }
 }
 
-const DAILY_SUMMARY_TIME: &str = "cron(0 3 * * ? *)";
-const SPRINT_REVIEW_TIME: &str = "cron(0 4 * * ? *)";
+//the fetched tickets and stored sprint state a command runs against
+pub struct CommandContext<'a> {
+    pub ticket_summary: &'a mut TicketSummary,
+    pub active_sprint_context: &'a Option<ActiveSprintContext>,
+    pub cumulative_sprint_contexts: &'a mut CumulativeSprintContexts,
+    pub daily_ticket_contexts: &'a DailyTicketContexts,
+}
+
+//everything a command produced, so the handler only has to post it
+#[derive(Debug)]
+pub struct CommandOutcome {
+    pub channel_id: String,
+    pub blocks: Vec<Value>,
+    pub alerts: Vec<(String, Vec<Value>)>,
+    pub state_changes: Vec<StateChange>,
+}
+
+//recomputes past sprints' completion from whichever of them were archived, returning how many were
+async fn backfill_history(cumulative_sprint_contexts: &mut CumulativeSprintContexts, sprint_client: &dyn SprintClient) -> Result<usize> {
+    let mut archives = vec![];
+    for record in &cumulative_sprint_contexts.history {
+        archives.extend(sprint_client.get_sprint_archive(&record.name).await?);
+    }
+
+    let backfilled_count = cumulative_sprint_contexts.backfill_percent_complete(&archives);
+    info!("Backfilled completion for {} of {} past sprints", backfilled_count, cumulative_sprint_contexts.history.len());
+    sprint_client.put_historical_data(cumulative_sprint_contexts).await?;
+    Ok(backfilled_count)
+}
 
 impl SprintCommand {
-    pub async fn save_sprint_state(
+    async fn save_sprint_state(
         &self, 
         ticket_summary: &mut TicketSummary,
         active_sprint_context: &Option<ActiveSprintContext>,
         cumulative_sprint_contexts: &mut CumulativeSprintContexts,
         sprint_client: &dyn SprintClient,
-        notification_client: &dyn NotificationClient
-    ) -> Result<(), anyhow::Error> {    
+        notification_client: &dyn NotificationClient,
+        config: &Config
+    ) -> Result<Vec<StateChange>, anyhow::Error> {
+        let mut state_changes = vec![];
+
         match self {
-            SprintCommand::SprintKickoff { sprint_name, end_date, channel_id } => {
+            SprintCommand::SprintPreview { preview_token: Some(preview_token), .. } => {
+                if config.preview_cache_seconds.is_some() {
+                    sprint_client.put_preview_cache(&PreviewCache {
+                        token: preview_token.clone(),
+                        created_at: current_timestamp(),
+                        ticket_summary: ticket_summary.clone(),
+                    }).await?;
+                    state_changes.push(StateChange::PreviewCache);
+                }
+            },
+            SprintCommand::SprintKickoff { sprint_name, end_date, channel_id, capacity, preview_token: _, theme_emoji, goal_text } => {
                 let new_sprint_context = ActiveSprintContext {
                     end_date: end_date.to_string(),
                     name: sprint_name.to_string(),
                     channel_id: channel_id.to_string(),
-                    start_date: print_current_date(),
+                    start_date: print_current_date(config.summary_timezone),
                     open_tickets_count_beginning: ticket_summary.open_ticket_count,
                     in_scope_tickets_count_beginning: ticket_summary.sprint_ticket_count,
-                    trello_board: env::var("TRELLO_BOARD_ID")?,
+                    trello_board: config.trello_board_id.clone(),
+                    capacity: *capacity,
+                    manual_deferrals: BTreeSet::new(),
+                    theme_emoji: theme_emoji.clone(),
+                    goal_text: goal_text.clone(),
+                    canvas_id: None,
+                    daily_summary_ts: None,
+                    root_message_ts: None,
                 };
                 sprint_client.put_sprint_data(&new_sprint_context).await?;
-                notification_client.create_daily_trigger_rule(sprint_name, DAILY_SUMMARY_TIME).await?;
+                notification_client.create_daily_trigger_rule(sprint_name, &config.daily_summary_time).await?;
+                //carried over tickets already took their added_in_sprint and added_on from the previous ticket data when fetched
+                sprint_client.put_ticket_data(&(ticket_summary).deref().into()).await?;
+                state_changes.extend([StateChange::SprintData, StateChange::TriggerRule, StateChange::TicketData]);
+            },
+            SprintCommand::SprintCheckIn { .. } => {
                 sprint_client.put_ticket_data(&(ticket_summary).deref().into()).await?;
+                state_changes.push(StateChange::TicketData);
             },
             SprintCommand::DailySummary => {
                 sprint_client.put_ticket_data(&(ticket_summary).deref().into()).await?;
+                state_changes.push(StateChange::TicketData);
                 let context = active_sprint_context.as_ref().unwrap();
-                if (days_between(Some(&print_current_date()), &context.end_date).unwrap() == 1) {
-                    notification_client.change_daily_trigger_rule(&context.name, SPRINT_REVIEW_TIME).await?;
+                if (days_between(&print_current_date(config.summary_timezone), &context.end_date).unwrap() == 1) {
+                    notification_client.change_daily_trigger_rule(&context.name, &config.sprint_review_time).await?;
+                    state_changes.push(StateChange::TriggerRule);
                 }
             },
+            SprintCommand::SprintDefer { ticket_id } => {
+                let mut sprint_context = active_sprint_context.clone().ok_or(anyhow!("Active sprint context is required for this operation."))?;
+                sprint_context.manual_deferrals.insert(ticket_id.clone());
+                sprint_client.put_sprint_data(&sprint_context).await?;
+                state_changes.push(StateChange::SprintData);
+            },
+            SprintCommand::SprintMapUser { trello_id, slack_user_id } => {
+                sprint_client.map_sprint_member(trello_id, slack_user_id).await?;
+                state_changes.push(StateChange::SprintMembers);
+            },
+            SprintCommand::SprintExtend { new_end_date } => {
+                let mut sprint_context = active_sprint_context.clone().ok_or(anyhow!("Active sprint context is required for this operation."))?;
+                sprint_context.end_date = new_end_date.clone();
+                sprint_client.put_sprint_data(&sprint_context).await?;
+
+                //the rule may already have moved to the review time the day before the old end date
+                let trigger_time = if days_between(&print_current_date(config.summary_timezone), new_end_date)? <= 1 { &config.sprint_review_time } else { &config.daily_summary_time };
+                notification_client.change_daily_trigger_rule(&sprint_context.name, trigger_time).await?;
+                state_changes.extend([StateChange::SprintData, StateChange::TriggerRule]);
+            },
+            SprintCommand::SprintBackfill => {
+                let backfilled_count = backfill_history(cumulative_sprint_contexts, sprint_client).await?;
+                state_changes.extend([StateChange::HistoricalData, StateChange::HistoryBackfilled { backfilled_count, sprint_count: cumulative_sprint_contexts.history.len() }]);
+            },
             SprintCommand::SprintCancel | SprintCommand::SprintEnd | SprintCommand::SprintReview => {
                 if let Some(sprint_data) = active_sprint_context {
                     notification_client.delete_daily_trigger_rule(&sprint_data.name).await?;
+                    state_changes.push(StateChange::TriggerRule);
     
-                    if matches!(self, SprintCommand::SprintEnd | SprintCommand::SprintReview) {
+                    let is_recorded = matches!(self, SprintCommand::SprintEnd | SprintCommand::SprintReview)
+                        || (self == &SprintCommand::SprintCancel && config.record_cancelled_sprints);
+
+                    if is_recorded {
                         let open_tickets_added_count = ticket_summary.open_ticket_count as i32 - sprint_data.open_tickets_count_beginning as i32;
                         let tickets_added_to_scope_count = ticket_summary.sprint_ticket_count as i32 - sprint_data.in_scope_tickets_count_beginning as i32;
 
@@ -134,14 +373,26 @@ impl SprintCommand {
                             completed_tickets_count: ticket_summary.completed_tickets.len() as u32,
                             open_tickets_added_count,
                             tickets_added_to_scope_count,
+                            cancelled: self == &SprintCommand::SprintCancel,
                         });
 
                         sprint_client.put_historical_data(cumulative_sprint_contexts).await?;
+                        state_changes.push(StateChange::HistoricalData);
+                    }
+
+                    if matches!(self, SprintCommand::SprintEnd | SprintCommand::SprintReview) {
+                        sprint_client.put_sprint_archive(&SprintArchive {
+                            name: sprint_data.name.clone(),
+                            sprint_ticket_count: ticket_summary.sprint_ticket_count,
+                            completed_tickets: ticket_summary.completed_tickets.iter().map(DailyTicketContext::from).collect(),
+                        }).await?;
                         ticket_summary.clear_completed_and_deferred();
                         sprint_client.put_ticket_data(&(ticket_summary).deref().into()).await?;
+                        state_changes.extend([StateChange::SprintArchive, StateChange::TicketData]);
                     }
     
                     sprint_client.clear_sprint_data().await?;
+                    state_changes.push(StateChange::SprintCleared);
                 } else {
                     return Err(anyhow!("Active sprint context is required for this operation."));
                 }
@@ -149,46 +400,298 @@ impl SprintCommand {
             _ => {}
         }
     
-        Ok(())
+        Ok(state_changes)
+    }
+
+    //builds the messages from the state before the command, then saves the state the command leaves behind
+    pub async fn execute(
+        &self,
+        channel_id: &str,
+        context: CommandContext<'_>,
+        localization: &Localization,
+        sprint_client: &dyn SprintClient,
+        notification_client: &dyn NotificationClient,
+        config: &Config
+    ) -> Result<CommandOutcome> {
+        let CommandContext { ticket_summary, active_sprint_context, cumulative_sprint_contexts, daily_ticket_contexts } = context;
+
+        let mut blocks = self.create_sprint_message(ticket_summary, active_sprint_context, cumulative_sprint_contexts, daily_ticket_contexts, localization, config).await?;
+        let alerts = self.blocked_prs_alert(ticket_summary, active_sprint_context, localization, config).into_iter().collect();
+        let state_changes = self.save_sprint_state(ticket_summary, active_sprint_context, cumulative_sprint_contexts, sprint_client, notification_client, config).await?;
+        blocks.extend(state_changes.iter().filter_map(|state_change| state_change.result_block(localization)));
+
+        Ok(CommandOutcome {
+            channel_id: channel_id.to_string(),
+            blocks,
+            alerts,
+            state_changes,
+        })
+    }
+
+    fn posts_summary_canvas(&self, config: &Config) -> bool {
+        config.summary_canvas.is_some() && matches!(self, SprintCommand::DailySummary | SprintCommand::SprintCheckIn { .. })
+    }
+
+    pub fn skips_channel_message(&self, config: &Config) -> bool {
+        self.posts_summary_canvas(config) && config.summary_canvas == Some(SummaryCanvas::Instead)
+    }
+
+    //the first summary creates the sprint's canvas and keeps its id on the sprint, later summaries replace its content
+    pub async fn update_summary_canvas(
+        &self,
+        blocks: &[Value],
+        active_sprint_context: &Option<ActiveSprintContext>,
+        canvas_client: &impl CanvasClient,
+        sprint_client: &dyn SprintClient,
+        localization: &Localization,
+        config: &Config
+    ) -> Result<Vec<StateChange>> {
+        let Some(context) = active_sprint_context.as_ref().filter(|_| self.posts_summary_canvas(config)) else {
+            return Ok(vec![]);
+        };
+
+        let title = localization.format(Label::CanvasTitle, &[&context.name]);
+        let canvas_id = canvas_client.upsert_canvas(context.canvas_id.as_deref(), &context.channel_id, &title, &blocks_to_markdown(blocks)).await?;
+        if context.canvas_id.as_ref() == Some(&canvas_id) {
+            return Ok(vec![]);
+        }
+
+        sprint_client.put_sprint_data(&ActiveSprintContext { canvas_id: Some(canvas_id), ..context.clone() }).await?;
+        Ok(vec![StateChange::SprintData])
+    }
+
+    pub fn updates_in_place(&self, config: &Config) -> bool {
+        config.update_daily_summary_in_place && self == &SprintCommand::DailySummary
+    }
+
+    //edits the sprint's daily summary message instead of posting a new one, keeping the ts of whichever message ends up holding it
+    pub async fn update_daily_summary_in_place(
+        &self,
+        blocks: &[Value],
+        active_sprint_context: &Option<ActiveSprintContext>,
+        slack_client: &impl TeamCommunicationClient,
+        sprint_client: &dyn SprintClient,
+        config: &Config
+    ) -> Result<Vec<StateChange>> {
+        let Some(context) = active_sprint_context.as_ref().filter(|_| self.updates_in_place(config)) else {
+            return Ok(vec![]);
+        };
+
+        //with threading on, the evolving summary lives in the sprint's thread rather than the channel
+        let thread_ts = context.root_message_ts.as_deref().filter(|_| config.thread_daily_summaries);
+        let ts = slack_client.upsert_teams_message(&context.channel_id, context.daily_summary_ts.as_deref(), thread_ts, &blocks).await?;
+        if context.daily_summary_ts.as_ref() == Some(&ts) {
+            return Ok(vec![]);
+        }
+
+        //the canvas may have just been saved on the sprint, so the latest copy is the one updated
+        let latest_context = sprint_client.get_sprint_data().await?.unwrap_or(context.clone());
+        sprint_client.put_sprint_data(&ActiveSprintContext { daily_summary_ts: Some(ts), ..latest_context }).await?;
+        Ok(vec![StateChange::SprintData])
+    }
+
+    pub fn threads_message(&self, config: &Config) -> bool {
+        config.thread_daily_summaries && matches!(self, SprintCommand::SprintKickoff { .. } | SprintCommand::DailySummary)
+    }
+
+    //the kickoff message becomes the sprint's root message, and daily summaries are posted as replies to it
+    pub async fn post_threaded_message(
+        &self,
+        channel_id: &str,
+        blocks: &[Value],
+        active_sprint_context: &Option<ActiveSprintContext>,
+        slack_client: &impl TeamCommunicationClient,
+        sprint_client: &dyn SprintClient
+    ) -> Result<Vec<StateChange>> {
+        match self {
+            SprintCommand::SprintKickoff { .. } => {
+                let ts = slack_client.post_teams_message(channel_id, &blocks, None).await?;
+                let Some(context) = sprint_client.get_sprint_data().await? else {
+                    return Ok(vec![]);
+                };
+
+                sprint_client.put_sprint_data(&ActiveSprintContext { root_message_ts: Some(ts), ..context }).await?;
+                Ok(vec![StateChange::SprintData])
+            },
+            //sprints kicked off before threading was turned on have no root message, so their summaries stay top level
+            _ => {
+                let thread_ts = active_sprint_context.as_ref().and_then(|context| context.root_message_ts.as_deref());
+                slack_client.post_teams_message(channel_id, &blocks, thread_ts).await?;
+                Ok(vec![])
+            },
+        }
+    }
+
+    pub fn posts_ephemeral_preview(&self, config: &Config) -> bool {
+        config.ephemeral_previews && matches!(self, SprintCommand::SprintPreview { .. })
+    }
+
+    //only the person who asked for the preview sees it, the confirmed kickoff is still posted to the channel
+    pub async fn post_ephemeral_preview(
+        &self,
+        channel_id: &str,
+        user_id: Option<&str>,
+        blocks: &[Value],
+        slack_client: &impl TeamCommunicationClient
+    ) -> Result<()> {
+        match user_id {
+            Some(user_id) => slack_client.post_ephemeral_message(channel_id, user_id, &blocks).await,
+            //without anyone to show it to, the preview goes to the channel as usual
+            None => slack_client.send_teams_message(channel_id, &blocks, None).await.map(|_| ()),
+        }
+    }
+
+    //ephemeral messages can't be edited or deleted through the API, so there's nothing to archive
+    pub fn tracks_preview_message(&self, config: &Config) -> bool {
+        config.kickoff_preview_archive.is_some() && !self.posts_ephemeral_preview(config) && matches!(self, SprintCommand::SprintPreview { .. })
+    }
+
+    //posted to the channel rather than the slash command's response url, since only chat.postMessage hands back a ts to archive it by
+    pub async fn post_preview_message(
+        &self,
+        channel_id: &str,
+        blocks: &[Value],
+        slack_client: &impl TeamCommunicationClient,
+        sprint_client: &dyn SprintClient
+    ) -> Result<Vec<StateChange>> {
+        let ts = slack_client.post_teams_message(channel_id, &blocks, None).await?;
+        sprint_client.put_preview_message(&PreviewMessageContext { channel_id: channel_id.to_string(), ts }).await?;
+        Ok(vec![StateChange::PreviewMessage])
+    }
+
+    //once the kickoff is confirmed the preview's numbers and button are stale, so it's removed or marked as out of date
+    pub async fn archive_kickoff_preview(
+        &self,
+        slack_client: &impl TeamCommunicationClient,
+        sprint_client: &dyn SprintClient,
+        localization: &Localization,
+        config: &Config
+    ) -> Result<Vec<StateChange>> {
+        let (SprintCommand::SprintKickoff { .. }, Some(preview_archive)) = (self, config.kickoff_preview_archive) else {
+            return Ok(vec![]);
+        };
+        let Some(preview_message) = sprint_client.get_preview_message().await? else {
+            return Ok(vec![]);
+        };
+
+        let archive_result = match preview_archive {
+            PreviewArchive::Delete => slack_client.delete_teams_message(&preview_message.channel_id, &preview_message.ts).await,
+            PreviewArchive::Update => slack_client.update_teams_message(&preview_message.channel_id, &preview_message.ts, &vec![context_block(&localization.text(Label::PreviewArchived))]).await,
+        };
+        //a preview someone already deleted doesn't need tidying up
+        if let Err(archive_error) = archive_result {
+            if !archive_error.downcast_ref::<SlackApiError>().is_some_and(|slack_error| slack_error.is_message_not_found()) {
+                return Err(archive_error);
+            }
+        }
+
+        sprint_client.clear_preview_message().await?;
+        Ok(vec![StateChange::PreviewMessage])
+    }
+
+    //stops scheduled summaries from retrying every day once the sprint channel is archived or deleted
+    pub async fn pause_on_unavailable_channel(
+        &self,
+        send_error: &anyhow::Error,
+        active_sprint_context: &Option<ActiveSprintContext>,
+        notification_client: &dyn NotificationClient
+    ) -> Result<bool> {
+        let channel_unavailable = send_error.downcast_ref::<SlackApiError>()
+            .is_some_and(|slack_error| slack_error.is_channel_unavailable());
+
+        match (self, active_sprint_context) {
+            (SprintCommand::DailySummary, Some(context)) if channel_unavailable => {
+                info!("Pausing sprint {} because channel {} is unavailable: {}", context.name, context.channel_id, send_error);
+                notification_client.disable_daily_trigger_rule(&context.name).await?;
+                Ok(true)
+            },
+            _ => Ok(false),
+        }
+    }
+
+    //a forced check-in still counts as the latest post, it just isn't held back by the one before it
+    pub async fn is_throttled(&self, sprint_client: &dyn SprintClient, now: i64, config: &Config) -> Result<bool> {
+        match (self, config.summary_throttle_seconds) {
+            (SprintCommand::DailySummary | SprintCommand::SprintCheckIn { force: false }, Some(window_seconds)) => sprint_client.posted_within(now, window_seconds).await,
+            _ => Ok(false),
+        }
+    }
+
+    pub async fn record_post(&self, sprint_client: &dyn SprintClient, now: i64, config: &Config) -> Result<()> {
+        match (self, config.summary_throttle_seconds) {
+            (SprintCommand::DailySummary | SprintCommand::SprintCheckIn { .. }, Some(_)) => sprint_client.put_last_post(&LastPostContext { posted_at: now }).await,
+            _ => Ok(()),
+        }
     }
 
-    pub async fn create_sprint_message(
+    async fn create_sprint_message(
         &self, 
         ticket_summary: &TicketSummary,
         active_sprint_context: &Option<ActiveSprintContext>,
         cumulative_sprint_contexts: &CumulativeSprintContexts,
-        daily_ticket_contexts: &DailyTicketContexts
+        daily_ticket_contexts: &DailyTicketContexts,
+        localization: &Localization,
+        config: &Config
     ) -> Result<Vec<Value>> {
-        let trello_board_id = env::var("TRELLO_BOARD_ID").unwrap_or("TRELLO_BOARD_ID needs to exist".to_owned());
-        let project_scope_block = section_block(&format!("{} tickets left in project scope.", ticket_summary.project_ticket_count_in_scope));
-        let board_link_block = context_block(&format!("<https://trello.com/b/{}|View sprint board>", trello_board_id));
+        let project_scope_block = section_block(&localization.format(Label::ProjectScope, &[&ticket_summary.project_ticket_count_in_scope]));
+        let board_link_block = context_block(&config.board_url().map_or(localization.text(Label::ViewSprintBoard), |board_url| format!("<{}|{}>", board_url, localization.text(Label::ViewSprintBoard))));
+        let scope_completed = localization.format(Label::ScopeCompleted, &[&format!("{:.2}", ticket_summary.completed_percentage)]);
+        let points_completed_blocks: Vec<Value> = points_completed(localization, ticket_summary).iter()
+            .map(|line| section_block(line))
+            .collect();
+
+        let blocks: Result<Vec<Value>> = match self {
+            SprintCommand::SprintPreview { sprint_name, end_date, channel_id: _, capacity, preview_token, theme_emoji, goal_text } => {
+                let mut kickoff_value = match capacity {
+                    Some(capacity) => format!("{} {} capacity={}", end_date, sprint_name, capacity),
+                    None => format!("{} {}", end_date, sprint_name),
+                };
+                if let Some(theme_emoji) = theme_emoji {
+                    kickoff_value = format!("{} theme={}", kickoff_value, theme_emoji);
+                }
+                if let Some(preview_token) = preview_token {
+                    kickoff_value = format!("{} preview={}", kickoff_value, preview_token);
+                }
+                //the goal takes up the rest of the command text, so it has to come last
+                if let Some(goal_text) = goal_text {
+                    kickoff_value = format!("{} goal={}", kickoff_value, goal_text);
+                }
 
-        match self {
-            SprintCommand::SprintPreview { sprint_name, end_date, channel_id: _ } => {
                 Ok([
                     vec![
-                        header_block(&format!("🔭 Sprint {} Preview: {} - {}", sprint_name, print_current_date(), end_date)),
-                        section_block(&format!("*{} Tickets*\n*{:?} Days*", ticket_summary.open_ticket_count, days_between(None, end_date)?)),
-                        section_block(&format!("\n{} tickets will be carried over from last sprint.", daily_ticket_contexts.count_open_tickets())),
+                        header_block(&themed_header(localization.format(Label::PreviewHeader, &[sprint_name, &print_current_date(config.summary_timezone), end_date]), theme_emoji.as_deref())),
+                        section_block(&localization.format(Label::PlannedTicketsAndDays, &[&ticket_summary.open_ticket_count, &days_between(&print_current_date(config.summary_timezone), end_date)?])),
                     ],
-                    cumulative_sprint_contexts.into_slack_blocks(),
-                    ticket_summary.into_slack_blocks(),
+                    goal_text.iter()
+                        .map(|goal_text| section_block(&localization.format(Label::SprintGoal, &[goal_text])))
+                        .collect(),
+                    carryover_line(localization, daily_ticket_contexts.count_open_tickets(), config.hide_empty_carryover).into_iter()
+                        .map(|line| section_block(&format!("\n{}", line)))
+                        .collect(),
+                    capacity_warning(localization, ticket_summary.open_ticket_count, *capacity).into_iter()
+                        .map(|warning| section_block(&warning))
+                        .collect(),
+                    cumulative_sprint_contexts.into_slack_blocks(localization),
+                    ticket_summary.into_slack_blocks(localization, config),
                     vec![
                         project_scope_block,
                         board_link_block,
-                        primary_button_block("Kick Off", "/sprint-kickoff-confirm",  &format!("{} {}", end_date, sprint_name)),
+                        primary_button_block(&localization.text(Label::KickOffButton), "/sprint-kickoff-confirm", &kickoff_value),
                     ]
                     ].concat()
                 )
             },
-            SprintCommand::SprintKickoff { sprint_name, end_date, channel_id: _ } => {
+            SprintCommand::SprintKickoff { sprint_name, end_date, channel_id: _, capacity: _, preview_token: _, theme_emoji, goal_text } => {
                 Ok([
                     vec![
-                        header_block(&format!("🚀 Sprint {} Kickoff: {} - {}", sprint_name, print_current_date(), end_date)),
-                        section_block("\nSprint starts now!"),
-                        section_block(&format!("*{} Tickets*\n*{:?} Days*", ticket_summary.open_ticket_count, days_between(None, end_date)?)),
+                        header_block(&themed_header(localization.format(Label::KickoffHeader, &[sprint_name, &print_current_date(config.summary_timezone), end_date]), theme_emoji.as_deref())),
+                        section_block(&format!("\n{}", localization.text(Label::SprintStartsNow))),
+                        section_block(&localization.format(Label::PlannedTicketsAndDays, &[&ticket_summary.open_ticket_count, &days_between(&print_current_date(config.summary_timezone), end_date)?])),
                     ],
-                    ticket_summary.into_slack_blocks(),
+                    goal_text.iter()
+                        .map(|goal_text| section_block(&localization.format(Label::SprintGoal, &[goal_text])))
+                        .collect(),
+                    ticket_summary.into_slack_blocks(localization, config),
                     vec![
                         board_link_block
                     ]
@@ -196,105 +699,269 @@ impl SprintCommand {
                     .concat()
                 )
             },
-            SprintCommand::SprintCheckIn => {
+            SprintCommand::SprintCheckIn { .. } => {
                 Ok([vec![
-                    header_block(&format!("🛰️ Sprint {} Check-In: {}", active_sprint_context.as_ref().unwrap().name, print_current_date())),
-                    section_block(&format!("*{} tickets open* out of {}.\n*{} days* remain in sprint.", 
-                        ticket_summary.open_ticket_count, 
-                        ticket_summary.sprint_ticket_count, 
-                        active_sprint_context.as_ref().unwrap().days_until_end()
-                    )),
-                    section_block(&format!("\n*{:.2}% of sprint scope completed.*", ticket_summary.completed_percentage)),
+                    header_block(&themed_header(localization.format(Label::CheckInHeader, &[&active_sprint_context.as_ref().unwrap().name, &print_current_date(config.summary_timezone)]), active_sprint_context.as_ref().unwrap().theme_emoji.as_deref())),
+                    section_block(&localization.format(Label::TicketsOpen, &[
+                        &ticket_summary.open_ticket_count, 
+                        &ticket_summary.sprint_ticket_count, 
+                        &active_sprint_context.as_ref().unwrap().business_days_until_end(config.summary_timezone),
+                        &active_sprint_context.as_ref().unwrap().days_until_end(config.summary_timezone)
+                    ])),
+                ],
+                    points_completed_blocks,
+                vec![
+                    section_block(&format!("\n{}", scope_completed)),
                 ],
-                    ticket_summary.into_slack_blocks(),
+                    ticket_summary.into_slack_blocks(localization, config),
                 vec![
                     project_scope_block,
                     board_link_block
                 ]].concat())
             },
+            //same numbers as a check-in, without the ticket lists since nothing is being reported to the team
+            SprintCommand::SprintStatus => {
+                let sprint = active_sprint_context.as_ref().unwrap();
+
+                Ok(vec![
+                    header_block(&themed_header(localization.format(Label::CheckInHeader, &[&sprint.name, &print_current_date(config.summary_timezone)]), sprint.theme_emoji.as_deref())),
+                    context_block(&localization.text(Label::StatusReadOnly)),
+                    section_block(&localization.format(Label::TicketsOpen, &[
+                        &ticket_summary.open_ticket_count,
+                        &ticket_summary.sprint_ticket_count,
+                        &sprint.business_days_until_end(config.summary_timezone),
+                        &sprint.days_until_end(config.summary_timezone)
+                    ])),
+                    section_block(&format!("\n{}", scope_completed)),
+                    board_link_block,
+                ])
+            },
             SprintCommand::SprintCancel => {                
                 Ok([vec![
-                    header_block(&format!("🔴 Sprint {} is cancelled.", active_sprint_context.as_ref().unwrap().name)),
-                    section_block(&format!("\n*{}/{} tickets completed in {} days.*", ticket_summary.completed_tickets.len(), ticket_summary.sprint_ticket_count, active_sprint_context.as_ref().unwrap().total_days_elapsed())),
-                    section_block(&format!("\n*{:.2}% of sprint scope completed.*\n", ticket_summary.completed_percentage)),
-                    section_block("\nProgress will not be saved.\n"),
+                    header_block(&localization.format(Label::CancelledHeader, &[&active_sprint_context.as_ref().unwrap().name])),
+                    section_block(&format!("\n{}", localization.format(Label::TicketsCompleted, &[&ticket_summary.completed_tickets.len(), &ticket_summary.sprint_ticket_count, &active_sprint_context.as_ref().unwrap().total_days_elapsed(config.summary_timezone)]))),
+                    section_block(&format!("\n{}\n", scope_completed)),
+                    section_block(&format!("\n{}\n", cancel_message(localization, config))),
                 ],
-                    ticket_summary.into_slack_blocks(),
+                    ticket_summary.into_slack_blocks(localization, config),
                 vec![
                     project_scope_block,
                     board_link_block,
                 ]].concat())
             },
-            SprintCommand::SprintEnd | SprintCommand::SprintReview => {
-                let mut header = header_block(&format!("🎆 Sprint {} Review: {} - {}", active_sprint_context.as_ref().unwrap().name, active_sprint_context.as_ref().unwrap().start_date, active_sprint_context.as_ref().unwrap().end_date));
-                if self == &SprintCommand::SprintEnd {
-                    header = header_block(&format!("💥 Sprint {} ended early.", active_sprint_context.as_ref().unwrap().name));
-                }
+            SprintCommand::SprintEnd | SprintCommand::SprintReview | SprintCommand::SprintReviewPreview => {
+                let sprint = active_sprint_context.as_ref().unwrap();
+                //only /sprint-end is an early end, scheduled and manual reviews share the normal header
+                let header = match self {
+                    SprintCommand::SprintEnd => localization.format(Label::EndedEarlyHeader, &[&sprint.name]),
+                    SprintCommand::SprintReviewPreview => localization.format(Label::ReviewPreviewHeader, &[&sprint.name, &sprint.start_date, &sprint.end_date]),
+                    _ => localization.format(Label::ReviewHeader, &[&sprint.name, &sprint.start_date, &sprint.end_date]),
+                };
+                let header = header_block(&themed_header(header, sprint.theme_emoji.as_deref()));
                 
                 let completion_emoji = if (0.0..25.0).contains(&ticket_summary.completed_percentage) {
-                    "🐢 League Entrants"
+                    Label::TierLeagueEntrants
                 } else if (25.0..50.0).contains(&ticket_summary.completed_percentage) {
-                    "🥉 Local Competitors"
+                    Label::TierLocalCompetitors
                 } else if (50.0..65.0).contains(&ticket_summary.completed_percentage) {
-                    "🥈 Playoff Contenders"
+                    Label::TierPlayoffContenders
                 } else if (65.0..80.0).contains(&ticket_summary.completed_percentage) {
-                    "🥇 Division Leaders"
+                    Label::TierDivisionLeaders
                 } else if (80.0..90.0).contains(&ticket_summary.completed_percentage) {
-                    "🏅 Conference Finalists"
+                    Label::TierConferenceFinalists
                 } else if (90.0..100.0).contains(&ticket_summary.completed_percentage) {
-                    "🎖️ World Series Elites"
+                    Label::TierWorldSeriesElites
                 } else if ticket_summary.completed_percentage == 100.0 {
-                    "🏆 Sprint Champions!"
+                    Label::TierSprintChampions
                 } else {
-                    "⁉️ Disqualified?"
-                };                
+                    Label::TierDisqualified
+                };
 
                 Ok([vec![
                         header,
-                        section_block(&format!("\n*{}/{} tickets completed in {} days.*", ticket_summary.completed_tickets.len(), ticket_summary.sprint_ticket_count, active_sprint_context.as_ref().unwrap().sprint_length())),
-                        section_block(&format!("\n*{:.2}% of sprint scope completed.*\n", ticket_summary.completed_percentage)),
-                        header_block(completion_emoji),
+                        section_block(&format!("\n{}", localization.format(Label::TicketsCompleted, &[&ticket_summary.completed_tickets.len(), &ticket_summary.sprint_ticket_count, &active_sprint_context.as_ref().unwrap().sprint_length()]))),
+                        section_block(&format!("\n{}\n", scope_completed)),
+                    ],
+                    cumulative_sprint_contexts.velocity_trend(ticket_summary.completed_tickets.len() as u32, config.velocity_window, localization).into_iter()
+                        .map(|trend| section_block(&trend))
+                        .collect(),
+                    vec![
+                        header_block(&localization.text(completion_emoji)),
                     ],
-                    cumulative_sprint_contexts.into_slack_blocks(),
-                    ticket_summary.into_slack_blocks(),
+                    config.completion_chart_url.iter()
+                        .map(|chart_service_url| completion_chart_block(localization, chart_service_url, cumulative_sprint_contexts, Some((&active_sprint_context.as_ref().unwrap().name, ticket_summary.completed_percentage))))
+                        .collect::<Result<Vec<Value>>>()?,
+                    active_sprint_context.as_ref().unwrap().goal_text.iter()
+                        .filter(|_| config.review_include_goal)
+                        .map(|goal_text| section_block(&format!("{}\n{}", localization.format(Label::SprintGoal, &[goal_text]), localization.text(Label::GoalAchievedPrompt))))
+                        .collect(),
+                    ticket_summary.into_goal_checklist_block(localization).into_iter().collect(),
+                    cumulative_sprint_contexts.into_slack_blocks(localization),
+                    ticket_summary.into_review_slack_blocks(localization, config),
                     vec![
-                        section_block(&format!("\n{} this sprint.", count_difference(ticket_summary.open_ticket_count as i32, active_sprint_context.as_ref().unwrap().open_tickets_count_beginning as i32))),
-                        section_block(&format!("\n{} project scope.", count_difference(ticket_summary.sprint_ticket_count as i32, active_sprint_context.as_ref().unwrap().in_scope_tickets_count_beginning as i32))),
+                        section_block(&format!("\n{}", localization.format(Label::ThisSprint, &[&count_difference(localization, ticket_summary.open_ticket_count as i32, active_sprint_context.as_ref().unwrap().open_tickets_count_beginning as i32)]))),
+                        section_block(&format!("\n{}", localization.format(Label::ProjectScopeChange, &[&count_difference(localization, ticket_summary.sprint_ticket_count as i32, active_sprint_context.as_ref().unwrap().in_scope_tickets_count_beginning as i32)]))),
                         project_scope_block,
                         board_link_block
-                    ]]
+                    ],
+                    if self == &SprintCommand::SprintReviewPreview {
+                        vec![context_block(&localization.text(Label::ReviewPreviewNotSaved))]
+                    } else {
+                        vec![]
+                    }]
                     .concat()
                 )
             },
+            SprintCommand::SprintDefer { ticket_id } => {
+                Ok(vec![
+                    section_block(&localization.format(Label::TicketDeferred, &[ticket_id, &active_sprint_context.as_ref().unwrap().name])),
+                ])
+            },
+            SprintCommand::SprintMapUser { trello_id, slack_user_id } => {
+                Ok(vec![
+                    section_block(&localization.format(Label::MemberMapped, &[trello_id, slack_user_id])),
+                ])
+            },
+            SprintCommand::SprintExtend { new_end_date } => {
+                let active_sprint_context = active_sprint_context.as_ref().unwrap();
+                Ok(vec![
+                    section_block(&localization.format(Label::SprintExtended, &[&active_sprint_context.name, &active_sprint_context.end_date, new_end_date, &days_between(&print_current_date(config.summary_timezone), new_end_date)?])),
+                ])
+            },
+            SprintCommand::SprintBackfill => Ok(vec![header_block(&localization.text(Label::BackfillHeader))]),
+            SprintCommand::SprintVelocity { sprint_count } => {
+                Ok(vec![
+                    header_block(&localization.text(Label::VelocityHeader)),
+                    section_block(&cumulative_sprint_contexts.velocity_summary(*sprint_count, localization)),
+                ])
+            },
+            SprintCommand::SprintConfig => {
+                let settings_text = config.effective_settings().into_iter()
+                    .map(|(label, value)| format!("*{}:* {}", localization.text(label), value))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                Ok(vec![
+                    header_block(&localization.text(Label::ConfigHeader)),
+                    section_block(&settings_text),
+                ])
+            },
+            SprintCommand::SprintHygiene => {
+                Ok([
+                    vec![header_block(&localization.format(Label::HygieneHeader, &[&print_current_date(config.summary_timezone)]))],
+                    ticket_summary.into_hygiene_slack_blocks(localization, config),
+                    vec![board_link_block],
+                ].concat())
+            },
             SprintCommand::DailySummary => {
                 Ok([
                     vec![
-                        header_block(&format!("{} Daily Summary: {}", active_sprint_context.as_ref().unwrap().remaining_time_indicator(), print_current_date())),
-                        section_block(&format!("*{} tickets open* out of {}.\n*{} days* remain in sprint.", 
-                            ticket_summary.open_ticket_count, 
-                            ticket_summary.sprint_ticket_count, 
-                            active_sprint_context.as_ref().unwrap().days_until_end()
-                        )),
-                        section_block(&format!("\n*{:.2}% of sprint scope completed.*", ticket_summary.completed_percentage)),
+                        header_block(&themed_header(localization.format(Label::DailySummaryHeader, &[
+                            &active_sprint_context.as_ref().unwrap().daily_header_indicator(config.urgent_last_day_indicator, config.summary_timezone),
+                            &header_date(localization, active_sprint_context.as_ref().unwrap(), config.header_date_style, config.summary_timezone)?
+                        ]), active_sprint_context.as_ref().unwrap().theme_emoji.as_deref())),
+                        section_block(&localization.format(Label::TicketsOpen, &[
+                            &ticket_summary.open_ticket_count, 
+                            &ticket_summary.sprint_ticket_count, 
+                            &active_sprint_context.as_ref().unwrap().business_days_until_end(config.summary_timezone),
+                            &active_sprint_context.as_ref().unwrap().days_until_end(config.summary_timezone)
+                        ])),
+                    ],
+                    points_completed_blocks,
+                    vec![
+                        section_block(&format!("\n{}", scope_completed)),
                     ],
-                    ticket_summary.into_slack_blocks(),
+                    config.completion_chart_url.iter()
+                        .map(|chart_service_url| completion_chart_block(localization, chart_service_url, cumulative_sprint_contexts, Some((&active_sprint_context.as_ref().unwrap().name, ticket_summary.completed_percentage))))
+                        .collect::<Result<Vec<Value>>>()?,
+                    scope_change_alert(localization, ticket_summary.sprint_ticket_count, active_sprint_context.as_ref().unwrap().in_scope_tickets_count_beginning, config.scope_change_alert_percent).into_iter()
+                        .map(|alert| section_block(&alert))
+                        .collect(),
+                    if config.daily_newly_completed_only {
+                        ticket_summary.newly_completed_only(daily_ticket_contexts).into_slack_blocks(localization, config)
+                    } else {
+                        ticket_summary.into_slack_blocks(localization, config)
+                    },
                     vec![   
                         board_link_block,
-                        section_block(&format!("{} tickets left in project scope.", ticket_summary.project_ticket_count_in_scope)),
-                    ]
+                        project_scope_block,
+                    ],
+                    ticket_summary.label_breakdown().into_iter()
+                        .map(|breakdown| context_block(&breakdown))
+                        .collect(),
                 ].concat())
             }
+        };
+        let mut blocks = blocks?;
+
+        if config.show_ticket_legend && self.lists_tickets() {
+            blocks.push(context_block(&localization.format(Label::TicketLegend, &[&config.new_ticket_days])));
+        }
+
+        //the intro sits right under the header so the message still opens with the sprint name
+        if let Some(intro) = config.summary_intro.as_ref().filter(|_| config.summary_intro_commands.iter().any(|command| command == self.slash_command())) {
+            let position = if blocks.first().is_some_and(|block| block["type"] == "header") { 1 } else { 0 };
+            blocks.insert(position, context_block(intro));
+        }
+
+        Ok(blocks)
+    }
+
+    //the daily summary's blocked PRs, posted on their own to the engineering channel
+    pub fn blocked_prs_alert(&self, ticket_summary: &TicketSummary, active_sprint_context: &Option<ActiveSprintContext>, localization: &Localization, config: &Config) -> Option<(String, Vec<Value>)> {
+        let channel_id = config.blocked_prs_channel.as_ref()?;
+        if self != &SprintCommand::DailySummary {
+            return None;
         }
+
+        let sprint_name = active_sprint_context.as_ref().map_or("", |context| context.name.as_str());
+        let blocked_blocks = ticket_summary.into_blocked_slack_blocks(localization, config)?;
+
+        Some((channel_id.clone(), [
+            vec![header_block(&localization.format(Label::BlockedPrsAlertHeader, &[&sprint_name]))],
+            blocked_blocks,
+        ].concat()))
+    }
+
+    //the name the command is triggered by, used to pick which messages get the intro
+    fn slash_command(&self) -> &'static str {
+        match self {
+            SprintCommand::SprintPreview { .. } => "/sprint-preview",
+            SprintCommand::SprintKickoff { .. } => "/sprint-kickoff",
+            SprintCommand::SprintCheckIn { .. } => "/sprint-check-in",
+            SprintCommand::SprintStatus => "/sprint-status",
+            SprintCommand::SprintEnd => "/sprint-end",
+            SprintCommand::SprintCancel => "/sprint-cancel",
+            SprintCommand::DailySummary => "/daily-trigger",
+            SprintCommand::SprintReview => "/sprint-review",
+            SprintCommand::SprintReviewPreview => "/sprint-review-preview",
+            SprintCommand::SprintDefer { .. } => "/sprint-defer",
+            SprintCommand::SprintMapUser { .. } => "/sprint-map-user",
+            SprintCommand::SprintHygiene => "/sprint-hygiene",
+            SprintCommand::SprintBackfill => "/sprint-backfill",
+            SprintCommand::SprintExtend { .. } => "/sprint-extend",
+            SprintCommand::SprintConfig => "/sprint-config",
+            SprintCommand::SprintVelocity { .. } => "/sprint-velocity",
+        }
+    }
+
+    //these only read or change stored sprint state, so their summary is never fetched
+    pub fn fetches_tickets(&self) -> bool {
+        !matches!(self, SprintCommand::SprintDefer { .. } | SprintCommand::SprintMapUser { .. } | SprintCommand::SprintBackfill | SprintCommand::SprintExtend { .. } | SprintCommand::SprintConfig | SprintCommand::SprintVelocity { .. })
+    }
+
+    fn lists_tickets(&self) -> bool {
+        !matches!(self, SprintCommand::SprintStatus | SprintCommand::SprintDefer { .. } | SprintCommand::SprintMapUser { .. } | SprintCommand::SprintHygiene | SprintCommand::SprintBackfill | SprintCommand::SprintExtend { .. } | SprintCommand::SprintConfig | SprintCommand::SprintVelocity { .. })
     }
 }
 
 #[cfg(test)]
 mod sprint_event_message_generator_tests {
     use super::*;
+    use crate::utils::config::{TicketSource, DAILY_SUMMARY_TIME, SPRINT_REVIEW_TIME};
     use crate::{sprint_summary::sprint_records::mocks::MockSprintClient, utils::eventbridge::eventbridge_mocks::MockEventBridgeClient};
     use chrono_tz::US::Pacific;
-    use sprint_event_message_generator_tests::sprint_records::{ActiveSprintContextClient, DailyTicketContextClient};
-    use std::env;
+    use sprint_event_message_generator_tests::sprint_records::{ActiveSprintContextClient, CumulativeSprintContextClient, DailyTicketContextClient, PreviewCacheClient, PreviewMessageClient, ProcessingLockClient, SprintArchiveClient, SprintMemberClient};
     use tokio::runtime::Runtime;
     
     #[test]
@@ -303,7 +970,8 @@ mod sprint_event_message_generator_tests {
             end_date: (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(10).unwrap()).format("%m/%d/%y").to_string(),
             ..Default::default()
         };
-        assert_eq!(sprint_context.days_until_end(), 10);
+        assert_eq!(sprint_context.days_until_end(Pacific), 10);
+        assert!((6..=8).contains(&sprint_context.business_days_until_end(Pacific)));
     }
 
     #[test]
@@ -312,7 +980,7 @@ mod sprint_event_message_generator_tests {
             start_date: (chrono::Local::now().with_timezone(&Pacific) - chrono::Duration::try_days(5).unwrap()).format("%m/%d/%y").to_string(),
             ..Default::default()
         };
-        assert_eq!(sprint_context.total_days_elapsed(), 5);
+        assert_eq!(sprint_context.total_days_elapsed(Pacific), 5);
     }
     
     #[test]
@@ -332,14 +1000,59 @@ mod sprint_event_message_generator_tests {
             end_date: (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(10).unwrap()).format("%m/%d/%y").to_string(),
             ..Default::default()
         };
-        assert_eq!(sprint_context.remaining_time_indicator(), "🌓");
+        assert_eq!(sprint_context.remaining_time_indicator(Pacific), "🌓");
 
         let sprint_context_advanced = ActiveSprintContext {
             start_date: (chrono::Local::now().with_timezone(&Pacific) - chrono::Duration::try_days(30).unwrap()).format("%m/%d/%y").to_string(),
             end_date: chrono::Local::now().with_timezone(&Pacific).format("%m/%d/%y").to_string(),
             ..Default::default()
         };
-        assert_eq!(sprint_context_advanced.remaining_time_indicator(), "🌑");
+        assert_eq!(sprint_context_advanced.remaining_time_indicator(Pacific), "🌑");
+    }
+
+    #[test]
+    fn test_remaining_time_indicator_same_day_sprint() {
+        let today = chrono::Local::now().with_timezone(&Pacific).format("%m/%d/%y").to_string();
+        let sprint_context = ActiveSprintContext {
+            start_date: today.clone(),
+            end_date: today,
+            ..Default::default()
+        };
+        assert_eq!(sprint_context.remaining_time_indicator(Pacific), "🌑");
+    }
+
+    #[test]
+    fn test_remaining_time_indicator_past_end_date() {
+        let sprint_context = ActiveSprintContext {
+            start_date: (chrono::Local::now().with_timezone(&Pacific) - chrono::Duration::try_days(20).unwrap()).format("%m/%d/%y").to_string(),
+            end_date: (chrono::Local::now().with_timezone(&Pacific) - chrono::Duration::try_days(5).unwrap()).format("%m/%d/%y").to_string(),
+            ..Default::default()
+        };
+        assert_eq!(sprint_context.remaining_time_indicator(Pacific), "🌑");
+    }
+
+    #[test]
+    fn test_remaining_time_indicator_not_yet_started() {
+        let sprint_context = ActiveSprintContext {
+            start_date: (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(2).unwrap()).format("%m/%d/%y").to_string(),
+            end_date: (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(12).unwrap()).format("%m/%d/%y").to_string(),
+            ..Default::default()
+        };
+        assert_eq!(sprint_context.remaining_time_indicator(Pacific), "🌕");
+    }
+
+    #[test]
+    fn test_daily_header_indicator_on_last_days() {
+        let sprint_context = |days_left: i64| ActiveSprintContext {
+            start_date: (chrono::Local::now().with_timezone(&Pacific) - chrono::Duration::try_days(10).unwrap()).format("%m/%d/%y").to_string(),
+            end_date: (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(days_left).unwrap()).format("%m/%d/%y").to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(sprint_context(0).daily_header_indicator(false, Pacific), "🌑");
+        assert_eq!(sprint_context(0).daily_header_indicator(true, Pacific), "🚨");
+        assert_eq!(sprint_context(1).daily_header_indicator(true, Pacific), "⏳");
+        assert_eq!(sprint_context(10).daily_header_indicator(true, Pacific), "🌓");
     }
 
     fn test_runtime() -> Runtime {
@@ -357,62 +1070,214 @@ mod sprint_event_message_generator_tests {
             sprint_name: "My Sprint".to_string(),
             end_date: "12/31/23".to_string(),
             channel_id: "XYZ123".to_string(),
+            capacity: None,
+            preview_token: None,
+            theme_emoji: None,
+            goal_text: None,
         };
 
         rt.block_on(async {
-            let result = event.create_sprint_message(&ticket_summary, &Some(active_sprint_context), &cumulative_sprint_contexts, &daily_ticket_contexts).await.unwrap();
+            let result = event.create_sprint_message(&ticket_summary, &Some(active_sprint_context), &cumulative_sprint_contexts, &daily_ticket_contexts, &Localization::default(), &Config::default()).await.unwrap();
             assert!(result.iter().any(|block| block.to_string().contains("Sprint Preview")));
             assert!(result.iter().any(|block| block.to_string().contains("View sprint board")));
-            assert!(result.iter().any(|block| block.to_string().contains("tickets will be carried over from last sprint.")));
+            assert!(result.iter().any(|block| block.to_string().contains("1 ticket will be carried over from last sprint.")));
         });
     }
 
     #[test]
-    fn test_sprint_kickoff_saves_data() {
-        env::set_var("TRELLO_BOARD_ID", "TestBoardID");
+    fn test_carryover_line_phrasing() {
+        let localization = Localization::default();
+
+        assert_eq!(carryover_line(&localization, 0, false), Some("No tickets will be carried over from last sprint.".to_string()));
+        assert_eq!(carryover_line(&localization, 1, false), Some("1 ticket will be carried over from last sprint.".to_string()));
+        assert_eq!(carryover_line(&localization, 5, false), Some("5 tickets will be carried over from last sprint.".to_string()));
+    }
+
+    #[test]
+    fn test_carryover_line_hidden_when_empty() {
+        let localization = Localization::default();
+
+        assert_eq!(carryover_line(&localization, 0, true), None);
+        assert_eq!(carryover_line(&localization, 1, true), Some("1 ticket will be carried over from last sprint.".to_string()));
+        assert_eq!(carryover_line(&localization, 5, true), Some("5 tickets will be carried over from last sprint.".to_string()));
+    }
+
+    #[test]
+    fn test_sprint_preview_message_warns_when_over_capacity() {
         let rt = test_runtime();
-        let mut ticket_summary = TicketSummary::default();
-        let mut cumulative_sprint_contexts = CumulativeSprintContexts::default();
-        let mock_sprint_client = MockSprintClient::new(None, Some(cumulative_sprint_contexts.clone()), None);
-        let mock_notification_client = MockEventBridgeClient::new();
-        let end_date = (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(10).unwrap()).date_naive().format("%m/%d/%y").to_string();
-        let event = SprintCommand::SprintKickoff {
-            sprint_name: "New Sprint".to_string(),
-            end_date: end_date.clone(),
+        let ticket_summary = TicketSummary::default();
+        let event = SprintCommand::SprintPreview {
+            sprint_name: "My Sprint".to_string(),
+            end_date: "12/31/23".to_string(),
             channel_id: "XYZ123".to_string(),
+            capacity: Some(15),
+            preview_token: None,
+            theme_emoji: None,
+            goal_text: None,
         };
 
         rt.block_on(async {
-            let _ = event.save_sprint_state(&mut ticket_summary, &None, &mut cumulative_sprint_contexts, &mock_sprint_client, &mock_notification_client).await.unwrap();
-            assert_eq!(mock_sprint_client.get_sprint_data().await.unwrap().unwrap(), ActiveSprintContext { 
-                name: "New Sprint".to_string(), 
-                start_date: print_current_date(), 
-                end_date, 
-                channel_id: "XYZ123".to_string(), 
-                trello_board: "TestBoardID".to_string(), 
-                open_tickets_count_beginning: 20, 
-                in_scope_tickets_count_beginning: 15
-            });
+            let result = event.create_sprint_message(&ticket_summary, &None, &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &Config::default()).await.unwrap();
+            assert!(result.iter().any(|block| block.to_string().contains("Over capacity:* 20 tickets planned for a capacity of 15.")));
+            assert!(result.iter().any(|block| block.to_string().contains("12/31/23 My Sprint capacity=15")));
         });
     }
 
     #[test]
-    fn test_sprint_cancel_clears_data() {
+    fn test_sprint_preview_message_within_capacity() {
         let rt = test_runtime();
-        let mut ticket_summary = TicketSummary::default();
-        let mut cumulative_sprint_contexts = CumulativeSprintContexts::default();
-        let active_sprint_context = Some(ActiveSprintContext::default());
-        let mock_sprint_client = MockSprintClient::new(active_sprint_context.clone(), Some(cumulative_sprint_contexts.clone()), None);
-        let mock_notification_client = MockEventBridgeClient::new();
-        let event = SprintCommand::SprintCancel;
-
-        rt.block_on(async {
+        let ticket_summary = TicketSummary::default();
+        let event = SprintCommand::SprintPreview {
+            sprint_name: "My Sprint".to_string(),
+            end_date: "12/31/23".to_string(),
+            channel_id: "XYZ123".to_string(),
+            capacity: Some(25),
+            preview_token: None,
+            theme_emoji: None,
+            goal_text: None,
+        };
+
+        rt.block_on(async {
+            let result = event.create_sprint_message(&ticket_summary, &None, &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &Config::default()).await.unwrap();
+            assert!(!result.iter().any(|block| block.to_string().contains("Over capacity")));
+        });
+    }
+
+    #[test]
+    fn test_sprint_preview_caches_summary_for_kickoff() {
+        let config = Config { preview_cache_seconds: Some(600), ..Config::default() };
+        let rt = test_runtime();
+        let mut ticket_summary = TicketSummary::default();
+        let mock_sprint_client = MockSprintClient::new(None, None, None);
+        let mock_notification_client = MockEventBridgeClient::new();
+        let event = SprintCommand::SprintPreview {
+            sprint_name: "My Sprint".to_string(),
+            end_date: "12/31/23".to_string(),
+            channel_id: "XYZ123".to_string(),
+            capacity: None,
+            preview_token: Some("trigger123".to_string()),
+            theme_emoji: None,
+            goal_text: None,
+        };
+
+        rt.block_on(async {
+            let result = event.create_sprint_message(&ticket_summary, &None, &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &config).await.unwrap();
+            assert!(result.iter().any(|block| block.to_string().contains("12/31/23 My Sprint preview=trigger123")));
+
+            event.save_sprint_state(&mut ticket_summary, &None, &mut CumulativeSprintContexts::default(), &mock_sprint_client, &mock_notification_client, &config).await.unwrap();
+            let cached_summary = mock_sprint_client.take_preview_summary("trigger123", current_timestamp(), 600).await.unwrap();
+            assert_eq!(cached_summary.map(|summary| summary.open_ticket_count), Some(ticket_summary.open_ticket_count));
+            assert!(mock_sprint_client.get_sprint_data().await.unwrap().is_none());
+        });
+    }
+
+    #[test]
+    fn test_sprint_kickoff_saves_data() {
+        let rt = test_runtime();
+        let mut ticket_summary = TicketSummary::default();
+        let mut cumulative_sprint_contexts = CumulativeSprintContexts::default();
+        let mock_sprint_client = MockSprintClient::new(None, Some(cumulative_sprint_contexts.clone()), None);
+        let mock_notification_client = MockEventBridgeClient::new();
+        let end_date = (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(10).unwrap()).date_naive().format("%m/%d/%y").to_string();
+        let event = SprintCommand::SprintKickoff {
+            sprint_name: "New Sprint".to_string(),
+            end_date: end_date.clone(),
+            channel_id: "XYZ123".to_string(),
+            capacity: Some(18),
+            preview_token: None,
+            theme_emoji: None,
+            goal_text: None,
+        };
+
+        rt.block_on(async {
+            let _ = event.save_sprint_state(&mut ticket_summary, &None, &mut cumulative_sprint_contexts, &mock_sprint_client, &mock_notification_client, &Config::default()).await.unwrap();
+            assert_eq!(mock_sprint_client.get_sprint_data().await.unwrap().unwrap(), ActiveSprintContext { 
+                name: "New Sprint".to_string(), 
+                start_date: print_current_date(Pacific), 
+                end_date, 
+                channel_id: "XYZ123".to_string(), 
+                trello_board: "TestBoardID".to_string(), 
+                open_tickets_count_beginning: 20, 
+                in_scope_tickets_count_beginning: 15,
+                capacity: Some(18),
+                manual_deferrals: BTreeSet::new(),
+                theme_emoji: None,
+                goal_text: None,
+                canvas_id: None,
+                daily_summary_ts: None,
+                root_message_ts: None,
+            });
+            assert_eq!(mock_notification_client.rules_created.lock().await.get("New Sprint").map(String::as_str), Some(DAILY_SUMMARY_TIME));
+        });
+    }
+
+    #[test]
+    fn test_sprint_cancel_clears_data() {
+        let rt = test_runtime();
+        let mut ticket_summary = TicketSummary::default();
+        let mut cumulative_sprint_contexts = CumulativeSprintContexts::default();
+        let active_sprint_context = Some(ActiveSprintContext::default());
+        let mock_sprint_client = MockSprintClient::new(active_sprint_context.clone(), Some(cumulative_sprint_contexts.clone()), None);
+        let mock_notification_client = MockEventBridgeClient::new();
+        let event = SprintCommand::SprintCancel;
+
+        rt.block_on(async {
             let _ = mock_notification_client.create_daily_trigger_rule("Sprint 1", DAILY_SUMMARY_TIME).await;
-            let _ = event.save_sprint_state(&mut ticket_summary, &active_sprint_context.clone(), &mut cumulative_sprint_contexts, &mock_sprint_client, &mock_notification_client).await.unwrap();
+            let _ = event.save_sprint_state(&mut ticket_summary, &active_sprint_context.clone(), &mut cumulative_sprint_contexts, &mock_sprint_client, &mock_notification_client, &Config::default()).await.unwrap();
             assert_eq!(mock_sprint_client.get_sprint_data().await.unwrap(), None);
         });
     }
 
+    #[test]
+    fn test_sprint_defer_records_manual_deferral() {
+        let rt = test_runtime();
+        let mut ticket_summary = TicketSummary::default();
+        let active_sprint_context = Some(ActiveSprintContext::default());
+        let mock_sprint_client = MockSprintClient::new(active_sprint_context.clone(), None, None);
+        let mock_notification_client = MockEventBridgeClient::new();
+        let event = SprintCommand::SprintDefer { ticket_id: "abc123".to_string() };
+
+        rt.block_on(async {
+            let _ = event.save_sprint_state(&mut ticket_summary, &active_sprint_context, &mut CumulativeSprintContexts::default(), &mock_sprint_client, &mock_notification_client, &Config::default()).await.unwrap();
+            let manual_deferrals = mock_sprint_client.get_sprint_data().await.unwrap().unwrap().manual_deferrals;
+            assert_eq!(manual_deferrals, BTreeSet::from(["abc123".to_string()]));
+        });
+    }
+
+    #[test]
+    fn test_archived_channel_pauses_daily_summary() {
+        let rt = test_runtime();
+        let active_sprint_context = Some(ActiveSprintContext::default());
+        let mock_notification_client = MockEventBridgeClient::new();
+        let send_error = anyhow::Error::new(SlackApiError { error: "is_archived".to_string() });
+
+        rt.block_on(async {
+            let _ = mock_notification_client.create_daily_trigger_rule("Sprint 1", DAILY_SUMMARY_TIME).await;
+            let paused = SprintCommand::DailySummary.pause_on_unavailable_channel(&send_error, &active_sprint_context, &mock_notification_client).await.unwrap();
+
+            assert!(paused);
+            assert_eq!(*mock_notification_client.rules_disabled.lock().await, vec!["Sprint 1".to_string()]);
+        });
+    }
+
+    #[test]
+    fn test_other_send_errors_do_not_pause_daily_summary() {
+        let rt = test_runtime();
+        let active_sprint_context = Some(ActiveSprintContext::default());
+        let mock_notification_client = MockEventBridgeClient::new();
+        let send_error = anyhow::Error::new(SlackApiError { error: "rate_limited".to_string() });
+
+        rt.block_on(async {
+            let _ = mock_notification_client.create_daily_trigger_rule("Sprint 1", DAILY_SUMMARY_TIME).await;
+            let paused = SprintCommand::DailySummary.pause_on_unavailable_channel(&send_error, &active_sprint_context, &mock_notification_client).await.unwrap();
+            let check_in_paused = SprintCommand::SprintCheckIn { force: false }.pause_on_unavailable_channel(&anyhow::Error::new(SlackApiError { error: "is_archived".to_string() }), &active_sprint_context, &mock_notification_client).await.unwrap();
+
+            assert!(!paused);
+            assert!(!check_in_paused, "Only scheduled summaries should pause the sprint");
+            assert!(mock_notification_client.rules_disabled.lock().await.is_empty());
+        });
+    }
+
     #[test]
     fn test_daily_summary_saves_ticket_data() {
         let rt = test_runtime();
@@ -426,7 +1291,7 @@ mod sprint_event_message_generator_tests {
         let event = SprintCommand::DailySummary;
 
         rt.block_on(async {
-            let _ = event.save_sprint_state(&mut ticket_summary, &Some(active_sprint_context), &mut CumulativeSprintContexts::default(), &mock_sprint_client, &mock_notification_client).await.unwrap();
+            let _ = event.save_sprint_state(&mut ticket_summary, &Some(active_sprint_context), &mut CumulativeSprintContexts::default(), &mock_sprint_client, &mock_notification_client, &Config::default()).await.unwrap();
 
             let ticket_data = mock_sprint_client.get_ticket_data().await.unwrap();
 
@@ -449,7 +1314,7 @@ mod sprint_event_message_generator_tests {
         rt.block_on(async {
             let name = &active_sprint_context.name.clone();
             let _ = mock_notification_client.create_daily_trigger_rule(name, DAILY_SUMMARY_TIME).await;
-            let _ = action.save_sprint_state(&mut ticket_summary, &Some(active_sprint_context), &mut CumulativeSprintContexts::default(), &mock_sprint_client, &mock_notification_client).await.unwrap();
+            let _ = action.save_sprint_state(&mut ticket_summary, &Some(active_sprint_context), &mut CumulativeSprintContexts::default(), &mock_sprint_client, &mock_notification_client, &Config::default()).await.unwrap();
             assert!(mock_notification_client.rules_created.lock().await.get(name) == Some(&SPRINT_REVIEW_TIME.to_string()));
         });
     }
@@ -457,7 +1322,6 @@ mod sprint_event_message_generator_tests {
     #[test]
     fn test_sprint_review_clears_current_sprint_data() {
         let rt = test_runtime();
-        env::set_var("TRELLO_BOARD_ID", "TestBoardID");
         let mut ticket_summary = TicketSummary::default();
         let active_sprint_context = ActiveSprintContext::default();
         let mut cumulative_sprint_contexts = CumulativeSprintContexts::default();
@@ -468,7 +1332,7 @@ mod sprint_event_message_generator_tests {
         rt.block_on(async {
             let name = &active_sprint_context.name.clone();
             let _ = mock_notification_client.create_daily_trigger_rule(name, DAILY_SUMMARY_TIME).await;
-            let _ = action.save_sprint_state( &mut ticket_summary,&Some(active_sprint_context),&mut cumulative_sprint_contexts, &mock_sprint_client,&mock_notification_client).await.unwrap();
+            let _ = action.save_sprint_state( &mut ticket_summary,&Some(active_sprint_context),&mut cumulative_sprint_contexts, &mock_sprint_client,&mock_notification_client, &Config::default()).await.unwrap();
             assert!(mock_sprint_client.get_sprint_data().await.unwrap().is_none());        
             assert!(mock_notification_client.rules_deleted.lock().await.contains(name));
         });
@@ -477,7 +1341,6 @@ mod sprint_event_message_generator_tests {
     #[test]
     fn test_sprint_review_message() {
         let rt = test_runtime();
-        env::set_var("TRELLO_BOARD_ID", "TestBoardID");
         let ticket_summary = TicketSummary::default();
         let cumulative_sprint_contexts = CumulativeSprintContexts::default();
         let daily_ticket_contexts = DailyTicketContexts::default();
@@ -489,7 +1352,7 @@ mod sprint_event_message_generator_tests {
         active_sprint_context.end_date = "06/11/24".to_string();
 
         rt.block_on(async {
-            let result = action.create_sprint_message(&ticket_summary, &Some(active_sprint_context), &cumulative_sprint_contexts, &daily_ticket_contexts).await.unwrap();
+            let result = action.create_sprint_message(&ticket_summary, &Some(active_sprint_context), &cumulative_sprint_contexts, &daily_ticket_contexts, &Localization::default(), &Config::default()).await.unwrap();
             assert!(result.iter().any(|block| block.to_string().contains("Sprint 21-Pascal Review: 05/28/24 - 06/11/24")));
             assert!(result.iter().any(|block| block.to_string().contains("completed in 14 days.")));
             assert!(result.iter().any(|block| block.to_string().contains("% of sprint scope completed.")));
@@ -497,6 +1360,124 @@ mod sprint_event_message_generator_tests {
         });
     }
 
+    #[test]
+    fn test_manual_review_header_differs_from_early_end() {
+        let rt = test_runtime();
+        let active_sprint_context = ActiveSprintContext {
+            name: "21-Pascal".to_string(),
+            start_date: "05/28/24".to_string(),
+            end_date: "06/11/24".to_string(),
+            ..ActiveSprintContext::default()
+        };
+
+        rt.block_on(async {
+            let review = SprintCommand::SprintReview.create_sprint_message(&TicketSummary::default(), &Some(active_sprint_context.clone()), &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &Config::default()).await.unwrap();
+            let early_end = SprintCommand::SprintEnd.create_sprint_message(&TicketSummary::default(), &Some(active_sprint_context), &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &Config::default()).await.unwrap();
+
+            assert!(review[0].to_string().contains("Sprint 21-Pascal Review: 05/28/24 - 06/11/24"));
+            assert!(!review[0].to_string().contains("ended early"));
+            assert!(early_end[0].to_string().contains("Sprint 21-Pascal ended early."));
+            assert_ne!(review[0], early_end[0]);
+        });
+    }
+
+    #[test]
+    fn test_sprint_review_preview_message_does_not_clear_sprint_data() {
+        let rt = test_runtime();
+        let mut ticket_summary = TicketSummary::default();
+        let mut cumulative_sprint_contexts = CumulativeSprintContexts::default();
+        let daily_ticket_contexts = DailyTicketContexts::default();
+        let action = SprintCommand::SprintReviewPreview;
+
+        let mut active_sprint_context = ActiveSprintContext::default();
+        active_sprint_context.name = "21-Pascal".to_string();
+        active_sprint_context.start_date = "05/28/24".to_string();
+        active_sprint_context.end_date = "06/11/24".to_string();
+
+        let mock_sprint_client = MockSprintClient::new(Some(active_sprint_context.clone()), Some(cumulative_sprint_contexts.clone()), None);
+        let mock_notification_client = MockEventBridgeClient::new();
+
+        rt.block_on(async {
+            let result = action.create_sprint_message(&ticket_summary, &Some(active_sprint_context.clone()), &cumulative_sprint_contexts, &daily_ticket_contexts, &Localization::default(), &Config::default()).await.unwrap();
+            assert!(result.iter().any(|block| block.to_string().contains("Sprint 21-Pascal Review Preview: 05/28/24 - 06/11/24")));
+            assert!(result.iter().any(|block| block.to_string().contains("completed in 14 days.")));
+            assert!(result.iter().any(|block| block.to_string().contains("Previous Sprints:")));
+            assert!(result.iter().any(|block| block.to_string().contains("Nothing has been saved")));
+
+            let history_count = cumulative_sprint_contexts.history.len();
+            action.save_sprint_state(&mut ticket_summary, &Some(active_sprint_context.clone()), &mut cumulative_sprint_contexts, &mock_sprint_client, &mock_notification_client, &Config::default()).await.unwrap();
+            assert_eq!(mock_sprint_client.get_sprint_data().await.unwrap(), Some(active_sprint_context));
+            assert_eq!(cumulative_sprint_contexts.history.len(), history_count);
+            assert!(mock_notification_client.rules_deleted.lock().await.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_fallback_summary_text() {
+        let mut ticket_summary = TicketSummary::default();
+        ticket_summary.open_ticket_count = 3;
+        ticket_summary.sprint_ticket_count = 8;
+        ticket_summary.completed_percentage = 62.5;
+
+        let text = fallback_summary_text(&Localization::default(), &ticket_summary, &Config::default());
+
+        assert!(text.contains("3 tickets open out of 8"));
+        assert!(text.contains("62.50% of sprint scope completed"));
+        assert!(text.contains("<https://trello.com/b/TestBoardID|"));
+
+        let unconfigured_text = fallback_summary_text(&Localization::default(), &ticket_summary, &Config { trello_board_id: String::new(), ..Config::default() });
+        assert!(unconfigured_text.ends_with("62.50% of sprint scope completed."));
+
+        let github_config = Config { ticket_source: TicketSource::GithubIssues, github_issues_repo: Some("tifapp/FitnessProject".to_string()), ..Config::default() };
+        assert!(fallback_summary_text(&Localization::default(), &ticket_summary, &github_config).contains("<https://github.com/tifapp/FitnessProject/issues|"));
+    }
+
+    #[test]
+    fn test_points_completed_only_for_estimated_sprints() {
+        let localization = Localization::default();
+        let mut ticket_summary = TicketSummary::default();
+        assert_eq!(points_completed(&localization, &ticket_summary), None);
+
+        ticket_summary.completed_points = 5;
+        ticket_summary.total_points = 13;
+        assert_eq!(points_completed(&localization, &ticket_summary), Some("*5/13 points completed.*".to_string()));
+    }
+
+    #[test]
+    fn test_scope_change_alert_crossing_threshold() {
+        let localization = Localization::default();
+
+        assert_eq!(scope_change_alert(&localization, 13, 10, Some(25.0)), Some("🚨 Sprint scope has grown 30% since kickoff (10 → 13 tickets).".to_string()));
+        assert_eq!(scope_change_alert(&localization, 12, 10, Some(25.0)), None);
+        assert_eq!(scope_change_alert(&localization, 8, 10, Some(0.0)), None);
+    }
+
+    #[test]
+    fn test_daily_summary_message_includes_scope_change_alert() {
+        let rt = test_runtime();
+        let config = Config { scope_change_alert_percent: Some(20.0), ..Config::default() };
+        let mut ticket_summary = TicketSummary::default();
+        ticket_summary.sprint_ticket_count = 15;
+        let active_sprint_context = ActiveSprintContext {
+            end_date: (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(5).unwrap()).format("%m/%d/%y").to_string(),
+            in_scope_tickets_count_beginning: 10,
+            ..ActiveSprintContext::default()
+        };
+
+        rt.block_on(async {
+            let result = SprintCommand::DailySummary.create_sprint_message(&ticket_summary, &Some(active_sprint_context), &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &config).await.unwrap();
+            assert!(result.iter().any(|block| block.to_string().contains("Sprint scope has grown 50% since kickoff")));
+        });
+    }
+
+    #[test]
+    fn test_scope_change_alert_unconfigured_or_empty_sprint() {
+        let localization = Localization::default();
+
+        assert_eq!(scope_change_alert(&localization, 20, 10, None), None);
+        assert_eq!(scope_change_alert(&localization, 5, 0, Some(10.0)), None);
+    }
+
     #[test]
     fn test_daily_summary_message() {
         let rt = test_runtime();
@@ -505,15 +1486,953 @@ mod sprint_event_message_generator_tests {
             end_date: (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(5).unwrap()).format("%m/%d/%y").to_string(),
             ..ActiveSprintContext::default()
         };
+        let business_days_left = active_sprint_context.business_days_until_end(Pacific);
         let cumulative_sprint_contexts = CumulativeSprintContexts::default();
         let event = SprintCommand::DailySummary;
         let daily_ticket_contexts = DailyTicketContexts::default();
 
         rt.block_on(async {
-            let result = event.create_sprint_message(&ticket_summary, &Some(active_sprint_context), &cumulative_sprint_contexts, &daily_ticket_contexts).await.unwrap();
+            let result = event.create_sprint_message(&ticket_summary, &Some(active_sprint_context), &cumulative_sprint_contexts, &daily_ticket_contexts, &Localization::default(), &Config::default()).await.unwrap();
             assert!(result.iter().any(|block| block.to_string().contains("Daily Summary")));
             assert!(result.iter().any(|block| block.to_string().contains("tickets open* out of")));
-            assert!(result.iter().any(|block| block.to_string().contains("5 days* remain in sprint.")));
+            assert!(result.iter().any(|block| block.to_string().contains(&format!("{} working days* remain in sprint (5 calendar days).", business_days_left))));
+        });
+    }
+
+    #[test]
+    fn test_sprint_map_user_adds_member_mapping() {
+        let rt = test_runtime();
+        let mut ticket_summary = TicketSummary::default();
+        let mock_sprint_client = MockSprintClient::new(None, None, None);
+        let mock_notification_client = MockEventBridgeClient::new();
+        let event = SprintCommand::SprintMapUser { trello_id: "trello_user3".to_string(), slack_user_id: "U0123ABC".to_string() };
+
+        rt.block_on(async {
+            event.save_sprint_state(&mut ticket_summary, &None, &mut CumulativeSprintContexts::default(), &mock_sprint_client, &mock_notification_client, &Config::default()).await.unwrap();
+
+            let sprint_members = mock_sprint_client.get_sprint_members().await.unwrap().unwrap();
+            assert_eq!(sprint_members.get("trello_user3").map(String::as_str), Some("U0123ABC"));
+            assert_eq!(sprint_members.get("trello_user1").map(String::as_str), Some("slack_user1"));
+
+            let result = event.create_sprint_message(&ticket_summary, &None, &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &Config::default()).await.unwrap();
+            assert!(result[0].to_string().contains("Trello member trello_user3 is now mapped to <@U0123ABC>."));
+        });
+    }
+
+    #[test]
+    fn test_relative_end_date() {
+        let localization = Localization::default();
+
+        assert_eq!(relative_end_date(&localization, 3), "ends in 3 days");
+        assert_eq!(relative_end_date(&localization, 1), "ends tomorrow");
+        assert_eq!(relative_end_date(&localization, 0), "ends today");
+        assert_eq!(relative_end_date(&localization, -1), "ended yesterday");
+        assert_eq!(relative_end_date(&localization, -4), "ended 4 days ago");
+    }
+
+    #[test]
+    fn test_daily_summary_header_with_relative_date() {
+        let rt = test_runtime();
+        let ticket_summary = TicketSummary::default();
+        let active_sprint_context = ActiveSprintContext {
+            end_date: (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(3).unwrap()).format("%m/%d/%y").to_string(),
+            ..ActiveSprintContext::default()
+        };
+
+        rt.block_on(async {
+            let config = Config { header_date_style: HeaderDateStyle::Relative, ..Config::default() };
+            let result = SprintCommand::DailySummary.create_sprint_message(&ticket_summary, &Some(active_sprint_context.clone()), &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &config).await.unwrap();
+            assert!(result[0].to_string().contains("Daily Summary: ends in 3 days"));
+
+            let config = Config { header_date_style: HeaderDateStyle::Both, ..Config::default() };
+            let result = SprintCommand::DailySummary.create_sprint_message(&ticket_summary, &Some(active_sprint_context), &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &config).await.unwrap();
+            assert!(result[0].to_string().contains(&format!("Daily Summary: {} · ends in 3 days", print_current_date(Pacific))));
+        });
+    }
+
+    #[test]
+    fn test_daily_summary_intro() {
+        let rt = test_runtime();
+        let ticket_summary = TicketSummary::default();
+        let active_sprint_context = Some(ActiveSprintContext {
+            end_date: (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(3).unwrap()).format("%m/%d/%y").to_string(),
+            ..ActiveSprintContext::default()
+        });
+        let intro = "Standup at 10 in <https://meet.example.com/standup|the usual room>";
+
+        rt.block_on(async {
+            let config = Config { summary_intro: Some(intro.to_string()), ..Config::default() };
+            let result = SprintCommand::DailySummary.create_sprint_message(&ticket_summary, &active_sprint_context, &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &config).await.unwrap();
+            assert_eq!(result[0]["type"], "header");
+            assert_eq!(result[1], context_block(intro));
+
+            //only the configured commands get the intro
+            let result = SprintCommand::SprintStatus.create_sprint_message(&ticket_summary, &active_sprint_context, &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &config).await.unwrap();
+            assert!(!result.contains(&context_block(intro)));
+
+            let result = SprintCommand::DailySummary.create_sprint_message(&ticket_summary, &active_sprint_context, &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &Config::default()).await.unwrap();
+            assert!(!serde_json::to_string(&result).unwrap().contains("Standup"));
+        });
+    }
+
+    #[test]
+    fn test_daily_summary_lists_only_newly_completed_tickets() {
+        use self::ticket::{Ticket, TicketDetails};
+        use self::ticket_state::TicketState;
+        use self::sprint_records::DailyTicketContext;
+
+        let rt = test_runtime();
+        let done_ticket = |name: &str| Ticket {
+            details: TicketDetails { id: name.to_string(), name: name.to_string(), state: TicketState::Done, ..TicketDetails::default() },
+            ..Ticket::default()
+        };
+        let ticket_summary = TicketSummary::from_tickets(vec![done_ticket("Finished Last Week"), done_ticket("Finished Today")], false);
+        let daily_ticket_contexts = DailyTicketContexts {
+            tickets: vec![
+                DailyTicketContext { id: "Finished Last Week".to_string(), state: TicketState::Done, ..DailyTicketContext::default() },
+                DailyTicketContext { id: "Finished Today".to_string(), state: TicketState::InProgress, ..DailyTicketContext::default() },
+            ].into(),
+        };
+        let active_sprint_context = ActiveSprintContext {
+            end_date: (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(5).unwrap()).format("%m/%d/%y").to_string(),
+            ..ActiveSprintContext::default()
+        };
+
+        rt.block_on(async {
+            let config = Config { daily_newly_completed_only: true, ..Config::default() };
+            let result = SprintCommand::DailySummary.create_sprint_message(&ticket_summary, &Some(active_sprint_context.clone()), &CumulativeSprintContexts::default(), &daily_ticket_contexts, &Localization::default(), &config).await.unwrap();
+            let message = serde_json::to_string(&result).unwrap();
+            assert!(message.contains("Finished Today"));
+            assert!(!message.contains("Finished Last Week"));
+
+            let result = SprintCommand::DailySummary.create_sprint_message(&ticket_summary, &Some(active_sprint_context), &CumulativeSprintContexts::default(), &daily_ticket_contexts, &Localization::default(), &Config::default()).await.unwrap();
+            assert!(serde_json::to_string(&result).unwrap().contains("Finished Last Week"));
+        });
+    }
+
+    #[test]
+    fn test_theme_emoji_replaces_header_emoji() {
+        assert_eq!(themed_header("🎆 Sprint Pascal Review: 04/01/24 - 04/14/24".to_string(), Some("🦄")), "🦄 Sprint Pascal Review: 04/01/24 - 04/14/24");
+        assert_eq!(themed_header("Sprint Pascal Review".to_string(), Some(":unicorn_face:")), ":unicorn_face: Sprint Pascal Review");
+        assert_eq!(themed_header("🎆 Sprint Pascal Review".to_string(), None), "🎆 Sprint Pascal Review");
+    }
+
+    #[test]
+    fn test_theme_emoji_in_daily_and_review_headers() {
+        let rt = test_runtime();
+        let ticket_summary = TicketSummary::default();
+        let active_sprint_context = ActiveSprintContext {
+            end_date: (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(5).unwrap()).format("%m/%d/%y").to_string(),
+            theme_emoji: Some("🦄".to_string()),
+            ..ActiveSprintContext::default()
+        };
+
+        rt.block_on(async {
+            let result = SprintCommand::DailySummary.create_sprint_message(&ticket_summary, &Some(active_sprint_context.clone()), &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &Config::default()).await.unwrap();
+            assert_eq!(result[0]["text"]["text"], format!("🦄 Daily Summary: {}", print_current_date(Pacific)));
+
+            let result = SprintCommand::SprintReview.create_sprint_message(&ticket_summary, &Some(active_sprint_context.clone()), &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &Config::default()).await.unwrap();
+            assert_eq!(result[0]["text"]["text"], format!("🦄 Sprint Sprint 1 Review: 02/20/23 - {}", active_sprint_context.end_date));
+        });
+    }
+
+    #[test]
+    fn test_last_day_daily_header_uses_urgent_indicator() {
+        let rt = test_runtime();
+        let config = Config { urgent_last_day_indicator: true, ..Config::default() };
+        let active_sprint_context = ActiveSprintContext {
+            end_date: print_current_date(Pacific),
+            ..ActiveSprintContext::default()
+        };
+
+        rt.block_on(async {
+            let result = SprintCommand::DailySummary.create_sprint_message(&TicketSummary::default(), &Some(active_sprint_context.clone()), &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &config).await.unwrap();
+            assert_eq!(result[0]["text"]["text"], format!("🚨 Daily Summary: {}", print_current_date(Pacific)));
+
+            let result = SprintCommand::DailySummary.create_sprint_message(&TicketSummary::default(), &Some(active_sprint_context), &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &Config::default()).await.unwrap();
+            assert_eq!(result[0]["text"]["text"], format!("🌑 Daily Summary: {}", print_current_date(Pacific)));
+        });
+    }
+
+    #[test]
+    fn test_ticket_legend_shown_once_when_enabled() {
+        let rt = test_runtime();
+        let active_sprint_context = ActiveSprintContext {
+            end_date: (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(5).unwrap()).format("%m/%d/%y").to_string(),
+            ..ActiveSprintContext::default()
+        };
+        let legend = context_block(&Localization::default().format(Label::TicketLegend, &[&2]));
+
+        rt.block_on(async {
+            let config = Config { show_ticket_legend: true, ..Config::default() };
+            let result = SprintCommand::DailySummary.create_sprint_message(&TicketSummary::default(), &Some(active_sprint_context.clone()), &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &config).await.unwrap();
+            assert_eq!(result.iter().filter(|block| **block == legend).count(), 1);
+
+            let result = SprintCommand::DailySummary.create_sprint_message(&TicketSummary::default(), &Some(active_sprint_context), &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &Config::default()).await.unwrap();
+            assert!(!result.contains(&legend));
+        });
+    }
+
+    #[test]
+    fn test_sprint_review_archives_completed_tickets() {
+        use self::ticket::{Ticket, TicketDetails};
+        use self::ticket_state::TicketState;
+
+        let rt = test_runtime();
+        let mut ticket_summary = TicketSummary::from_tickets(vec![
+            Ticket { details: TicketDetails { id: "done1".to_string(), state: TicketState::Done, ..TicketDetails::default() }, ..Ticket::default() },
+            Ticket { details: TicketDetails { id: "open1".to_string(), state: TicketState::InProgress, ..TicketDetails::default() }, ..Ticket::default() },
+        ], false);
+        let active_sprint_context = ActiveSprintContext::default();
+        let mock_sprint_client = MockSprintClient::new(Some(active_sprint_context.clone()), None, None);
+        let mock_notification_client = MockEventBridgeClient::new();
+
+        rt.block_on(async {
+            let _ = mock_notification_client.create_daily_trigger_rule("Sprint 1", DAILY_SUMMARY_TIME).await;
+            SprintCommand::SprintReview.save_sprint_state(&mut ticket_summary, &Some(active_sprint_context), &mut CumulativeSprintContexts { history: vec![] }, &mock_sprint_client, &mock_notification_client, &Config::default()).await.unwrap();
+
+            let archive = mock_sprint_client.get_sprint_archive("Sprint 1").await.unwrap().unwrap();
+            assert_eq!(archive.sprint_ticket_count, 2);
+            assert_eq!(archive.completed_tickets.iter().map(|ticket| ticket.id.as_str()).collect::<Vec<_>>(), vec!["done1"]);
+        });
+    }
+
+    #[test]
+    fn test_sprint_backfill_rewrites_history_from_archives() {
+        let rt = test_runtime();
+        let mock_sprint_client = MockSprintClient::new(None, None, None);
+        let mock_notification_client = MockEventBridgeClient::new();
+        let mut cumulative_sprint_contexts = CumulativeSprintContexts::default();
+
+        rt.block_on(async {
+            mock_sprint_client.put_sprint_archive(&SprintArchive {
+                name: "Sprint 101".to_string(),
+                sprint_ticket_count: 4,
+                completed_tickets: vec![DailyTicketContext::default()],
+            }).await.unwrap();
+
+            SprintCommand::SprintBackfill.save_sprint_state(&mut TicketSummary::default(), &None, &mut cumulative_sprint_contexts, &mock_sprint_client, &mock_notification_client, &Config::default()).await.unwrap();
+
+            let history = mock_sprint_client.get_historical_data().await.unwrap().unwrap().history;
+            assert_eq!(history.iter().map(|record| record.percent_complete).collect::<Vec<_>>(), vec![0.9, 25.0, 0.9]);
+            assert_eq!(history[1].completed_tickets_count, 1);
+        });
+    }
+
+    #[test]
+    fn test_only_ticket_commands_fetch_tickets() {
+        assert!(SprintCommand::DailySummary.fetches_tickets());
+        assert!(SprintCommand::SprintStatus.fetches_tickets());
+        assert!(SprintCommand::SprintHygiene.fetches_tickets());
+        assert!(!SprintCommand::SprintMapUser { trello_id: "trello123".to_string(), slack_user_id: "U123".to_string() }.fetches_tickets());
+        assert!(!SprintCommand::SprintConfig.fetches_tickets());
+        assert!(!SprintCommand::SprintVelocity { sprint_count: 3 }.fetches_tickets());
+    }
+
+    #[test]
+    fn test_sprint_backfill_reports_recomputed_sprints() {
+        let rt = test_runtime();
+        let mock_sprint_client = MockSprintClient::new(None, None, None);
+        let mock_notification_client = MockEventBridgeClient::new();
+        let mut cumulative_sprint_contexts = CumulativeSprintContexts::default();
+
+        rt.block_on(async {
+            mock_sprint_client.put_sprint_archive(&SprintArchive {
+                name: "Sprint 101".to_string(),
+                sprint_ticket_count: 4,
+                completed_tickets: vec![DailyTicketContext::default()],
+            }).await.unwrap();
+
+            let outcome = SprintCommand::SprintBackfill.execute("C123456", CommandContext {
+                ticket_summary: &mut TicketSummary::default(),
+                active_sprint_context: &None,
+                cumulative_sprint_contexts: &mut cumulative_sprint_contexts,
+                daily_ticket_contexts: &DailyTicketContexts::default(),
+            }, &Localization::default(), &mock_sprint_client, &mock_notification_client, &Config::default()).await.unwrap();
+
+            assert_eq!(outcome.blocks, vec![
+                header_block("♻️ Sprint History Backfill"),
+                section_block("Recomputed completion for 1 of 3 past sprints from their archives. Sprints without an archive keep the percentage recorded when they ended."),
+            ]);
+            assert_eq!(outcome.state_changes, vec![StateChange::HistoricalData, StateChange::HistoryBackfilled { backfilled_count: 1, sprint_count: 3 }]);
+        });
+    }
+
+    #[test]
+    fn test_review_recap_includes_sprint_goal() {
+        let rt = test_runtime();
+        let active_sprint_context = ActiveSprintContext {
+            end_date: "03/03/23".to_string(),
+            goal_text: Some("Ship the new onboarding flow".to_string()),
+            ..ActiveSprintContext::default()
+        };
+        let goal_recap = section_block("🎯 *Sprint goal:* Ship the new onboarding flow\nWas the goal achieved?");
+
+        rt.block_on(async {
+            let config = Config { review_include_goal: true, ..Config::default() };
+            let result = SprintCommand::SprintReview.create_sprint_message(&TicketSummary::default(), &Some(active_sprint_context.clone()), &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &config).await.unwrap();
+            assert_eq!(result[5], goal_recap);
+
+            let result = SprintCommand::SprintReview.create_sprint_message(&TicketSummary::default(), &Some(active_sprint_context), &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &Config::default()).await.unwrap();
+            assert!(!result.contains(&goal_recap));
+        });
+    }
+
+    #[test]
+    fn test_review_goal_checklist_with_one_goal_done() {
+        use self::ticket::{Ticket, TicketDetails};
+        use self::ticket_label::TicketLabel;
+        use self::ticket_state::TicketState;
+
+        let rt = test_runtime();
+        let goal_ticket = |name: &str, state: TicketState| Ticket {
+            details: TicketDetails { name: name.to_string(), url: format!("https://trello.com/c/{}", name), labels: vec![TicketLabel::Goal], state, ..TicketDetails::default() },
+            ..Ticket::default()
+        };
+        let ticket_summary = TicketSummary::from_tickets(vec![
+            goal_ticket("onboarding", TicketState::Done),
+            goal_ticket("payments", TicketState::InProgress),
+            Ticket::default(),
+        ], false);
+        let active_sprint_context = ActiveSprintContext { end_date: "03/03/23".to_string(), ..ActiveSprintContext::default() };
+
+        rt.block_on(async {
+            let result = SprintCommand::SprintReview.create_sprint_message(&ticket_summary, &Some(active_sprint_context), &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &Config::default()).await.unwrap();
+
+            assert!(result.contains(&section_block("*Goals:*\n✅ <https://trello.com/c/onboarding|onboarding>\n❌ <https://trello.com/c/payments|payments>")));
+        });
+    }
+
+    #[test]
+    fn test_sprint_kickoff_stores_goal_text() {
+        let rt = test_runtime();
+        let mut ticket_summary = TicketSummary::default();
+        let mock_sprint_client = MockSprintClient::new(None, None, None);
+        let mock_notification_client = MockEventBridgeClient::new();
+        let event = SprintCommand::SprintKickoff {
+            sprint_name: "My Sprint".to_string(),
+            end_date: "12/31/23".to_string(),
+            channel_id: "XYZ123".to_string(),
+            capacity: None,
+            preview_token: None,
+            theme_emoji: None,
+            goal_text: Some("Ship the new onboarding flow".to_string()),
+        };
+
+        rt.block_on(async {
+            event.save_sprint_state(&mut ticket_summary, &None, &mut CumulativeSprintContexts::default(), &mock_sprint_client, &mock_notification_client, &Config::default()).await.unwrap();
+            let sprint_data = mock_sprint_client.get_sprint_data().await.unwrap().unwrap();
+            assert_eq!(sprint_data.goal_text.as_deref(), Some("Ship the new onboarding flow"));
+        });
+    }
+
+    #[test]
+    fn test_sprint_cancel_renders_custom_message() {
+        let rt = test_runtime();
+        let active_sprint_context = Some(ActiveSprintContext::default());
+        let config = Config { sprint_cancel_message: Some("Partial progress is tracked in the retro doc.".to_string()), ..Config::default() };
+
+        rt.block_on(async {
+            let result = SprintCommand::SprintCancel.create_sprint_message(&TicketSummary::default(), &active_sprint_context, &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &config).await.unwrap();
+            assert_eq!(result[3], section_block("\nPartial progress is tracked in the retro doc.\n"));
+
+            let result = SprintCommand::SprintCancel.create_sprint_message(&TicketSummary::default(), &active_sprint_context, &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &Config::default()).await.unwrap();
+            assert_eq!(result[3], section_block("\nProgress will not be saved.\n"));
+        });
+    }
+
+    #[test]
+    fn test_sprint_cancel_records_cancelled_history_entry() {
+        let rt = test_runtime();
+        let config = Config { record_cancelled_sprints: true, ..Config::default() };
+        let active_sprint_context = Some(ActiveSprintContext::default());
+        let mock_sprint_client = MockSprintClient::new(active_sprint_context.clone(), None, None);
+        let mock_notification_client = MockEventBridgeClient::new();
+        let mut cumulative_sprint_contexts = CumulativeSprintContexts { history: vec![] };
+
+        rt.block_on(async {
+            let result = SprintCommand::SprintCancel.create_sprint_message(&TicketSummary::default(), &active_sprint_context, &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &config).await.unwrap();
+            assert_eq!(result[3], section_block("\nThis sprint will be recorded in the sprint history as cancelled.\n"));
+
+            let _ = mock_notification_client.create_daily_trigger_rule("Sprint 1", DAILY_SUMMARY_TIME).await;
+            SprintCommand::SprintCancel.save_sprint_state(&mut TicketSummary::default(), &active_sprint_context, &mut cumulative_sprint_contexts, &mock_sprint_client, &mock_notification_client, &config).await.unwrap();
+
+            let history = mock_sprint_client.get_historical_data().await.unwrap().unwrap().history;
+            assert_eq!(history.len(), 1);
+            assert!(history[0].cancelled);
+            assert!(mock_sprint_client.get_sprint_archive("Sprint 1").await.unwrap().is_none());
+        });
+    }
+
+    #[test]
+    fn test_daily_summary_attaches_completion_chart() {
+        let rt = test_runtime();
+        let active_sprint_context = ActiveSprintContext {
+            end_date: (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(5).unwrap()).format("%m/%d/%y").to_string(),
+            ..ActiveSprintContext::default()
+        };
+        let is_chart = |block: &Value| block["type"] == "image";
+
+        rt.block_on(async {
+            let config = Config { completion_chart_url: Some("https://quickchart.io/chart".to_string()), ..Config::default() };
+            let result = SprintCommand::DailySummary.create_sprint_message(&TicketSummary::default(), &Some(active_sprint_context.clone()), &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &config).await.unwrap();
+            assert_eq!(result.iter().filter(|block| is_chart(block)).count(), 1);
+
+            let result = SprintCommand::DailySummary.create_sprint_message(&TicketSummary::default(), &Some(active_sprint_context), &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &Config::default()).await.unwrap();
+            assert!(!result.iter().any(is_chart));
+        });
+    }
+
+    #[test]
+    fn test_sprint_extend_moves_end_date_and_trigger() {
+        let rt = test_runtime();
+        let today = chrono::Local::now().with_timezone(&Pacific);
+        let active_sprint_context = Some(ActiveSprintContext { end_date: (today + chrono::Duration::try_days(1).unwrap()).format("%m/%d/%y").to_string(), ..ActiveSprintContext::default() });
+        let extended_end_date = (today + chrono::Duration::try_days(4).unwrap()).format("%m/%d/%y").to_string();
+        let mock_sprint_client = MockSprintClient::new(active_sprint_context.clone(), None, None);
+        let mock_notification_client = MockEventBridgeClient::new();
+        let event = SprintCommand::SprintExtend { new_end_date: extended_end_date.clone() };
+
+        rt.block_on(async {
+            let _ = mock_notification_client.create_daily_trigger_rule("Sprint 1", SPRINT_REVIEW_TIME).await;
+            event.save_sprint_state(&mut TicketSummary::default(), &active_sprint_context, &mut CumulativeSprintContexts::default(), &mock_sprint_client, &mock_notification_client, &Config::default()).await.unwrap();
+
+            assert_eq!(mock_sprint_client.get_sprint_data().await.unwrap().unwrap().end_date, extended_end_date);
+            assert_eq!(mock_notification_client.rules_created.lock().await.get("Sprint 1").map(String::as_str), Some(DAILY_SUMMARY_TIME));
+
+            let result = event.create_sprint_message(&TicketSummary::default(), &active_sprint_context, &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &Config::default()).await.unwrap();
+            let old_end_date = &active_sprint_context.as_ref().unwrap().end_date;
+            assert_eq!(result[0], section_block(&format!("📅 Sprint Sprint 1 now ends {} instead of {}, with 4 days remaining.", extended_end_date, old_end_date)));
+        });
+    }
+
+    #[test]
+    fn test_blocked_prs_alert_targets_dev_channel() {
+        use self::ticket::{CheckRunDetails, PullRequest, Ticket, TicketDetails};
+        use self::ticket_state::TicketState;
+
+        let ticket_summary = TicketSummary::from_tickets(vec![
+            Ticket {
+                details: TicketDetails { name: "Blocked Ticket".to_string(), state: TicketState::InProgress, ..TicketDetails::default() },
+                pr: Some(PullRequest { mergeable: Some(true), failing_check_runs: vec![CheckRunDetails { name: "build".to_string(), details_url: "https://example.com/build".to_string() }], ..PullRequest::default() }),
+                ..Ticket::default()
+            },
+            Ticket { details: TicketDetails { name: "Open Ticket".to_string(), state: TicketState::InProgress, ..TicketDetails::default() }, ..Ticket::default() },
+            Ticket { details: TicketDetails { name: "Done Ticket".to_string(), state: TicketState::Done, ..TicketDetails::default() }, ..Ticket::default() },
+        ], false);
+        let config = Config { blocked_prs_channel: Some("C_DEV".to_string()), ..Config::default() };
+
+        let (channel_id, blocks) = SprintCommand::DailySummary.blocked_prs_alert(&ticket_summary, &Some(ActiveSprintContext::default()), &Localization::default(), &config).unwrap();
+
+        assert_eq!(channel_id, "C_DEV");
+        assert_eq!(blocks[0], header_block("🚨 Blocked PRs in Sprint 1"));
+        let blocks = serde_json::to_string(&blocks).unwrap();
+        assert!(blocks.contains("Blocked Ticket"));
+        assert!(!blocks.contains("Open Ticket"));
+        assert!(!blocks.contains("Done Ticket"));
+    }
+
+    #[test]
+    fn test_blocked_prs_alert_skipped_when_unconfigured_or_nothing_blocked() {
+        let config = Config { blocked_prs_channel: Some("C_DEV".to_string()), ..Config::default() };
+        let active_sprint_context = Some(ActiveSprintContext::default());
+
+        assert!(SprintCommand::DailySummary.blocked_prs_alert(&TicketSummary::from_tickets(vec![], false), &active_sprint_context, &Localization::default(), &config).is_none());
+        assert!(SprintCommand::DailySummary.blocked_prs_alert(&TicketSummary::default(), &active_sprint_context, &Localization::default(), &Config::default()).is_none());
+    }
+
+    #[test]
+    fn test_sprint_check_in_outcome() {
+        let rt = test_runtime();
+        let active_sprint_context = Some(ActiveSprintContext {
+            end_date: (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(5).unwrap()).format("%m/%d/%y").to_string(),
+            ..ActiveSprintContext::default()
+        });
+        let mock_sprint_client = MockSprintClient::new(active_sprint_context.clone(), None, None);
+        let mock_notification_client = MockEventBridgeClient::new();
+
+        rt.block_on(async {
+            let outcome = SprintCommand::SprintCheckIn { force: false }.execute("C123456", CommandContext {
+                ticket_summary: &mut TicketSummary::default(),
+                active_sprint_context: &active_sprint_context,
+                cumulative_sprint_contexts: &mut CumulativeSprintContexts::default(),
+                daily_ticket_contexts: &DailyTicketContexts::default(),
+            }, &Localization::default(), &mock_sprint_client, &mock_notification_client, &Config::default()).await.unwrap();
+
+            assert_eq!(outcome.channel_id, "C123456");
+            assert_eq!(outcome.blocks[0]["type"], "header");
+            assert!(outcome.blocks[0]["text"]["text"].as_str().unwrap().contains("Sprint 1"));
+            assert!(outcome.alerts.is_empty());
+            assert_eq!(outcome.state_changes, vec![StateChange::TicketData]);
+        });
+    }
+
+    #[test]
+    fn test_failed_command_releases_processing_lock() {
+        let rt = test_runtime();
+        let active_sprint_context = Some(ActiveSprintContext::default());
+        let mock_sprint_client = MockSprintClient::new(active_sprint_context.clone(), None, None);
+        let mock_notification_client = MockEventBridgeClient::new();
+
+        rt.block_on(async {
+            assert!(mock_sprint_client.acquire_processing_lock("extend-request", 1000, 1900).await.unwrap());
+
+            let result = mock_sprint_client.release_processing_lock_after("extend-request", SprintCommand::SprintExtend { new_end_date: "not a date".to_string() }.execute("C123456", CommandContext {
+                ticket_summary: &mut TicketSummary::default(),
+                active_sprint_context: &active_sprint_context,
+                cumulative_sprint_contexts: &mut CumulativeSprintContexts::default(),
+                daily_ticket_contexts: &DailyTicketContexts::default(),
+            }, &Localization::default(), &mock_sprint_client, &mock_notification_client, &Config::default())).await;
+
+            assert!(result.is_err());
+            assert!(mock_sprint_client.get_processing_lock().await.unwrap().is_none());
+            assert!(mock_sprint_client.acquire_processing_lock("scheduled-request", 1001, 1901).await.unwrap());
+        });
+    }
+
+    #[test]
+    fn test_sprint_check_in_saves_ticket_data() {
+        let rt = test_runtime();
+        let active_sprint_context = Some(ActiveSprintContext {
+            end_date: (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(1).unwrap()).format("%m/%d/%y").to_string(),
+            ..ActiveSprintContext::default()
+        });
+        let mock_sprint_client = MockSprintClient::new(active_sprint_context.clone(), None, None);
+        let mock_notification_client = MockEventBridgeClient::new();
+
+        rt.block_on(async {
+            let _ = mock_notification_client.create_daily_trigger_rule("Sprint 1", DAILY_SUMMARY_TIME).await;
+            let rules_before = mock_notification_client.rules_created.lock().await.clone();
+            SprintCommand::SprintCheckIn { force: false }.save_sprint_state(&mut TicketSummary::default(), &active_sprint_context, &mut CumulativeSprintContexts::default(), &mock_sprint_client, &mock_notification_client, &Config::default()).await.unwrap();
+
+            assert!(mock_sprint_client.get_ticket_data().await.unwrap().is_some());
+            assert_eq!(*mock_notification_client.rules_created.lock().await, rules_before);
+        });
+    }
+
+    #[test]
+    fn test_sprint_status_is_read_only() {
+        let rt = test_runtime();
+        let active_sprint_context = Some(ActiveSprintContext {
+            end_date: (chrono::Local::now().with_timezone(&Pacific) + chrono::Duration::try_days(5).unwrap()).format("%m/%d/%y").to_string(),
+            ..ActiveSprintContext::default()
+        });
+        let mock_sprint_client = MockSprintClient::new(active_sprint_context.clone(), None, None);
+        let mock_notification_client = MockEventBridgeClient::new();
+        let mut cumulative_sprint_contexts = CumulativeSprintContexts::default();
+
+        rt.block_on(async {
+            let outcome = SprintCommand::SprintStatus.execute("C123456", CommandContext {
+                ticket_summary: &mut TicketSummary::default(),
+                active_sprint_context: &active_sprint_context,
+                cumulative_sprint_contexts: &mut cumulative_sprint_contexts,
+                daily_ticket_contexts: &DailyTicketContexts::default(),
+            }, &Localization::default(), &mock_sprint_client, &mock_notification_client, &Config::default()).await.unwrap();
+            let rendered = serde_json::to_string(&outcome.blocks).unwrap();
+
+            assert!(outcome.blocks[0]["text"]["text"].as_str().unwrap().contains("Sprint 1 Check-In"));
+            assert!(rendered.contains("(read-only)"));
+            assert!(rendered.contains("tickets open"));
+            assert!(rendered.contains("of sprint scope completed"));
+            assert!(outcome.state_changes.is_empty());
+
+            assert_eq!(mock_sprint_client.get_sprint_data().await.unwrap(), active_sprint_context);
+            assert!(mock_sprint_client.get_ticket_data().await.unwrap().is_none());
+            assert!(mock_sprint_client.get_historical_data().await.unwrap().is_none());
+            assert_eq!(cumulative_sprint_contexts.history.len(), CumulativeSprintContexts::default().history.len());
+            assert!(mock_notification_client.rules_created.lock().await.is_empty());
+            assert!(mock_notification_client.rules_deleted.lock().await.is_empty());
+            assert!(mock_notification_client.rules_disabled.lock().await.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_sprint_config_shows_settings_without_secrets() {
+        let rt = test_runtime();
+        let config = Config {
+            github_token: "ghp_secret".to_string(),
+            slack_oauth: "xoxb-secret".to_string(),
+            trello_api_token: Some("trello_secret".to_string()),
+            summary_timezone: chrono_tz::Europe::London,
+            ..Config::default()
+        };
+
+        rt.block_on(async {
+            let blocks = SprintCommand::SprintConfig.create_sprint_message(&TicketSummary::default(), &None, &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &Localization::default(), &config).await.unwrap();
+            let rendered = serde_json::to_string(&blocks).unwrap();
+
+            assert!(rendered.contains("*Timezone:* Europe/London"));
+            assert!(rendered.contains("*Ticket source:* Trello board TestBoardID"));
+            assert!(rendered.contains(DAILY_SUMMARY_TIME));
+            assert!(!rendered.contains("ghp_secret"));
+            assert!(!rendered.contains("xoxb-secret"));
+            assert!(!rendered.contains("trello_secret"));
+            assert!(!rendered.contains("TestTrelloKey"));
+
+            let localization = Localization::new(std::collections::HashMap::from([(Label::ConfigTimezone, "Zona horaria".to_string())]));
+            let blocks = SprintCommand::SprintConfig.create_sprint_message(&TicketSummary::default(), &None, &CumulativeSprintContexts::default(), &DailyTicketContexts::default(), &localization, &config).await.unwrap();
+            assert!(serde_json::to_string(&blocks).unwrap().contains("*Zona horaria:* Europe/London"));
+        });
+    }
+
+    #[derive(Default)]
+    struct MockCanvasClient {
+        created: std::cell::RefCell<Vec<(String, String)>>,
+        edited: std::cell::RefCell<Vec<(String, String)>>,
+    }
+
+    impl CanvasClient for MockCanvasClient {
+        async fn create_canvas(&self, channel_id: &str, title: &str, _markdown: &str) -> Result<String> {
+            self.created.borrow_mut().push((channel_id.to_string(), title.to_string()));
+            Ok("F_CANVAS".to_string())
+        }
+
+        async fn edit_canvas(&self, canvas_id: &str, markdown: &str) -> Result<()> {
+            self.edited.borrow_mut().push((canvas_id.to_string(), markdown.to_string()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_summary_canvas_id_is_kept_on_sprint() {
+        let rt = test_runtime();
+        let active_sprint_context = Some(ActiveSprintContext::default());
+        let mock_sprint_client = MockSprintClient::new(active_sprint_context.clone(), None, None);
+        let canvas_client = MockCanvasClient::default();
+        let config = Config { summary_canvas: Some(SummaryCanvas::Also), ..Config::default() };
+        let blocks = vec![header_block("Sprint 1 Daily Summary")];
+
+        rt.block_on(async {
+            let state_changes = SprintCommand::DailySummary.update_summary_canvas(&blocks, &active_sprint_context, &canvas_client, &mock_sprint_client, &Localization::default(), &config).await.unwrap();
+
+            assert_eq!(state_changes, vec![StateChange::SprintData]);
+            assert_eq!(*canvas_client.created.borrow(), vec![("C123456".to_string(), "Sprint Sprint 1 Summary".to_string())]);
+            let saved_context = mock_sprint_client.get_sprint_data().await.unwrap();
+            assert_eq!(saved_context.as_ref().and_then(|context| context.canvas_id.as_deref()), Some("F_CANVAS"));
+
+            let state_changes = SprintCommand::SprintCheckIn { force: false }.update_summary_canvas(&blocks, &saved_context, &canvas_client, &mock_sprint_client, &Localization::default(), &config).await.unwrap();
+
+            assert!(state_changes.is_empty());
+            assert_eq!(canvas_client.created.borrow().len(), 1);
+            assert_eq!(*canvas_client.edited.borrow(), vec![("F_CANVAS".to_string(), "# Sprint 1 Daily Summary".to_string())]);
+        });
+    }
+
+    #[test]
+    fn test_summary_canvas_not_configured() {
+        let rt = test_runtime();
+        let active_sprint_context = Some(ActiveSprintContext::default());
+        let mock_sprint_client = MockSprintClient::new(active_sprint_context.clone(), None, None);
+        let canvas_client = MockCanvasClient::default();
+        let instead_config = Config { summary_canvas: Some(SummaryCanvas::Instead), ..Config::default() };
+
+        rt.block_on(async {
+            let state_changes = SprintCommand::DailySummary.update_summary_canvas(&[], &active_sprint_context, &canvas_client, &mock_sprint_client, &Localization::default(), &Config::default()).await.unwrap();
+
+            assert!(state_changes.is_empty());
+            assert!(canvas_client.created.borrow().is_empty());
+        });
+        assert!(SprintCommand::DailySummary.skips_channel_message(&instead_config));
+        assert!(!SprintCommand::SprintHygiene.skips_channel_message(&instead_config));
+        assert!(!SprintCommand::DailySummary.skips_channel_message(&Config { summary_canvas: Some(SummaryCanvas::Also), ..Config::default() }));
+    }
+
+    struct MockMessageClient {
+        update_error: Option<String>,
+        posted: std::cell::RefCell<Vec<String>>,
+        threads: std::cell::RefCell<Vec<Option<String>>>,
+        updated: std::cell::RefCell<Vec<String>>,
+        deleted: std::cell::RefCell<Vec<String>>,
+        ephemeral: std::cell::RefCell<Vec<String>>,
+    }
+
+    impl MockMessageClient {
+        fn new(update_error: Option<&str>) -> Self {
+            MockMessageClient { update_error: update_error.map(str::to_string), posted: Default::default(), threads: Default::default(), updated: Default::default(), deleted: Default::default(), ephemeral: Default::default() }
+        }
+    }
+
+    impl TeamCommunicationClient for MockMessageClient {
+        async fn send_teams_message<T: serde::Serialize>(&self, _channel_id: &str, _blocks: &T, _response_url: Option<String>) -> Result<Option<String>> {
+            Ok(None)
+        }
+
+        async fn send_text_message(&self, _channel_id: &str, _text: &str, _response_url: Option<String>) -> Result<Option<String>> {
+            Ok(None)
+        }
+
+        async fn post_teams_message<T: serde::Serialize>(&self, channel_id: &str, _blocks: &T, thread_ts: Option<&str>) -> Result<String> {
+            self.posted.borrow_mut().push(channel_id.to_string());
+            self.threads.borrow_mut().push(thread_ts.map(str::to_string));
+            Ok(format!("1700000000.00000{}", self.posted.borrow().len()))
+        }
+
+        async fn update_teams_message<T: serde::Serialize>(&self, _channel_id: &str, ts: &str, _blocks: &T) -> Result<()> {
+            match &self.update_error {
+                Some(error) => Err(SlackApiError { error: error.clone() }.into()),
+                None => {
+                    self.updated.borrow_mut().push(ts.to_string());
+                    Ok(())
+                },
+            }
+        }
+
+        async fn delete_teams_message(&self, _channel_id: &str, ts: &str) -> Result<()> {
+            self.deleted.borrow_mut().push(ts.to_string());
+            Ok(())
+        }
+
+        async fn post_ephemeral_message<T: serde::Serialize>(&self, _channel_id: &str, user_id: &str, _blocks: &T) -> Result<()> {
+            self.ephemeral.borrow_mut().push(user_id.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_daily_summary_updated_in_place() {
+        let rt = test_runtime();
+        let active_sprint_context = Some(ActiveSprintContext::default());
+        let mock_sprint_client = MockSprintClient::new(active_sprint_context.clone(), None, None);
+        let message_client = MockMessageClient::new(None);
+        let config = Config { update_daily_summary_in_place: true, ..Config::default() };
+        let blocks = vec![header_block("Sprint 1 Daily Summary")];
+
+        rt.block_on(async {
+            let state_changes = SprintCommand::DailySummary.update_daily_summary_in_place(&blocks, &active_sprint_context, &message_client, &mock_sprint_client, &config).await.unwrap();
+
+            assert_eq!(state_changes, vec![StateChange::SprintData]);
+            assert_eq!(*message_client.posted.borrow(), vec!["C123456".to_string()]);
+            let saved_context = mock_sprint_client.get_sprint_data().await.unwrap();
+            assert_eq!(saved_context.as_ref().and_then(|context| context.daily_summary_ts.as_deref()), Some("1700000000.000001"));
+
+            let state_changes = SprintCommand::DailySummary.update_daily_summary_in_place(&blocks, &saved_context, &message_client, &mock_sprint_client, &config).await.unwrap();
+
+            assert!(state_changes.is_empty());
+            assert_eq!(message_client.posted.borrow().len(), 1);
+            assert_eq!(*message_client.updated.borrow(), vec!["1700000000.000001".to_string()]);
+        });
+        assert!(!SprintCommand::SprintCheckIn { force: false }.updates_in_place(&config));
+        assert!(!SprintCommand::DailySummary.updates_in_place(&Config::default()));
+    }
+
+    #[test]
+    fn test_daily_summary_updated_in_place_within_thread() {
+        let rt = test_runtime();
+        let active_sprint_context = Some(ActiveSprintContext { root_message_ts: Some("1690000000.000001".to_string()), ..ActiveSprintContext::default() });
+        let mock_sprint_client = MockSprintClient::new(active_sprint_context.clone(), None, None);
+        let message_client = MockMessageClient::new(None);
+
+        rt.block_on(async {
+            let config = Config { update_daily_summary_in_place: true, ..Config::default() };
+            SprintCommand::DailySummary.update_daily_summary_in_place(&[], &active_sprint_context, &message_client, &mock_sprint_client, &config).await.unwrap();
+
+            let config = Config { update_daily_summary_in_place: true, thread_daily_summaries: true, ..Config::default() };
+            SprintCommand::DailySummary.update_daily_summary_in_place(&[], &active_sprint_context, &message_client, &mock_sprint_client, &config).await.unwrap();
+
+            assert_eq!(*message_client.threads.borrow(), vec![None, Some("1690000000.000001".to_string())]);
+        });
+    }
+
+    #[test]
+    fn test_deleted_daily_summary_is_posted_again() {
+        let rt = test_runtime();
+        let active_sprint_context = Some(ActiveSprintContext { daily_summary_ts: Some("1690000000.000001".to_string()), ..ActiveSprintContext::default() });
+        let mock_sprint_client = MockSprintClient::new(active_sprint_context.clone(), None, None);
+        let message_client = MockMessageClient::new(Some("message_not_found"));
+        let config = Config { update_daily_summary_in_place: true, ..Config::default() };
+
+        rt.block_on(async {
+            let state_changes = SprintCommand::DailySummary.update_daily_summary_in_place(&[], &active_sprint_context, &message_client, &mock_sprint_client, &config).await.unwrap();
+
+            assert_eq!(state_changes, vec![StateChange::SprintData]);
+            assert_eq!(message_client.posted.borrow().len(), 1);
+            let saved_context = mock_sprint_client.get_sprint_data().await.unwrap();
+            assert_eq!(saved_context.and_then(|context| context.daily_summary_ts), Some("1700000000.000001".to_string()));
+        });
+    }
+
+    #[test]
+    fn test_preview_posted_ephemerally_under_option() {
+        let rt = test_runtime();
+        let message_client = MockMessageClient::new(None);
+        let config = Config { ephemeral_previews: true, kickoff_preview_archive: Some(PreviewArchive::Delete), ..Config::default() };
+        let preview = SprintCommand::SprintPreview { sprint_name: "Sprint 1".to_string(), end_date: "01/15/24".to_string(), channel_id: "C123456".to_string(), capacity: None, preview_token: None, theme_emoji: None, goal_text: None };
+        let kickoff = SprintCommand::SprintKickoff { sprint_name: "Sprint 1".to_string(), end_date: "01/15/24".to_string(), channel_id: "C123456".to_string(), capacity: None, preview_token: None, theme_emoji: None, goal_text: None };
+
+        assert!(preview.posts_ephemeral_preview(&config));
+        assert!(!preview.tracks_preview_message(&config), "An ephemeral preview can't be archived later");
+        assert!(!kickoff.posts_ephemeral_preview(&config), "The confirmed kickoff is still posted publicly");
+        assert!(!preview.posts_ephemeral_preview(&Config::default()));
+
+        rt.block_on(async {
+            preview.post_ephemeral_preview("C123456", Some("U123"), &[], &message_client).await.unwrap();
+
+            assert_eq!(*message_client.ephemeral.borrow(), vec!["U123".to_string()]);
+            assert!(message_client.posted.borrow().is_empty());
+        });
+    }
+
+    #[test]
+    fn test_kickoff_preview_removed_on_confirm() {
+        let rt = test_runtime();
+        let mock_sprint_client = MockSprintClient::new(None, None, None);
+        let message_client = MockMessageClient::new(None);
+        let config = Config { kickoff_preview_archive: Some(PreviewArchive::Delete), ..Config::default() };
+        let preview = SprintCommand::SprintPreview { sprint_name: "Sprint 1".to_string(), end_date: "01/15/24".to_string(), channel_id: "C123456".to_string(), capacity: None, preview_token: None, theme_emoji: None, goal_text: None };
+        let kickoff = SprintCommand::SprintKickoff { sprint_name: "Sprint 1".to_string(), end_date: "01/15/24".to_string(), channel_id: "C123456".to_string(), capacity: None, preview_token: None, theme_emoji: None, goal_text: None };
+
+        rt.block_on(async {
+            assert!(preview.tracks_preview_message(&config));
+            assert!(!kickoff.tracks_preview_message(&config));
+            preview.post_preview_message("C123456", &[], &message_client, &mock_sprint_client).await.unwrap();
+            assert_eq!(mock_sprint_client.get_preview_message().await.unwrap(), Some(PreviewMessageContext { channel_id: "C123456".to_string(), ts: "1700000000.000001".to_string() }));
+
+            let state_changes = kickoff.archive_kickoff_preview(&message_client, &mock_sprint_client, &Localization::default(), &config).await.unwrap();
+
+            assert_eq!(state_changes, vec![StateChange::PreviewMessage]);
+            assert_eq!(*message_client.deleted.borrow(), vec!["1700000000.000001".to_string()]);
+            assert!(mock_sprint_client.get_preview_message().await.unwrap().is_none());
+        });
+    }
+
+    #[test]
+    fn test_kickoff_preview_updated_on_confirm() {
+        let rt = test_runtime();
+        let mock_sprint_client = MockSprintClient::new(None, None, None);
+        let kickoff = SprintCommand::SprintKickoff { sprint_name: "Sprint 1".to_string(), end_date: "01/15/24".to_string(), channel_id: "C123456".to_string(), capacity: None, preview_token: None, theme_emoji: None, goal_text: None };
+
+        rt.block_on(async {
+            //nothing is stored or touched while archiving is off
+            let message_client = MockMessageClient::new(None);
+            mock_sprint_client.put_preview_message(&PreviewMessageContext { channel_id: "C123456".to_string(), ts: "1690000000.000001".to_string() }).await.unwrap();
+            assert!(kickoff.archive_kickoff_preview(&message_client, &mock_sprint_client, &Localization::default(), &Config::default()).await.unwrap().is_empty());
+
+            let config = Config { kickoff_preview_archive: Some(PreviewArchive::Update), ..Config::default() };
+            kickoff.archive_kickoff_preview(&message_client, &mock_sprint_client, &Localization::default(), &config).await.unwrap();
+            assert_eq!(*message_client.updated.borrow(), vec!["1690000000.000001".to_string()]);
+            assert!(message_client.deleted.borrow().is_empty());
+
+            //a preview that was already deleted is forgotten rather than failing the kickoff
+            let message_client = MockMessageClient::new(Some("message_not_found"));
+            mock_sprint_client.put_preview_message(&PreviewMessageContext { channel_id: "C123456".to_string(), ts: "1690000000.000002".to_string() }).await.unwrap();
+            assert_eq!(kickoff.archive_kickoff_preview(&message_client, &mock_sprint_client, &Localization::default(), &config).await.unwrap(), vec![StateChange::PreviewMessage]);
+            assert!(mock_sprint_client.get_preview_message().await.unwrap().is_none());
+        });
+    }
+
+    #[test]
+    fn test_kickoff_message_becomes_thread_root() {
+        let rt = test_runtime();
+        //the kickoff has already saved the new sprint by the time its message is posted
+        let mock_sprint_client = MockSprintClient::new(Some(ActiveSprintContext::default()), None, None);
+        let message_client = MockMessageClient::new(None);
+        let config = Config { thread_daily_summaries: true, ..Config::default() };
+        let kickoff = SprintCommand::SprintKickoff { sprint_name: "Sprint 1".to_string(), end_date: "01/15/24".to_string(), channel_id: "C123456".to_string(), capacity: None, preview_token: None, theme_emoji: None, goal_text: None };
+
+        rt.block_on(async {
+            assert!(kickoff.threads_message(&config));
+            let state_changes = kickoff.post_threaded_message("C123456", &[], &None, &message_client, &mock_sprint_client).await.unwrap();
+
+            assert_eq!(state_changes, vec![StateChange::SprintData]);
+            assert_eq!(*message_client.threads.borrow(), vec![None]);
+            let saved_context = mock_sprint_client.get_sprint_data().await.unwrap();
+            assert_eq!(saved_context.and_then(|context| context.root_message_ts), Some("1700000000.000001".to_string()));
+        });
+    }
+
+    #[test]
+    fn test_daily_summary_replies_in_sprint_thread() {
+        let rt = test_runtime();
+        let active_sprint_context = Some(ActiveSprintContext { root_message_ts: Some("1690000000.000001".to_string()), ..ActiveSprintContext::default() });
+        let mock_sprint_client = MockSprintClient::new(active_sprint_context.clone(), None, None);
+        let message_client = MockMessageClient::new(None);
+        let config = Config { thread_daily_summaries: true, ..Config::default() };
+
+        rt.block_on(async {
+            let state_changes = SprintCommand::DailySummary.post_threaded_message("C123456", &[], &active_sprint_context, &message_client, &mock_sprint_client).await.unwrap();
+            assert!(state_changes.is_empty());
+
+            SprintCommand::DailySummary.post_threaded_message("C123456", &[], &Some(ActiveSprintContext::default()), &message_client, &mock_sprint_client).await.unwrap();
+            assert_eq!(*message_client.threads.borrow(), vec![Some("1690000000.000001".to_string()), None]);
+        });
+        assert!(!SprintCommand::SprintCheckIn { force: false }.threads_message(&config));
+        assert!(!SprintCommand::DailySummary.threads_message(&Config::default()));
+    }
+
+    #[test]
+    fn test_second_summary_within_throttle_window_is_suppressed() {
+        let rt = test_runtime();
+        let mock_sprint_client = MockSprintClient::new(Some(ActiveSprintContext::default()), None, None);
+        let config = Config { summary_throttle_seconds: Some(900), ..Config::default() };
+
+        rt.block_on(async {
+            assert!(!SprintCommand::DailySummary.is_throttled(&mock_sprint_client, 1000, &config).await.unwrap());
+            SprintCommand::DailySummary.record_post(&mock_sprint_client, 1000, &config).await.unwrap();
+
+            assert!(SprintCommand::SprintCheckIn { force: false }.is_throttled(&mock_sprint_client, 1300, &config).await.unwrap());
+            assert!(SprintCommand::DailySummary.is_throttled(&mock_sprint_client, 1899, &config).await.unwrap());
+            assert!(!SprintCommand::DailySummary.is_throttled(&mock_sprint_client, 1900, &config).await.unwrap());
+        });
+    }
+
+    #[test]
+    fn test_forced_check_in_bypasses_throttle() {
+        let rt = test_runtime();
+        let mock_sprint_client = MockSprintClient::new(Some(ActiveSprintContext::default()), None, None);
+        let config = Config { summary_throttle_seconds: Some(900), ..Config::default() };
+
+        rt.block_on(async {
+            SprintCommand::DailySummary.record_post(&mock_sprint_client, 1000, &config).await.unwrap();
+
+            assert!(!SprintCommand::SprintCheckIn { force: true }.is_throttled(&mock_sprint_client, 1300, &config).await.unwrap());
+            assert!(!SprintCommand::DailySummary.is_throttled(&mock_sprint_client, 1300, &Config::default()).await.unwrap());
+        });
+    }
+
+    #[test]
+    fn test_sprint_kickoff_keeps_carried_over_ticket_provenance() {
+        use std::collections::{HashMap, VecDeque};
+        use self::sprint_records::DailyTicketContext;
+        use self::ticket::TicketDetails;
+        use self::ticket_sources::{ticket_summary_mocks::{MockPullRequestClient, MockTicketDetailsClient, MockTicketSummaryClient}, TicketHistory, TicketSummaryClient};
+        use self::ticket_state::TicketState;
+        use crate::utils::time_budget::TimeBudget;
+
+        let rt = test_runtime();
+        let carried_over_ids = ["in_progress", "pending_release", "demo"];
+        let previous_ticket_data = DailyTicketContexts {
+            tickets: carried_over_ids.iter().map(|id| DailyTicketContext { id: id.to_string(), state: TicketState::InProgress, ..DailyTicketContext::default() }).collect::<VecDeque<_>>(),
+        };
+        let ticket_client = MockTicketSummaryClient::new(
+            MockTicketDetailsClient::new(vec![
+                TicketDetails { id: "in_progress".to_string(), state: TicketState::InProgress, pr_url: None, ..TicketDetails::default() },
+                TicketDetails { id: "pending_release".to_string(), state: TicketState::PendingRelease, pr_url: None, ..TicketDetails::default() },
+                TicketDetails { id: "demo".to_string(), state: TicketState::DemoFinalApproval, pr_url: None, ..TicketDetails::default() },
+            ]),
+            MockPullRequestClient::new(HashMap::new()),
+        );
+        let mock_sprint_client = MockSprintClient::new(None, None, Some(previous_ticket_data.clone()));
+        let mock_notification_client = MockEventBridgeClient::new();
+        let event = SprintCommand::SprintKickoff {
+            sprint_name: "Sprint 102".to_string(),
+            end_date: "03/03/23".to_string(),
+            channel_id: "XYZ123".to_string(),
+            capacity: None,
+            preview_token: None,
+            theme_emoji: None,
+            goal_text: None,
+        };
+
+        rt.block_on(async {
+            let mut ticket_summary = ticket_client.fetch_ticket_summary("Sprint 102", TicketHistory { historical_records: &CumulativeSprintContexts::default(), previous_ticket_data: &previous_ticket_data, manual_deferrals: &BTreeSet::new() }, HashMap::new(), &TimeBudget::default(), &Config::default()).await.unwrap();
+            event.save_sprint_state(&mut ticket_summary, &None, &mut CumulativeSprintContexts::default(), &mock_sprint_client, &mock_notification_client, &Config::default()).await.unwrap();
+
+            let ticket_data = mock_sprint_client.get_ticket_data().await.unwrap().unwrap();
+            for id in carried_over_ids {
+                let ticket = ticket_data.tickets.iter().find(|ticket| ticket.id == id).unwrap_or_else(|| panic!("{} should still be in the ticket data", id));
+                assert_eq!(ticket.added_in_sprint, "Sprint 101", "{} should keep the sprint it was added in", id);
+                assert_eq!(ticket.added_on, "04/01/24");
+            }
         });
     }
 }
@@ -1,24 +1,35 @@
 use std::{self, collections::VecDeque};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::future::Future;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::{from_value, Value};
 
+use crate::tracing::{error, info};
+use crate::utils::date::days_between;
+use crate::utils::localization::{Label, Localization};
 use crate::utils::s3::JsonStorageClient;
 use crate::utils::slack_components::section_block;
 
 use super::ticket::TicketLink;
+use super::ticket_summary::TicketSummary;
 use super::ticket_label::TicketLabel;
 use super::ticket_state::TicketState;
 
 #[async_trait(?Send)]
 pub trait SprintMemberClient {
     async fn get_sprint_members(&self) -> Result<Option<HashMap<String, String>>>;
+    async fn put_sprint_members(&self, sprint_members: &HashMap<String, String>) -> Result<()>;
+
+    async fn map_sprint_member(&self, trello_id: &str, slack_user_id: &str) -> Result<()> {
+        let mut sprint_members = self.get_sprint_members().await?.unwrap_or_default();
+        sprint_members.insert(trello_id.to_string(), slack_user_id.to_string());
+        self.put_sprint_members(&sprint_members).await
+    }
 }
 
 #[async_trait(?Send)]
-#[cfg(not(test))]
 impl<T> SprintMemberClient for T where T: JsonStorageClient, {
     async fn get_sprint_members(&self) -> Result<Option<HashMap<String, String>>> {
         self.get_json("trello_to_slack_users.json").await?
@@ -28,6 +39,10 @@ impl<T> SprintMemberClient for T where T: JsonStorageClient, {
             })
             .transpose()
     }
+
+    async fn put_sprint_members(&self, sprint_members: &HashMap<String, String>) -> Result<()> {
+        self.put_json("trello_to_slack_users.json", &serde_json::to_value(sprint_members)?).await
+    }
 }
 
 //Sprint record is updated at the beginning of each sprint
@@ -40,6 +55,21 @@ pub struct ActiveSprintContext {
     pub trello_board: String,
     pub open_tickets_count_beginning: u32,
     pub in_scope_tickets_count_beginning: u32,
+    pub capacity: Option<u32>,
+    #[serde(default)]
+    pub manual_deferrals: BTreeSet<String>,
+    #[serde(default)]
+    pub theme_emoji: Option<String>,
+    #[serde(default)]
+    pub goal_text: Option<String>,
+    #[serde(default)]
+    pub canvas_id: Option<String>,
+    //the daily summary message that later summaries edit when they're updated in place
+    #[serde(default)]
+    pub daily_summary_ts: Option<String>,
+    //the kickoff message that daily summaries are threaded under
+    #[serde(default)]
+    pub root_message_ts: Option<String>,
 }
 
 #[async_trait(?Send)]
@@ -50,7 +80,6 @@ pub trait ActiveSprintContextClient {
 }
 
 #[async_trait(?Send)]
-#[cfg(not(test))]
 impl<T> ActiveSprintContextClient for T where T: JsonStorageClient, {
     async fn get_sprint_data(&self) -> Result<Option<ActiveSprintContext>> {
         self.get_json("sprint_data.json").await?
@@ -98,6 +127,10 @@ impl DailyTicketContexts {
             .filter(|ticket| ticket.state != TicketState::Done)
             .count()
     }
+
+    pub fn was_completed(&self, ticket_id: &str) -> bool {
+        self.tickets.iter().any(|ticket| ticket.id == ticket_id && ticket.state.is_terminal())
+    }
 }
 
 #[async_trait(?Send)]
@@ -107,7 +140,6 @@ pub trait DailyTicketContextClient {
 }
 
 #[async_trait(?Send)]
-#[cfg(not(test))]
 impl<T> DailyTicketContextClient for T where T: JsonStorageClient, {
     async fn get_ticket_data(&self) -> Result<Option<DailyTicketContexts>> {
         self.get_json("ticket_data.json").await?
@@ -135,6 +167,13 @@ pub struct CumulativeSprintContext {
     pub completed_tickets_count: u32,
     pub tickets_added_to_scope_count: i32,
     pub open_tickets_added_count: i32,
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+//whole averages read better without the trailing .0
+fn format_average(average: f64) -> String {
+    if average.fract() == 0.0 { format!("{}", average) } else { format!("{:.1}", average) }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -143,12 +182,12 @@ pub struct CumulativeSprintContexts {
 }
 
 impl CumulativeSprintContexts {
-    pub fn into_slack_blocks(&self) -> Vec<Value> {
+    pub fn into_slack_blocks(&self, localization: &Localization) -> Vec<Value> {
         if self.history.is_empty() {
             return vec![];
         }
     
-        let mut history_text = String::from("\n\n*Previous Sprints:*");
+        let mut history_text = format!("\n\n*{}*", localization.text(Label::PreviousSprintsHeader));
     
         for record in &self.history {
             history_text += &format!(
@@ -158,22 +197,109 @@ impl CumulativeSprintContexts {
                 record.completed_tickets_count,
                 record.percent_complete
             );
+            if record.cancelled {
+                history_text += &format!(" {}", localization.text(Label::CancelledSprintTag));
+            }
         }
     
         vec![section_block(&history_text)]
     }
 
-    pub fn count_sprints_since(&self, sprint_name: &str) -> usize {
+    //cancelled sprints would drag the average down, so only the last sprints that ran their course are counted
+    pub fn average_velocity(&self, sprint_count: usize) -> Option<(f64, usize)> {
+        let recent_counts: Vec<u32> = self.history.iter()
+            .rev()
+            .filter(|record| !record.cancelled)
+            .take(sprint_count)
+            .map(|record| record.completed_tickets_count)
+            .collect();
+
+        if recent_counts.is_empty() {
+            return None;
+        }
+
+        Some((recent_counts.iter().sum::<u32>() as f64 / recent_counts.len() as f64, recent_counts.len()))
+    }
+
+    pub fn velocity_trend(&self, completed_tickets_count: u32, sprint_count: usize, localization: &Localization) -> Option<String> {
+        let (average, counted_sprints) = self.average_velocity(sprint_count)?;
+        if average == 0.0 {
+            return Some(localization.format(Label::VelocityTrendFromZero, &[&completed_tickets_count, &counted_sprints]));
+        }
+
+        let change_percent = (completed_tickets_count as f64 - average) / average * 100.0;
+        let trend_emoji = if change_percent < 0.0 { "📉" } else { "📈" };
+
+        Some(localization.format(Label::VelocityTrend, &[&trend_emoji, &completed_tickets_count, &format_average(average), &counted_sprints, &format!("{:+.0}", change_percent)]))
+    }
+
+    //asking for more sprints than were recorded averages the ones there are, and says so
+    pub fn velocity_summary(&self, sprint_count: usize, localization: &Localization) -> String {
+        match self.average_velocity(sprint_count) {
+            Some((average, counted_sprints)) if counted_sprints < sprint_count => format!(
+                "{}\n{}",
+                localization.format(Label::VelocityAverage, &[&format_average(average), &counted_sprints]),
+                localization.format(Label::VelocityLimitedHistory, &[&sprint_count, &counted_sprints])
+            ),
+            Some((average, counted_sprints)) => localization.format(Label::VelocityAverage, &[&format_average(average), &counted_sprints]),
+            None => localization.text(Label::NoVelocityHistory),
+        }
+    }
+
+    pub fn count_sprints_since(&self, sprint_name: &str, name_matching: SprintNameMatching) -> usize {
         self.history
             .iter()
             .rev()
-            .position(|item| item.name == sprint_name)
+            .position(|item| name_matching.matches(&item.name, sprint_name))
             .map(|index| index + 1)
             .unwrap_or(0)
     }
 
-    pub fn was_sprint_name_used(&self, sprint_name: &str) -> bool {
-        self.count_sprints_since(sprint_name) > 0
+    //a sprint missing from history is either the current one or predates the history, so older tickets are aged by the recorded sprints that ended after they were added
+    pub fn sprint_age(&self, added_in_sprint: &str, added_on: &str, current_sprint_name: &str, name_matching: SprintNameMatching) -> usize {
+        match self.count_sprints_since(added_in_sprint, name_matching) {
+            0 if !name_matching.matches(added_in_sprint, current_sprint_name) => self.history
+                .iter()
+                .filter(|record| days_between(added_on, &record.end_date).is_ok_and(|days| days >= 0))
+                .count(),
+            sprints_since => sprints_since,
+        }
+    }
+
+    //sprints without an archive keep the percentage recorded when they ended
+    pub fn backfill_percent_complete(&mut self, archives: &[SprintArchive]) -> usize {
+        let mut backfilled_count = 0;
+
+        for record in self.history.iter_mut() {
+            if let Some(archive) = archives.iter().find(|archive| archive.name == record.name) {
+                record.percent_complete = archive.percent_complete();
+                record.completed_tickets_count = archive.completed_tickets.len() as u32;
+                backfilled_count += 1;
+            }
+        }
+
+        backfilled_count
+    }
+
+    pub fn was_sprint_name_used(&self, sprint_name: &str, name_matching: SprintNameMatching) -> bool {
+        self.count_sprints_since(sprint_name, name_matching) > 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SprintNameMatching {
+    #[default]
+    Exact,
+    //"Sprint 1" and " sprint 1" are treated as the same sprint
+    CaseInsensitive,
+}
+
+impl SprintNameMatching {
+    pub fn matches(&self, name: &str, other: &str) -> bool {
+        match self {
+            SprintNameMatching::Exact => name == other,
+            SprintNameMatching::CaseInsensitive => name.trim().to_lowercase() == other.trim().to_lowercase(),
+        }
     }
 }
 
@@ -185,7 +311,6 @@ pub trait CumulativeSprintContextClient {
 }
 
 #[async_trait(?Send)]
-#[cfg(not(test))]
 impl<T> CumulativeSprintContextClient for T where T: JsonStorageClient, {
     async fn get_historical_data(&self) -> Result<Option<CumulativeSprintContexts>> {
         self.get_json("historical_data.json").await?
@@ -204,17 +329,333 @@ impl<T> CumulativeSprintContextClient for T where T: JsonStorageClient, {
     }
 }
 
-pub trait SprintClient: SprintMemberClient + CumulativeSprintContextClient + DailyTicketContextClient + ActiveSprintContextClient {}
-impl<T> SprintClient for T where T: SprintMemberClient + CumulativeSprintContextClient + DailyTicketContextClient + ActiveSprintContextClient {}
+//Last processed Slack trigger, used to skip retried or double-submitted interactions
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct ProcessedTriggerContext {
+    pub trigger_id: String,
+    pub processed_at: i64,
+}
+
+impl ProcessedTriggerContext {
+    pub fn is_duplicate(&self, trigger_id: &str, now: i64, window_seconds: i64) -> bool {
+        self.trigger_id == trigger_id && now - self.processed_at <= window_seconds
+    }
+}
+
+#[async_trait(?Send)]
+pub trait ProcessedTriggerClient {
+    async fn get_processed_trigger(&self) -> Result<Option<ProcessedTriggerContext>>;
+    async fn put_processed_trigger(&self, processed_trigger: &ProcessedTriggerContext) -> Result<()>;
+
+    async fn claim_trigger(&self, trigger_id: &str, now: i64, window_seconds: i64) -> Result<bool> {
+        if let Some(previous) = self.get_processed_trigger().await? {
+            if previous.is_duplicate(trigger_id, now, window_seconds) {
+                return Ok(false);
+            }
+        }
+
+        self.put_processed_trigger(&ProcessedTriggerContext {
+            trigger_id: trigger_id.to_string(),
+            processed_at: now,
+        }).await?;
+
+        Ok(true)
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> ProcessedTriggerClient for T where T: JsonStorageClient, {
+    async fn get_processed_trigger(&self) -> Result<Option<ProcessedTriggerContext>> {
+        self.get_json("processed_trigger.json").await?
+            .map(|json_value| {
+                from_value::<ProcessedTriggerContext>(json_value)
+                    .context("Failed to deserialize processed trigger data")
+            })
+            .transpose()
+    }
+
+    async fn put_processed_trigger(&self, processed_trigger: &ProcessedTriggerContext) -> Result<()> {
+        let processed_trigger_value = serde_json::to_value(processed_trigger)
+            .context("Failed to convert processed trigger data to JSON value")?;
+
+        self.put_json("processed_trigger.json", &processed_trigger_value).await
+    }
+}
+
+//When a summary last went out to the sprint channel, so a scheduled trigger and a manual check-in don't both post
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct LastPostContext {
+    pub posted_at: i64,
+}
+
+#[async_trait(?Send)]
+pub trait LastPostClient {
+    async fn get_last_post(&self) -> Result<Option<LastPostContext>>;
+    async fn put_last_post(&self, last_post: &LastPostContext) -> Result<()>;
+
+    async fn posted_within(&self, now: i64, window_seconds: i64) -> Result<bool> {
+        Ok(self.get_last_post().await?
+            .is_some_and(|last_post| now - last_post.posted_at < window_seconds))
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> LastPostClient for T where T: JsonStorageClient, {
+    async fn get_last_post(&self) -> Result<Option<LastPostContext>> {
+        self.get_json("last_post.json").await?
+            .map(|json_value| {
+                from_value::<LastPostContext>(json_value)
+                    .context("Failed to deserialize last post data")
+            })
+            .transpose()
+    }
+
+    async fn put_last_post(&self, last_post: &LastPostContext) -> Result<()> {
+        let last_post_value = serde_json::to_value(last_post)
+            .context("Failed to convert last post data to JSON value")?;
+
+        self.put_json("last_post.json", &last_post_value).await
+    }
+}
+
+//Held while an invocation reads and writes sprint state, so overlapping invocations don't act on stale data
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct ProcessingLock {
+    pub holder: String,
+    pub acquired_at: i64,
+    //set by the holder from its own deadline, so a crashed invocation's lock lapses once it couldn't still be running
+    #[serde(default)]
+    pub expires_at: i64,
+}
+
+impl ProcessingLock {
+    pub fn is_held_by_other(&self, holder: &str, now: i64) -> bool {
+        self.holder != holder && now < self.expires_at
+    }
+}
+
+#[async_trait(?Send)]
+pub trait ProcessingLockClient {
+    async fn get_processing_lock(&self) -> Result<Option<ProcessingLock>>;
+    //only writes if the stored lock is still existing_lock, so of two invocations that read the same lock just one gets it
+    async fn replace_processing_lock(&self, existing_lock: Option<&ProcessingLock>, processing_lock: &ProcessingLock) -> Result<bool>;
+    //only clears if the stored lock is still existing_lock, so a lock taken over after it expired stays with its new holder
+    async fn clear_processing_lock(&self, existing_lock: &ProcessingLock) -> Result<bool>;
+
+    async fn acquire_processing_lock(&self, holder: &str, now: i64, expires_at: i64) -> Result<bool> {
+        let existing_lock = self.get_processing_lock().await?;
+        if existing_lock.as_ref().is_some_and(|existing_lock| existing_lock.is_held_by_other(holder, now)) {
+            return Ok(false);
+        }
+
+        self.replace_processing_lock(existing_lock.as_ref(), &ProcessingLock {
+            holder: holder.to_string(),
+            acquired_at: now,
+            expires_at,
+        }).await
+    }
+
+    async fn release_processing_lock(&self, holder: &str) -> Result<()> {
+        match self.get_processing_lock().await? {
+            Some(existing_lock) if existing_lock.holder == holder => {
+                if !self.clear_processing_lock(&existing_lock).await? {
+                    info!("Processing lock changed hands before it was released, leaving it in place");
+                }
+                Ok(())
+            },
+            _ => Ok(()),
+        }
+    }
+
+    //the lock is released whether or not work succeeds, so a failed command doesn't hold off every later invocation until it expires
+    async fn release_processing_lock_after<T, E, F>(&self, holder: &str, work: F) -> std::result::Result<T, E> where Self: Sized, F: Future<Output = std::result::Result<T, E>> {
+        let result = work.await;
+
+        if let Err(e) = self.release_processing_lock(holder).await {
+            error!("Failed to release processing lock: {:?}", e);
+        }
+
+        result
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> ProcessingLockClient for T where T: JsonStorageClient, {
+    async fn get_processing_lock(&self) -> Result<Option<ProcessingLock>> {
+        self.get_json("processing_lock.json").await?
+            .map(|json_value| {
+                from_value::<ProcessingLock>(json_value)
+                    .context("Failed to deserialize processing lock data")
+            })
+            .transpose()
+    }
+
+    async fn replace_processing_lock(&self, existing_lock: Option<&ProcessingLock>, processing_lock: &ProcessingLock) -> Result<bool> {
+        let processing_lock_value = serde_json::to_value(processing_lock)
+            .context("Failed to convert processing lock data to JSON value")?;
+        let existing_lock_value = existing_lock.map(serde_json::to_value).transpose()
+            .context("Failed to convert existing processing lock data to JSON value")?;
+
+        self.put_json_if("processing_lock.json", &processing_lock_value, existing_lock_value.as_ref()).await
+    }
+
+    async fn clear_processing_lock(&self, existing_lock: &ProcessingLock) -> Result<bool> {
+        let existing_lock_value = serde_json::to_value(existing_lock)
+            .context("Failed to convert existing processing lock data to JSON value")?;
+
+        self.delete_json_if("processing_lock.json", &existing_lock_value).await
+    }
+}
+
+//The summary fetched for a sprint preview, so confirming the kickoff soon after doesn't fetch everything again
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PreviewCache {
+    pub token: String,
+    pub created_at: i64,
+    #[serde(serialize_with = "TicketSummary::serialize_sorted")]
+    pub ticket_summary: TicketSummary,
+}
+
+#[async_trait(?Send)]
+pub trait PreviewCacheClient {
+    async fn get_preview_cache(&self) -> Result<Option<PreviewCache>>;
+    async fn put_preview_cache(&self, preview_cache: &PreviewCache) -> Result<()>;
+    async fn clear_preview_cache(&self) -> Result<()>;
+
+    async fn take_preview_summary(&self, token: &str, now: i64, max_age_seconds: i64) -> Result<Option<TicketSummary>> {
+        match self.get_preview_cache().await? {
+            Some(preview_cache) if preview_cache.token == token && now - preview_cache.created_at <= max_age_seconds => {
+                self.clear_preview_cache().await?;
+                Ok(Some(preview_cache.ticket_summary))
+            },
+            _ => Ok(None),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> PreviewCacheClient for T where T: JsonStorageClient, {
+    async fn get_preview_cache(&self) -> Result<Option<PreviewCache>> {
+        self.get_json("preview_cache.json").await?
+            .map(|json_value| {
+                from_value::<PreviewCache>(json_value)
+                    .context("Failed to deserialize preview cache data")
+            })
+            .transpose()
+    }
+
+    async fn put_preview_cache(&self, preview_cache: &PreviewCache) -> Result<()> {
+        let preview_cache_value = serde_json::to_value(preview_cache)
+            .context("Failed to convert preview cache data to JSON value")?;
+
+        self.put_json("preview_cache.json", &preview_cache_value).await
+    }
+
+    async fn clear_preview_cache(&self) -> Result<()> {
+        self.delete_json("preview_cache.json").await
+    }
+}
+
+//The sprint preview message a kickoff can be confirmed from, so it can be tidied up once the kickoff goes through
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct PreviewMessageContext {
+    pub channel_id: String,
+    pub ts: String,
+}
+
+#[async_trait(?Send)]
+pub trait PreviewMessageClient {
+    async fn get_preview_message(&self) -> Result<Option<PreviewMessageContext>>;
+    async fn put_preview_message(&self, preview_message: &PreviewMessageContext) -> Result<()>;
+    async fn clear_preview_message(&self) -> Result<()>;
+}
+
+#[async_trait(?Send)]
+impl<T> PreviewMessageClient for T where T: JsonStorageClient, {
+    async fn get_preview_message(&self) -> Result<Option<PreviewMessageContext>> {
+        self.get_json("preview_message.json").await?
+            .map(|json_value| {
+                from_value::<PreviewMessageContext>(json_value)
+                    .context("Failed to deserialize preview message data")
+            })
+            .transpose()
+    }
+
+    async fn put_preview_message(&self, preview_message: &PreviewMessageContext) -> Result<()> {
+        let preview_message_value = serde_json::to_value(preview_message)
+            .context("Failed to convert preview message data to JSON value")?;
+
+        self.put_json("preview_message.json", &preview_message_value).await
+    }
+
+    async fn clear_preview_message(&self) -> Result<()> {
+        self.delete_json("preview_message.json").await
+    }
+}
+
+//The tickets completed in a finished sprint, kept so its history can be recomputed if the completion math changes
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SprintArchive {
+    pub name: String,
+    pub sprint_ticket_count: u32,
+    pub completed_tickets: Vec<DailyTicketContext>,
+}
+
+impl SprintArchive {
+    pub fn percent_complete(&self) -> f64 {
+        if self.sprint_ticket_count == 0 {
+            return 0.0;
+        }
+
+        (self.completed_tickets.len() as f64 / self.sprint_ticket_count as f64) * 100.0
+    }
+}
+
+//sprint names are free text, so anything but letters, digits, - and _ is percent-encoded to keep each archive a single key under archives/
+fn sprint_archive_key(sprint_name: &str) -> String {
+    let encoded_name: String = sprint_name.bytes().map(|byte| match byte {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' => (byte as char).to_string(),
+        _ => format!("%{:02X}", byte),
+    }).collect();
+
+    format!("archives/{}.json", encoded_name)
+}
+
+#[async_trait(?Send)]
+pub trait SprintArchiveClient {
+    async fn get_sprint_archive(&self, sprint_name: &str) -> Result<Option<SprintArchive>>;
+    async fn put_sprint_archive(&self, sprint_archive: &SprintArchive) -> Result<()>;
+}
+
+#[async_trait(?Send)]
+impl<T> SprintArchiveClient for T where T: JsonStorageClient, {
+    async fn get_sprint_archive(&self, sprint_name: &str) -> Result<Option<SprintArchive>> {
+        self.get_json(&sprint_archive_key(sprint_name)).await?
+            .map(|json_value| {
+                from_value::<SprintArchive>(json_value)
+                    .context("Failed to deserialize sprint archive")
+            })
+            .transpose()
+    }
+
+    async fn put_sprint_archive(&self, sprint_archive: &SprintArchive) -> Result<()> {
+        let sprint_archive_value = serde_json::to_value(sprint_archive)
+            .context("Failed to convert sprint archive to JSON value")?;
+
+        self.put_json(&sprint_archive_key(&sprint_archive.name), &sprint_archive_value).await
+    }
+}
+
+pub trait SprintClient: SprintMemberClient + CumulativeSprintContextClient + DailyTicketContextClient + ActiveSprintContextClient + ProcessedTriggerClient + LastPostClient + ProcessingLockClient + PreviewCacheClient + PreviewMessageClient + SprintArchiveClient {}
+impl<T> SprintClient for T where T: SprintMemberClient + CumulativeSprintContextClient + DailyTicketContextClient + ActiveSprintContextClient + ProcessedTriggerClient + LastPostClient + ProcessingLockClient + PreviewCacheClient + PreviewMessageClient + SprintArchiveClient {}
 
 #[cfg(test)]
 pub mod mocks {
-    use std::collections::{HashMap, VecDeque};
+    use std::collections::{BTreeSet, HashMap, VecDeque};
     use anyhow::Result;
     use async_trait::async_trait;
-    use serde_json::{json, Value};
-    use crate::{sprint_summary::ticket_state::TicketState, utils::s3::JsonStorageClient};
-    use super::{ActiveSprintContext, ActiveSprintContextClient, CumulativeSprintContext, CumulativeSprintContextClient, CumulativeSprintContexts, DailyTicketContext, DailyTicketContextClient, DailyTicketContexts, SprintMemberClient};
+    use crate::sprint_summary::ticket_state::TicketState;
+    use super::{ActiveSprintContext, ActiveSprintContextClient, CumulativeSprintContext, CumulativeSprintContextClient, CumulativeSprintContexts, DailyTicketContext, DailyTicketContextClient, DailyTicketContexts, LastPostClient, LastPostContext, ProcessedTriggerClient, ProcessedTriggerContext, PreviewCache, PreviewCacheClient, PreviewMessageClient, PreviewMessageContext, ProcessingLock, ProcessingLockClient, SprintArchive, SprintArchiveClient, SprintMemberClient};
     use std::sync::Arc;
     use tokio::sync::Mutex;
 
@@ -228,6 +669,13 @@ pub mod mocks {
                 trello_board: "testboard".to_string(),
                 open_tickets_count_beginning: 0,
                 in_scope_tickets_count_beginning: 0,
+                capacity: None,
+                manual_deferrals: BTreeSet::new(),
+                theme_emoji: None,
+                goal_text: None,
+                canvas_id: None,
+                daily_summary_ts: None,
+                root_message_ts: None,
             }
         }
     }
@@ -241,7 +689,8 @@ pub mod mocks {
                 percent_complete: 0.9, 
                 completed_tickets_count: 12, 
                 tickets_added_to_scope_count: 5, 
-                open_tickets_added_count: 7 
+                open_tickets_added_count: 7,
+                cancelled: false,
             }
         }
     }
@@ -288,21 +737,14 @@ pub mod mocks {
     pub struct MockSprintClient {
         sprint_data: Arc<Mutex<Option<ActiveSprintContext>>>,
         historical_data: Arc<Mutex<Option<CumulativeSprintContexts>>>,
-        ticket_data: Arc<Mutex<Option<DailyTicketContexts>>>
-    }
-
-    impl JsonStorageClient for MockSprintClient {
-        async fn get_json(&self, _: &str) -> Result<Option<Value>> {
-            Ok(Some(json!("Stub")))
-        }
-
-        async fn put_json(&self, _: &str, _: &Value) -> Result<()> {
-            Ok(())
-        }
-
-        async fn delete_json(&self, _: &str) -> Result<()> {
-            Ok(())
-        }
+        ticket_data: Arc<Mutex<Option<DailyTicketContexts>>>,
+        processed_trigger: Arc<Mutex<Option<ProcessedTriggerContext>>>,
+        last_post: Arc<Mutex<Option<LastPostContext>>>,
+        processing_lock: Arc<Mutex<Option<ProcessingLock>>>,
+        preview_cache: Arc<Mutex<Option<PreviewCache>>>,
+        preview_message: Arc<Mutex<Option<PreviewMessageContext>>>,
+        sprint_members: Arc<Mutex<HashMap<String, String>>>,
+        sprint_archives: Arc<Mutex<HashMap<String, SprintArchive>>>,
     }
 
     #[async_trait(?Send)]
@@ -356,11 +798,124 @@ pub mod mocks {
     #[async_trait(?Send)]
     impl SprintMemberClient for MockSprintClient {
         async fn get_sprint_members(&self) -> Result<Option<HashMap<String, String>>> {
-            let members = HashMap::from([
-                ("trello_user1".to_string(), "slack_user1".to_string()),
-                ("trello_user2".to_string(), "slack_user2".to_string()),
-            ]);
-            Ok(Some(members))
+            let members = self.sprint_members.lock().await;
+            Ok(Some(members.clone()))
+        }
+
+        async fn put_sprint_members(&self, sprint_members: &HashMap<String, String>) -> Result<()> {
+            let mut members_lock = self.sprint_members.lock().await;
+            *members_lock = sprint_members.clone();
+            Ok(())
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl ProcessedTriggerClient for MockSprintClient {
+        async fn get_processed_trigger(&self) -> Result<Option<ProcessedTriggerContext>> {
+            let processed_trigger = self.processed_trigger.lock().await;
+            Ok(processed_trigger.clone())
+        }
+
+        async fn put_processed_trigger(&self, processed_trigger: &ProcessedTriggerContext) -> Result<()> {
+            let mut processed_trigger_lock = self.processed_trigger.lock().await;
+            *processed_trigger_lock = Some(processed_trigger.clone());
+            Ok(())
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl LastPostClient for MockSprintClient {
+        async fn get_last_post(&self) -> Result<Option<LastPostContext>> {
+            let last_post = self.last_post.lock().await;
+            Ok(last_post.clone())
+        }
+
+        async fn put_last_post(&self, last_post: &LastPostContext) -> Result<()> {
+            let mut last_post_lock = self.last_post.lock().await;
+            *last_post_lock = Some(last_post.clone());
+            Ok(())
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl ProcessingLockClient for MockSprintClient {
+        async fn get_processing_lock(&self) -> Result<Option<ProcessingLock>> {
+            let processing_lock = self.processing_lock.lock().await;
+            Ok(processing_lock.clone())
+        }
+
+        async fn replace_processing_lock(&self, existing_lock: Option<&ProcessingLock>, processing_lock: &ProcessingLock) -> Result<bool> {
+            let mut processing_lock_lock = self.processing_lock.lock().await;
+            if processing_lock_lock.as_ref() != existing_lock {
+                return Ok(false);
+            }
+
+            *processing_lock_lock = Some(processing_lock.clone());
+            Ok(true)
+        }
+
+        async fn clear_processing_lock(&self, existing_lock: &ProcessingLock) -> Result<bool> {
+            let mut processing_lock_lock = self.processing_lock.lock().await;
+            if processing_lock_lock.as_ref() != Some(existing_lock) {
+                return Ok(false);
+            }
+
+            *processing_lock_lock = None;
+            Ok(true)
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl PreviewCacheClient for MockSprintClient {
+        async fn get_preview_cache(&self) -> Result<Option<PreviewCache>> {
+            let preview_cache = self.preview_cache.lock().await;
+            Ok(preview_cache.clone())
+        }
+
+        async fn put_preview_cache(&self, preview_cache: &PreviewCache) -> Result<()> {
+            let mut preview_cache_lock = self.preview_cache.lock().await;
+            *preview_cache_lock = Some(preview_cache.clone());
+            Ok(())
+        }
+
+        async fn clear_preview_cache(&self) -> Result<()> {
+            let mut preview_cache_lock = self.preview_cache.lock().await;
+            *preview_cache_lock = None;
+            Ok(())
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl PreviewMessageClient for MockSprintClient {
+        async fn get_preview_message(&self) -> Result<Option<PreviewMessageContext>> {
+            let preview_message = self.preview_message.lock().await;
+            Ok(preview_message.clone())
+        }
+
+        async fn put_preview_message(&self, preview_message: &PreviewMessageContext) -> Result<()> {
+            let mut preview_message_lock = self.preview_message.lock().await;
+            *preview_message_lock = Some(preview_message.clone());
+            Ok(())
+        }
+
+        async fn clear_preview_message(&self) -> Result<()> {
+            let mut preview_message_lock = self.preview_message.lock().await;
+            *preview_message_lock = None;
+            Ok(())
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl SprintArchiveClient for MockSprintClient {
+        async fn get_sprint_archive(&self, sprint_name: &str) -> Result<Option<SprintArchive>> {
+            let sprint_archives = self.sprint_archives.lock().await;
+            Ok(sprint_archives.get(sprint_name).cloned())
+        }
+
+        async fn put_sprint_archive(&self, sprint_archive: &SprintArchive) -> Result<()> {
+            let mut sprint_archives_lock = self.sprint_archives.lock().await;
+            sprint_archives_lock.insert(sprint_archive.name.clone(), sprint_archive.clone());
+            Ok(())
         }
     }
 
@@ -369,7 +924,17 @@ pub mod mocks {
             Self { 
                 sprint_data: Arc::new(Mutex::new(sprint_data)), 
                 historical_data: Arc::new(Mutex::new(historical_data)), 
-                ticket_data: Arc::new(Mutex::new(ticket_data))
+                ticket_data: Arc::new(Mutex::new(ticket_data)),
+                processed_trigger: Arc::new(Mutex::new(None)),
+                last_post: Arc::new(Mutex::new(None)),
+                processing_lock: Arc::new(Mutex::new(None)),
+                preview_cache: Arc::new(Mutex::new(None)),
+                preview_message: Arc::new(Mutex::new(None)),
+                sprint_members: Arc::new(Mutex::new(HashMap::from([
+                    ("trello_user1".to_string(), "slack_user1".to_string()),
+                    ("trello_user2".to_string(), "slack_user2".to_string()),
+                ]))),
+                sprint_archives: Arc::new(Mutex::new(HashMap::new())),
             }
         }
     }
@@ -378,6 +943,7 @@ pub mod mocks {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sprint_summary::ticket::{Ticket, TicketDetails};
     use serde_json::json;
     
     #[test]
@@ -386,10 +952,61 @@ mod tests {
         assert_eq!(contexts.count_open_tickets(), 1);
     }
 
+    fn history_with_completed_counts(completed_counts: &[u32]) -> CumulativeSprintContexts {
+        CumulativeSprintContexts {
+            history: completed_counts.iter().enumerate().map(|(index, completed_tickets_count)| CumulativeSprintContext {
+                name: format!("Sprint {}", index + 1),
+                completed_tickets_count: *completed_tickets_count,
+                ..CumulativeSprintContext::default()
+            }).collect(),
+        }
+    }
+
+    #[test]
+    fn test_velocity_trend_rising() {
+        let contexts = history_with_completed_counts(&[30, 8, 9, 10]);
+
+        assert_eq!(contexts.average_velocity(3), Some((9.0, 3)));
+        assert_eq!(contexts.velocity_trend(12, 3, &Localization::default()).as_deref(), Some("📈 12 completed (avg 9 over last 3, +33%)"));
+    }
+
+    #[test]
+    fn test_velocity_trend_falling() {
+        let mut contexts = history_with_completed_counts(&[10, 12, 14]);
+        contexts.history.push(CumulativeSprintContext { completed_tickets_count: 1, cancelled: true, ..CumulativeSprintContext::default() });
+
+        assert_eq!(contexts.velocity_trend(9, 3, &Localization::default()).as_deref(), Some("📉 9 completed (avg 12 over last 3, -25%)"));
+    }
+
+    #[test]
+    fn test_velocity_trend_with_insufficient_history() {
+        let contexts = history_with_completed_counts(&[4, 5]);
+
+        assert_eq!(contexts.velocity_trend(5, 3, &Localization::default()).as_deref(), Some("📈 5 completed (avg 4.5 over last 2, +11%)"));
+        assert_eq!(history_with_completed_counts(&[0]).velocity_trend(2, 3, &Localization::default()).as_deref(), Some("📈 2 completed (avg 0 over last 1)"));
+        assert_eq!(history_with_completed_counts(&[]).velocity_trend(5, 3, &Localization::default()), None);
+    }
+
+    #[test]
+    fn test_velocity_summary_averages_last_sprints() {
+        let contexts = history_with_completed_counts(&[30, 8, 9, 10, 13]);
+
+        assert_eq!(contexts.velocity_summary(2, &Localization::default()), "*11.5 tickets completed* per sprint on average over the last 2 sprints.");
+        assert_eq!(contexts.velocity_summary(5, &Localization::default()), "*14 tickets completed* per sprint on average over the last 5 sprints.");
+    }
+
+    #[test]
+    fn test_velocity_summary_with_more_sprints_than_history() {
+        let contexts = history_with_completed_counts(&[30, 8, 9, 10, 13]);
+
+        assert_eq!(contexts.velocity_summary(10, &Localization::default()), "*14 tickets completed* per sprint on average over the last 5 sprints.\nOnly 5 of the 10 requested sprints are recorded.");
+        assert_eq!(history_with_completed_counts(&[]).velocity_summary(3, &Localization::default()), "No finished sprints are recorded yet.");
+    }
+
     #[test]
     fn test_into_slack_blocks_empty_history() {
         let contexts = CumulativeSprintContexts { history: vec![] };
-        assert!(contexts.into_slack_blocks().is_empty());
+        assert!(contexts.into_slack_blocks(&Localization::default()).is_empty());
     }
 
     #[test]
@@ -404,6 +1021,7 @@ mod tests {
                     completed_tickets_count: 100,
                     tickets_added_to_scope_count: 50,
                     open_tickets_added_count: 20,
+                    cancelled: false,
                 },
                 CumulativeSprintContext {
                     name: "Sprint 2".to_string(),
@@ -413,15 +1031,16 @@ mod tests {
                     completed_tickets_count: 97,
                     tickets_added_to_scope_count: 55,
                     open_tickets_added_count: 25,
+                    cancelled: true,
                 },
             ],
         };
-        let blocks = contexts.into_slack_blocks();
+        let blocks = contexts.into_slack_blocks(&Localization::default());
         let expected_blocks = vec![
             json!(
                 {
                     "text": {
-                        "text": "\n\n*Previous Sprints:*\n01/01/24 - 01/15/24: *100 tickets | 90.00%*\n02/01/24 - 02/15/24: *97 tickets | 95.50%*",
+                        "text": "\n\n*Previous Sprints:*\n01/01/24 - 01/15/24: *100 tickets | 90.00%*\n02/01/24 - 02/15/24: *97 tickets | 95.50%* _cancelled_",
                         "type": "mrkdwn"
                     },
                     "type": "section"
@@ -439,7 +1058,7 @@ mod tests {
                 CumulativeSprintContext { name: "Sprint 2".to_string(), ..Default::default() },
             ],
         };
-        assert_eq!(contexts.count_sprints_since("Sprint 1"), 2);
+        assert_eq!(contexts.count_sprints_since("Sprint 1", SprintNameMatching::Exact), 2);
     }
 
     #[test]
@@ -449,13 +1068,13 @@ mod tests {
                 CumulativeSprintContext { name: "Sprint 1".to_string(), ..Default::default() },
             ],
         };
-        assert_eq!(contexts.count_sprints_since("Sprint 3"), 0);
+        assert_eq!(contexts.count_sprints_since("Sprint 3", SprintNameMatching::Exact), 0);
     }
 
     #[test]
     fn test_count_sprints_since_empty_history() {
         let contexts = CumulativeSprintContexts { history: vec![] };
-        assert_eq!(contexts.count_sprints_since("Sprint 1"), 0);
+        assert_eq!(contexts.count_sprints_since("Sprint 1", SprintNameMatching::Exact), 0);
     }
 
     #[test]
@@ -465,7 +1084,7 @@ mod tests {
                 CumulativeSprintContext { name: "Sprint 1".to_string(), ..Default::default() },
             ],
         };
-        assert!(contexts.was_sprint_name_used("Sprint 1"));
+        assert!(contexts.was_sprint_name_used("Sprint 1", SprintNameMatching::Exact));
     }
 
     #[test]
@@ -475,12 +1094,224 @@ mod tests {
                 CumulativeSprintContext { name: "Sprint 1".to_string(), ..Default::default() },
             ],
         };
-        assert!(!contexts.was_sprint_name_used("Sprint 2"));
+        assert!(!contexts.was_sprint_name_used("Sprint 2", SprintNameMatching::Exact));
     }
 
     #[test]
     fn test_was_sprint_name_used_empty_history() {
         let contexts = CumulativeSprintContexts { history: vec![] };
-        assert!(!contexts.was_sprint_name_used("Sprint 1"));
+        assert!(!contexts.was_sprint_name_used("Sprint 1", SprintNameMatching::Exact));
+    }
+
+    #[tokio::test]
+    async fn test_claim_trigger_skips_repeated_trigger_id() {
+        let client = mocks::MockSprintClient::new(None, None, None);
+
+        assert!(client.claim_trigger("trigger123", 1000, 300).await.unwrap());
+        assert!(!client.claim_trigger("trigger123", 1100, 300).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_claim_trigger_processes_new_trigger_id() {
+        let client = mocks::MockSprintClient::new(None, None, None);
+
+        assert!(client.claim_trigger("trigger123", 1000, 300).await.unwrap());
+        assert!(client.claim_trigger("trigger456", 1100, 300).await.unwrap());
+        assert_eq!(client.get_processed_trigger().await.unwrap().unwrap().trigger_id, "trigger456");
+    }
+
+    #[tokio::test]
+    async fn test_claim_trigger_processes_repeated_trigger_id_outside_window() {
+        let client = mocks::MockSprintClient::new(None, None, None);
+
+        assert!(client.claim_trigger("trigger123", 1000, 300).await.unwrap());
+        assert!(client.claim_trigger("trigger123", 1301, 300).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_processing_lock_held_causes_second_invocation_to_back_off() {
+        let client = mocks::MockSprintClient::new(None, None, None);
+
+        assert!(client.acquire_processing_lock("kickoff-request", 1000, 1900).await.unwrap());
+        assert!(!client.acquire_processing_lock("scheduled-request", 1001, 1901).await.unwrap());
+
+        client.release_processing_lock("kickoff-request").await.unwrap();
+        assert!(client.acquire_processing_lock("scheduled-request", 1002, 1902).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_processing_lock_race_has_one_winner() {
+        let client = mocks::MockSprintClient::new(None, None, None);
+
+        //both invocations read before either writes
+        let first_seen = client.get_processing_lock().await.unwrap();
+        let second_seen = client.get_processing_lock().await.unwrap();
+
+        let first_lock = ProcessingLock { holder: "kickoff-request".to_string(), acquired_at: 1000, expires_at: 1900 };
+        let second_lock = ProcessingLock { holder: "scheduled-request".to_string(), acquired_at: 1000, expires_at: 1900 };
+        assert!(client.replace_processing_lock(first_seen.as_ref(), &first_lock).await.unwrap());
+        assert!(!client.replace_processing_lock(second_seen.as_ref(), &second_lock).await.unwrap());
+
+        assert_eq!(client.get_processing_lock().await.unwrap(), Some(first_lock));
+    }
+
+    #[tokio::test]
+    async fn test_failed_work_releases_processing_lock() {
+        let client = mocks::MockSprintClient::new(None, None, None);
+
+        assert!(client.acquire_processing_lock("extend-request", 1000, 1900).await.unwrap());
+        let result: Result<()> = client.release_processing_lock_after("extend-request", async { Err(anyhow::anyhow!("Invalid end date")) }).await;
+
+        assert!(result.is_err());
+        assert!(client.get_processing_lock().await.unwrap().is_none());
+        assert!(client.acquire_processing_lock("scheduled-request", 1001, 1901).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_processing_lock_expires() {
+        let client = mocks::MockSprintClient::new(None, None, None);
+
+        assert!(client.acquire_processing_lock("crashed-request", 1000, 1900).await.unwrap());
+        assert!(client.acquire_processing_lock("next-request", 1900, 2800).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_processing_lock_only_released_by_holder() {
+        let client = mocks::MockSprintClient::new(None, None, None);
+
+        assert!(client.acquire_processing_lock("kickoff-request", 1000, 1900).await.unwrap());
+        client.release_processing_lock("scheduled-request").await.unwrap();
+
+        assert_eq!(client.get_processing_lock().await.unwrap().unwrap().holder, "kickoff-request");
+    }
+
+    #[tokio::test]
+    async fn test_expired_lock_taken_over_is_not_cleared_by_its_old_holder() {
+        let client = mocks::MockSprintClient::new(None, None, None);
+
+        assert!(client.acquire_processing_lock("slow-request", 1000, 1900).await.unwrap());
+        let slow_lock = client.get_processing_lock().await.unwrap().unwrap();
+        assert!(client.acquire_processing_lock("next-request", 1900, 2800).await.unwrap());
+
+        assert!(!client.clear_processing_lock(&slow_lock).await.unwrap());
+        client.release_processing_lock("slow-request").await.unwrap();
+        assert_eq!(client.get_processing_lock().await.unwrap().unwrap().holder, "next-request");
+    }
+
+    #[tokio::test]
+    async fn test_take_preview_summary_reuses_fresh_cache_once() {
+        let client = mocks::MockSprintClient::new(None, None, None);
+        client.put_preview_cache(&PreviewCache { token: "trigger123".to_string(), created_at: 1000, ticket_summary: TicketSummary::default() }).await.unwrap();
+
+        let cached_summary = client.take_preview_summary("trigger123", 1100, 600).await.unwrap();
+
+        assert_eq!(cached_summary.map(|summary| summary.open_ticket_count), Some(TicketSummary::default().open_ticket_count));
+        assert!(client.get_preview_cache().await.unwrap().is_none());
+        assert!(client.take_preview_summary("trigger123", 1100, 600).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_sprint_archive_key_encodes_sprint_name() {
+        assert_eq!(sprint_archive_key("Sprint_101-b"), "archives/Sprint_101-b.json");
+        assert_eq!(sprint_archive_key("Q3 ../Sprint 2?"), "archives/Q3%20%2E%2E%2FSprint%202%3F.json");
+    }
+
+    #[test]
+    fn test_preview_cache_stores_tickets_sorted_by_id() {
+        let ticket = |id: &str| Ticket { details: TicketDetails { id: id.to_string(), state: TicketState::InProgress, ..TicketDetails::default() }, pr: None, ..Ticket::default() };
+        let preview_cache = PreviewCache { token: "trigger123".to_string(), created_at: 1000, ticket_summary: TicketSummary::from_tickets(vec![ticket("b"), ticket("c"), ticket("a")], false) };
+
+        let preview_cache_value = serde_json::to_value(&preview_cache).unwrap();
+
+        let open_ids: Vec<&str> = preview_cache_value["ticket_summary"]["open_tickets"].as_array().unwrap().iter().map(|ticket| ticket["details"]["id"].as_str().unwrap()).collect();
+        assert_eq!(open_ids, vec!["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_take_preview_summary_misses_stale_or_other_cache() {
+        let client = mocks::MockSprintClient::new(None, None, None);
+        assert!(client.take_preview_summary("trigger123", 1000, 600).await.unwrap().is_none());
+
+        client.put_preview_cache(&PreviewCache { token: "trigger123".to_string(), created_at: 1000, ticket_summary: TicketSummary::default() }).await.unwrap();
+
+        assert!(client.take_preview_summary("trigger456", 1100, 600).await.unwrap().is_none());
+        assert!(client.take_preview_summary("trigger123", 1601, 600).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_was_sprint_name_used_case_insensitive() {
+        let contexts = CumulativeSprintContexts {
+            history: vec![CumulativeSprintContext { name: "Sprint 1".to_string(), ..Default::default() }],
+        };
+
+        assert!(!contexts.was_sprint_name_used("sprint 1", SprintNameMatching::Exact));
+        assert!(contexts.was_sprint_name_used("sprint 1", SprintNameMatching::CaseInsensitive));
+        assert!(contexts.was_sprint_name_used(" SPRINT 1 ", SprintNameMatching::CaseInsensitive));
+        assert!(!contexts.was_sprint_name_used("Sprint 10", SprintNameMatching::CaseInsensitive));
+    }
+
+    #[test]
+    fn test_sprint_age_for_current_and_pre_history_sprints() {
+        let contexts = CumulativeSprintContexts {
+            history: vec![
+                CumulativeSprintContext { name: "Sprint 1".to_string(), end_date: "02/01/24".to_string(), ..CumulativeSprintContext::default() },
+                CumulativeSprintContext { name: "Sprint 2".to_string(), end_date: "03/01/24".to_string(), ..CumulativeSprintContext::default() },
+            ]
+        };
+
+        assert_eq!(contexts.sprint_age("Sprint 1", "01/15/24", "Sprint 3", SprintNameMatching::Exact), 2);
+        assert_eq!(contexts.sprint_age("Sprint 3", "03/02/24", "Sprint 3", SprintNameMatching::Exact), 0);
+        assert_eq!(contexts.sprint_age("Sprint 0", "12/01/23", "Sprint 3", SprintNameMatching::Exact), 2);
+        assert_eq!(contexts.sprint_age("Sprint 0", "02/15/24", "Sprint 3", SprintNameMatching::Exact), 1);
+    }
+
+    #[test]
+    fn test_count_sprints_since_case_insensitive() {
+        let contexts = CumulativeSprintContexts {
+            history: vec![
+                CumulativeSprintContext { name: "Sprint 1".to_string(), ..Default::default() },
+                CumulativeSprintContext { name: "Sprint 2".to_string(), ..Default::default() },
+            ],
+        };
+
+        assert_eq!(contexts.count_sprints_since("sprint 1", SprintNameMatching::CaseInsensitive), 2);
+        assert_eq!(contexts.count_sprints_since("sprint 1", SprintNameMatching::Exact), 0);
+    }
+
+    #[test]
+    fn test_backfill_percent_complete_from_archives() {
+        let mut contexts = CumulativeSprintContexts::default();
+        let archives = vec![SprintArchive {
+            name: "Sprint 102".to_string(),
+            sprint_ticket_count: 8,
+            completed_tickets: vec![DailyTicketContext::default(); 6],
+        }];
+
+        assert_eq!(contexts.backfill_percent_complete(&archives), 1);
+        assert_eq!(contexts.history[2].percent_complete, 75.0);
+        assert_eq!(contexts.history[2].completed_tickets_count, 6);
+        assert_eq!(contexts.history[0].percent_complete, 0.9);
+        assert_eq!(contexts.history[0].completed_tickets_count, 12);
+    }
+
+    #[test]
+    fn test_empty_archive_is_zero_percent_complete() {
+        let archive = SprintArchive { name: "Sprint 1".to_string(), sprint_ticket_count: 0, completed_tickets: vec![] };
+        assert_eq!(archive.percent_complete(), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_active_sprint_context_round_trips_through_storage() {
+        use crate::utils::s3::mocks::InMemoryJsonStorage;
+
+        let storage = InMemoryJsonStorage::default();
+        let sprint_data = ActiveSprintContext { goal_text: Some("Ship onboarding".to_string()), manual_deferrals: BTreeSet::from(["abc123".to_string()]), ..ActiveSprintContext::default() };
+
+        assert_eq!(storage.get_sprint_data().await.unwrap(), None);
+        storage.put_sprint_data(&sprint_data).await.unwrap();
+        assert_eq!(storage.get_sprint_data().await.unwrap(), Some(sprint_data));
+
+        storage.clear_sprint_data().await.unwrap();
+        assert_eq!(storage.get_sprint_data().await.unwrap(), None);
     }
 }
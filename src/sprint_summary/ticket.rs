@@ -1,6 +1,9 @@
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use crate::utils::config::Config;
 use crate::utils::date::{days_between, print_current_date};
+use crate::utils::localization::{Label, Localization};
 use crate::utils::slack_components::{link_element, text_element, user_element};
 use super::sprint_records::DailyTicketContext;
 use super::ticket_label::TicketLabel;
@@ -12,6 +15,22 @@ pub struct CheckRunDetails {
     pub details_url: String,
 }
 
+//github's mergeable_state, "unknown" while mergeability is still being computed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeableState {
+    Clean,
+    Dirty,
+    Blocked,
+    Behind,
+    Unstable,
+    Draft,
+    HasHooks,
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PullRequest {
     pub state: String,
@@ -19,13 +38,35 @@ pub struct PullRequest {
     pub is_draft: bool,
     pub action_required_check_runs: Vec<CheckRunDetails>,
     pub failing_check_runs: Vec<CheckRunDetails>,
+    #[serde(default)]
+    pub optional_failing_check_runs: Vec<CheckRunDetails>,
     pub merged: bool,
-    pub mergeable: Option<bool>
+    pub mergeable: Option<bool>,
+    #[serde(default)]
+    pub mergeable_state: MergeableState,
+    #[serde(default)]
+    pub checks_outdated: bool,
 }
 
 impl PullRequest {
+    //a null mergeable or a blocked/unknown state only means github is still working it out, not a conflict
+    pub fn has_conflicts(&self) -> bool {
+        self.mergeable == Some(false) || self.mergeable_state == MergeableState::Dirty
+    }
+
     pub fn is_blocked(&self) -> bool {
-        self.merged == false && (self.mergeable != Some(true) || !self.failing_check_runs.is_empty())
+        self.merged == false && (self.has_conflicts() || !self.failing_check_runs.is_empty())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Issue {
+    pub state: String,
+}
+
+impl Issue {
+    pub fn is_closed(&self) -> bool {
+        self.state == "closed"
     }
 }
 
@@ -41,7 +82,10 @@ pub struct TicketDetails {
     pub labels: Vec<TicketLabel>,
     pub checklist_items: u32,
     pub checked_checklist_items: u32,
+    #[serde(default)]
+    pub points: Option<u32>,
     pub pr_url: Option<String>,
+    pub issue_url: Option<String>,
     pub dependency_of: Option<TicketLink>,
 }
 
@@ -58,24 +102,55 @@ pub struct Ticket {
     pub added_on: String,
     pub last_moved_on: String,
     pub moved_out_of_sprint: bool,
+    pub reopened: bool,
     pub members: Vec<String>,
     pub details: TicketDetails,
     pub pr: Option<PullRequest>,
+    pub issue: Option<Issue>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TicketWarning {
+    MissingDescription,
+    MissingLabels,
+    MissingAssignees,
+    MissingPr,
+    UnmergedPr,
+    SlaExceeded,
 }
 
-const NEW_DAYS: i64 = 2;
+impl TicketWarning {
+    pub fn label(&self) -> Label {
+        match self {
+            TicketWarning::MissingDescription => Label::MissingDescriptionWarning,
+            TicketWarning::MissingLabels => Label::MissingLabelsWarning,
+            TicketWarning::MissingAssignees => Label::MissingAssigneesWarning,
+            TicketWarning::MissingPr => Label::MissingPrWarning,
+            TicketWarning::UnmergedPr => Label::UnmergedPrWarning,
+            TicketWarning::SlaExceeded => Label::SlaExceededWarning,
+        }
+    }
+}
 
 impl Ticket {
-    pub fn is_new(&self) -> bool {
-        days_between(Some(&self.added_on), &print_current_date()).unwrap() < NEW_DAYS
+    pub fn is_new(&self, config: &Config) -> bool {
+        self.is_new_within(config.new_ticket_days, config.summary_timezone)
+    }
+
+    fn is_new_within(&self, new_ticket_days: i64, timezone: Tz) -> bool {
+        days_between(&self.added_on, &print_current_date(timezone)).unwrap() < new_ticket_days
     }
 
     pub fn is_goal(&self) -> bool {
         self.details.labels.iter().any(|label| *label == TicketLabel::Goal)
     }    
 
-    fn ticket_name_new_emoji(&self) -> String {
-        if self.is_new() {
+    pub fn is_no_code(&self) -> bool {
+        self.details.labels.iter().any(|label| *label == TicketLabel::NoCode)
+    }
+
+    fn ticket_name_new_emoji(&self, config: &Config) -> String {
+        if self.is_new(config) {
             return "🆕".to_string();
         }
 
@@ -98,9 +173,26 @@ impl Ticket {
         "".to_string()
     }
 
-    fn annotated_ticket_name(&self) -> String {
+    fn ticket_name_reopened_emoji(&self) -> String {
+        if self.reopened {
+            return "🔁".to_string();
+        }
+
+        "".to_string()
+    }
+
+    fn completed_ticket_emoji(&self) -> String {
+        if self.is_goal() {
+            return "🏆".to_string();
+        }
+
+        "✅".to_string()
+    }
+
+    fn annotated_ticket_name(&self, config: &Config) -> String {
         let statuses = vec![
-            self.ticket_name_new_emoji(), 
+            self.ticket_name_new_emoji(config), 
+            self.ticket_name_reopened_emoji(),
             self.ticket_name_age_emoji(), 
             self.ticket_name_goal_emoji()
         ];
@@ -118,72 +210,94 @@ impl Ticket {
         }
     }
 
-    fn ticket_name_block(&self) -> Value {
-        link_element(&self.details.url, &self.annotated_ticket_name(), Some(json!({"bold": true, "strike": self.moved_out_of_sprint})))
+    fn ticket_name_block(&self, config: &Config) -> Value {
+        link_element(&self.details.url, &self.annotated_ticket_name(config), Some(json!({"bold": true, "strike": self.moved_out_of_sprint})))
     }    
 
-    fn missing_assignees_warning(&self) -> Option<String> {
+    fn missing_assignees_warning(&self) -> Option<TicketWarning> {
         if self.details.state > TicketState::InScope && self.members.is_empty() {
-            Some(" | Missing Assignees".to_string())
+            Some(TicketWarning::MissingAssignees)
         } else {
             None
         }
     }
 
-    fn missing_description_warning(&self) -> Option<String> {
+    fn missing_description_warning(&self) -> Option<TicketWarning> {
         if self.details.state > TicketState::InvestigationDiscussion && !self.details.has_description {
-            Some(" | Missing Description".to_string())
+            Some(TicketWarning::MissingDescription)
         } else {
             None
         }
     }
 
-    fn missing_labels_warning(&self) -> Option<String> {
+    fn missing_labels_warning(&self) -> Option<TicketWarning> {
         if self.details.state > TicketState::InvestigationDiscussion && !self.details.has_labels {
-            Some(" | Missing Labels".to_string())
+            Some(TicketWarning::MissingLabels)
         } else {
             None
         }
     }
 
-    fn missing_pr_warning(&self) -> Option<String> {
-        if self.details.state > TicketState::InProgress && self.pr.is_none() {
-            Some(" | Missing PR".to_string())
+    //a linked pr whose details weren't fetched isn't missing
+    fn missing_pr_warning(&self) -> Option<TicketWarning> {
+        if self.details.state > TicketState::InProgress && self.details.pr_url.is_none() && !self.is_no_code() {
+            Some(TicketWarning::MissingPr)
         } else {
             None
         }
     }
 
-    fn unmerged_pr_warning(&self) -> Option<String> {
-        if self.details.state > TicketState::PendingRelease {
-            match &self.pr {
-                Some(pr) if !pr.merged => Some(" | PR not merged".to_string()),
-                Some(pr) if pr.merged => None,
-                _ => Some(" | PR not merged".to_string())
+    fn unmerged_pr_warning(&self) -> Option<TicketWarning> {
+        if self.details.state > TicketState::PendingRelease && !self.is_no_code() {
+            match (&self.pr, &self.details.pr_url) {
+                (Some(pr), _) if !pr.merged => Some(TicketWarning::UnmergedPr),
+                (Some(_), _) => None,
+                //skipped under the time budget or failed to fetch, so whether it merged is unknown
+                (None, Some(_)) => None,
+                (None, None) => Some(TicketWarning::UnmergedPr),
             }
         } else {
             None
         }
     }
 
-    fn warning_blocks(&self) -> Vec<Value> {
-        let mut warnings = Vec::new();
+    //a ticket with several labels is held to the strictest of their SLAs
+    fn sla_exceeded_warning_within(&self, label_sla_days: &[(TicketLabel, i64)], timezone: Tz) -> Option<TicketWarning> {
+        if self.details.state == TicketState::Done {
+            return None;
+        }
+
+        let age = days_between(&self.added_on, &print_current_date(timezone)).ok()?;
+        if label_sla_days.iter().any(|(label, sla_days)| self.details.labels.contains(label) && age > *sla_days) {
+            Some(TicketWarning::SlaExceeded)
+        } else {
+            None
+        }
+    }
 
-        let checks = vec![
+    pub fn warnings(&self, config: &Config) -> Vec<TicketWarning> {
+        [
             self.missing_description_warning(),
             self.missing_labels_warning(),
             self.missing_assignees_warning(),
             self.missing_pr_warning(),
             self.unmerged_pr_warning(),
-        ];
+            self.sla_exceeded_warning_within(&config.label_sla_days, config.summary_timezone),
+        ].into_iter()
+            .flatten()
+            .collect()
+    }
 
-        if checks.iter().any(Option::is_some) {
-            warnings.push("\n⚠️".to_string());
+    fn warning_blocks(&self, localization: &Localization, config: &Config) -> Vec<Value> {
+        let warnings = self.warnings(config);
+        if warnings.is_empty() {
+            return vec![];
         }
 
-        warnings.extend(checks.into_iter().flatten());
-
-        warnings.iter().map(|warning| text_element(warning, Some(json!({"bold": true})))).collect()
+        std::iter::once("\n⚠️".to_string())
+            .chain(warnings.iter().map(|warning| format!(" | {}", localization.text(warning.label()))))
+            .map(|warning| text_element(&warning, Some(json!({"bold": true}))))
+            .collect()
     }
 
     fn pr_link_block(&self, pr: &PullRequest) -> Value {
@@ -207,27 +321,52 @@ impl Ticket {
     fn pr_merge_status_block(&self, pr: &PullRequest) -> Value {
         if pr.merged {
             text_element(" | PR Merged ✔️", None)
-        } else if pr.mergeable == Some(true) {
+        } else if pr.has_conflicts() {
+            text_element(" | Can't Merge (see GitHub for details)", Some(json!({"bold": true})))
+        } else {
             text_element(" | Pending Merge", None)
+        }
+    }
+
+    fn pr_checks_outdated_block(&self, pr: &PullRequest) -> Option<Value> {
+        if pr.checks_outdated {
+            Some(text_element(" | ⏳ Checks outdated", None))
         } else {
-            text_element(" | Can't Merge (see GitHub for details)", Some(json!({"bold": true})))
+            None
         }
     }
 
-    fn pr_failing_checks_block(&self, pr: &PullRequest) -> Vec<Value> {
+    //past max_shown, the rest of the checks are left to the PR's checks tab
+    fn check_run_elements(&self, check_runs: &[CheckRunDetails], style: Value, max_shown: Option<usize>) -> Vec<Value> {
+        let shown_count = max_shown.map_or(check_runs.len(), |max_shown| max_shown.min(check_runs.len()));
+        let mut elements: Vec<Value> = check_runs[..shown_count].iter()
+            .map(|check_run| link_element(&check_run.details_url, &check_run.name, Some(style.clone())))
+            .collect();
+
+        if shown_count < check_runs.len() {
+            elements.push(text_element(" ", None));
+            elements.push(link_element(&format!("{}/checks", self.details.pr_url.as_deref().unwrap_or_default()),
+                &format!("+{} more, view all on GitHub", check_runs.len() - shown_count),
+                None));
+        }
+
+        elements
+    }
+
+    fn pr_failing_checks_block(&self, pr: &PullRequest, max_shown: Option<usize>) -> Vec<Value> {
         let mut blocks = Vec::new();
         if !pr.failing_check_runs.is_empty() {
             blocks.push(text_element(" | Failing check runs: ", None));
-            for check_run in &pr.failing_check_runs {
-                blocks.push(link_element(&check_run.details_url, 
-                    &check_run.name, 
-                    Some(json!({"bold": true, "code": true}))));
-            }
+            blocks.extend(self.check_run_elements(&pr.failing_check_runs, json!({"bold": true, "code": true}), max_shown));
+        }
+        if !pr.optional_failing_check_runs.is_empty() {
+            blocks.push(text_element(" | Failing optional checks: ", None));
+            blocks.extend(self.check_run_elements(&pr.optional_failing_check_runs, json!({"code": true}), max_shown));
         }
         blocks
     }
 
-    fn pr_blocks(&self) -> Vec<Value> {
+    fn pr_blocks(&self, config: &Config) -> Vec<Value> {
         let mut blocks = vec![];
 
         if let Some(pr) = &self.pr {
@@ -237,35 +376,81 @@ impl Ticket {
                 blocks.push(comment_block);
             }
             blocks.push(self.pr_merge_status_block(pr));
-            blocks.extend(self.pr_failing_checks_block(pr));
+            if let Some(checks_outdated_block) = self.pr_checks_outdated_block(pr) {
+                blocks.push(checks_outdated_block);
+            }
+            blocks.extend(self.pr_failing_checks_block(pr, config.max_failing_checks_shown));
+        } else if let Some(pr_url) = &self.details.pr_url {
+            //the pr is still linked when its details were skipped or failed to fetch
+            blocks.push(text_element("\n", None));
+            blocks.push(link_element(pr_url, "View PR", None));
+            blocks.push(text_element(" | PR details unavailable", None));
+        }
+
+        blocks
+    }
+    
+    fn issue_blocks(&self) -> Vec<Value> {
+        let mut blocks = vec![];
+
+        if let (Some(issue), Some(issue_url)) = (&self.issue, &self.details.issue_url) {
+            blocks.push(text_element("\n", None));
+            blocks.push(link_element(issue_url, "View Issue", None));
+            if issue.is_closed() {
+                blocks.push(text_element(" | Issue Closed ✔️", None));
+            } else {
+                blocks.push(text_element(" | Issue Open", None));
+            }
         }
 
         blocks
     }
     
-    fn dependency_blocks(&self) -> Vec<Value> {
+    fn dependency_blocks(&self, localization: &Localization) -> Vec<Value> {
         let mut blocks = vec![];
         
         if let Some(dependency) = &self.details.dependency_of {
             blocks.push(text_element("\n", None));
-            blocks.push(text_element("Part of ", None));
+            blocks.push(text_element(&format!("{} ", localization.text(Label::PartOf)), None));
             blocks.push(link_element(&dependency.url, &dependency.name, None));
         }
 
         blocks
     }
 
-    fn checklist_blocks(&self) -> Vec<Value> {
+    fn is_nearly_done(&self, nearly_done_percent: f64) -> bool {
+        self.details.checklist_items > 0
+            && self.details.state != TicketState::Done
+            && (self.details.checked_checklist_items as f64 / self.details.checklist_items as f64) * 100.0 > nearly_done_percent
+    }
+
+    fn checklist_blocks(&self, nearly_done_percent: f64) -> Vec<Value> {
         let mut blocks = vec![];
         
         if self.details.checklist_items > 0 {
             blocks.push(text_element("\n", None));
             blocks.push(text_element(&format!("{}/{} completed", self.details.checked_checklist_items, self.details.checklist_items), None));
+
+            if self.is_nearly_done(nearly_done_percent) {
+                blocks.push(text_element(" 🔜 nearly done", None));
+            }
         }
 
         blocks
     }
 
+    //Goal already shows up in the ticket name, so only the other labels get an emoji here
+    pub fn label_blocks(&self) -> Vec<Value> {
+        let mut labels: Vec<&TicketLabel> = vec![];
+        for label in self.details.labels.iter().filter(|label| **label != TicketLabel::Goal) {
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+
+        labels.into_iter().map(|label| text_element(&format!(" {}", label.emoji()), None)).collect()
+    }
+
     fn member_blocks(&self) -> Vec<Value> {
         let mut blocks = vec![];
 
@@ -279,18 +464,35 @@ impl Ticket {
         blocks
     }
 
-    pub fn into_slack_blocks(&self) -> Value {
-        let mut ticket_elements = vec![
-            self.ticket_name_block()
-        ];
+    pub fn into_slack_blocks(&self, localization: &Localization, config: &Config) -> Value {
+        self.build_slack_blocks(vec![], localization, config)
+    }
+
+    pub fn into_hygiene_slack_blocks(&self, config: &Config) -> Value {
+        let mut ticket_elements = vec![self.ticket_name_block(config)];
+        ticket_elements.extend(self.member_blocks());
+
+        json!(ticket_elements)
+    }
+
+    pub fn into_completed_slack_blocks(&self, localization: &Localization, config: &Config) -> Value {
+        self.build_slack_blocks(vec![text_element(&format!("{} ", self.completed_ticket_emoji()), None)], localization, config)
+    }
+
+    fn build_slack_blocks(&self, mut ticket_elements: Vec<Value>, localization: &Localization, config: &Config) -> Value {
+        ticket_elements.push(self.ticket_name_block(config));
+
+        ticket_elements.extend(self.label_blocks());
         
-        ticket_elements.extend(self.warning_blocks());
+        ticket_elements.extend(self.warning_blocks(localization, config));
         
-        ticket_elements.extend(self.pr_blocks());
+        ticket_elements.extend(self.pr_blocks(config));
+
+        ticket_elements.extend(self.issue_blocks());
         
-        ticket_elements.extend(self.dependency_blocks());
+        ticket_elements.extend(self.dependency_blocks(localization));
         
-        ticket_elements.extend(self.checklist_blocks());
+        ticket_elements.extend(self.checklist_blocks(config.nearly_done_checklist_percent));
 
         ticket_elements.extend(self.member_blocks());
 
@@ -321,8 +523,10 @@ impl From<&DailyTicketContext> for Ticket {
         Ticket {
             members: vec![],
             pr: None,
+            issue: None,
             sprint_age: 0,
             moved_out_of_sprint: true,
+            reopened: false,
             added_in_sprint: record.added_in_sprint.clone(),
             added_on: record.added_on.clone(),
             last_moved_on: record.last_moved_on.clone(),
@@ -336,8 +540,10 @@ impl From<&DailyTicketContext> for Ticket {
                 labels: record.labels.clone().unwrap_or_else(Vec::new), 
                 checklist_items: 0,
                 checked_checklist_items: 0,  
+                points: None,
                 member_ids: vec![],
                 pr_url: None,      
+                issue_url: None,
                 dependency_of: record.dependency_of.clone()  
             }
         }
@@ -349,7 +555,7 @@ impl From<&DailyTicketContext> for Ticket {
 pub mod mocks {
     use crate::sprint_summary::{ticket::TicketDetails, ticket_state::TicketState};
 
-    use super::{PullRequest, Ticket};
+    use super::{MergeableState, PullRequest, Ticket};
     
     impl Default for PullRequest {
         fn default() -> Self {
@@ -358,9 +564,12 @@ pub mod mocks {
                 comments: 3,
                 merged: false,
                 mergeable: Some(true),
+                mergeable_state: MergeableState::Clean,
                 failing_check_runs: vec![],
+                optional_failing_check_runs: vec![],
                 state: "success".to_string(),
                 action_required_check_runs: vec![],
+                checks_outdated: false,
             }
         }
     }
@@ -375,10 +584,12 @@ pub mod mocks {
                 has_labels: false,
                 checklist_items: 5,
                 checked_checklist_items: 3,
+                points: None,
                 member_ids: vec![],
                 id: "abc123".to_string(),
                 labels: vec![],
                 pr_url: Some("http://github.com/example".to_string()),
+                issue_url: None,
                 dependency_of: None,
             }
         }
@@ -388,11 +599,13 @@ pub mod mocks {
         fn default() -> Self {
             Ticket {
                 moved_out_of_sprint: false,
+                reopened: false,
                 sprint_age: 1,
                 added_on: "04/20/24".to_string(),
                 details: TicketDetails::default(),
                 members: vec![],
                 pr: Some(PullRequest::default()),
+                issue: None,
                 added_in_sprint: "testsprint".to_string(),
                 last_moved_on: "03/20/24".to_string(),
             }
@@ -402,6 +615,8 @@ pub mod mocks {
 
 #[cfg(test)]
 mod tests {
+    use chrono_tz::US::Pacific;
+    use crate::utils::date::current_date;
     use super::*;
     
     #[test]
@@ -438,7 +653,7 @@ mod tests {
     #[test]
     fn test_pr_is_blocked_not_mergeable() {
         let pr = PullRequest { 
-            mergeable: None,
+            mergeable: Some(false),
             merged: false,
             ..PullRequest::default()
         };
@@ -446,6 +661,60 @@ mod tests {
         assert_eq!(pr.is_blocked(), true);
     }
 
+    #[test]
+    fn test_pr_is_not_blocked_while_mergeability_is_computed() {
+        let pr = PullRequest { 
+            mergeable: None,
+            mergeable_state: MergeableState::Unknown,
+            merged: false,
+            ..PullRequest::default()
+        };
+        
+        assert_eq!(pr.is_blocked(), false);
+    }
+
+    #[test]
+    fn test_pr_is_blocked_by_mergeable_state() {
+        let pr_in_state = |mergeable_state| PullRequest { mergeable: None, mergeable_state, ..PullRequest::default() };
+
+        assert!(!pr_in_state(MergeableState::Clean).is_blocked());
+        assert!(pr_in_state(MergeableState::Dirty).is_blocked());
+        assert!(!pr_in_state(MergeableState::Blocked).is_blocked());
+        assert!(!pr_in_state(MergeableState::Unknown).is_blocked());
+    }
+
+    #[test]
+    fn test_mergeable_state_parsed_from_github_names() {
+        let states: Vec<MergeableState> = serde_json::from_str(r#"["clean", "dirty", "blocked", "unknown", "has_hooks", "something_new"]"#).unwrap();
+
+        assert_eq!(states, vec![MergeableState::Clean, MergeableState::Dirty, MergeableState::Blocked, MergeableState::Unknown, MergeableState::HasHooks, MergeableState::Unknown]);
+    }
+
+    #[test]
+    fn test_issue_blocks_open_issue() {
+        let mut ticket = Ticket::default();
+        ticket.details.issue_url = Some("https://github.com/owner/repo/issues/1".to_string());
+        ticket.issue = Some(Issue { state: "open".to_string() });
+
+        let blocks = ticket.issue_blocks();
+        assert_eq!(blocks[1]["url"], "https://github.com/owner/repo/issues/1");
+        assert_eq!(blocks[2]["text"], " | Issue Open");
+    }
+
+    #[test]
+    fn test_issue_blocks_closed_issue() {
+        let mut ticket = Ticket::default();
+        ticket.details.issue_url = Some("https://github.com/owner/repo/issues/1".to_string());
+        ticket.issue = Some(Issue { state: "closed".to_string() });
+
+        assert_eq!(ticket.issue_blocks()[2]["text"], " | Issue Closed ✔️");
+    }
+
+    #[test]
+    fn test_issue_blocks_without_issue() {
+        assert!(Ticket::default().issue_blocks().is_empty());
+    }
+
     #[test]
     fn test_pr_is_blocked_merged() {
         let pr = PullRequest { 
@@ -460,15 +729,52 @@ mod tests {
     #[test]
     fn test_ticket_name_new_emoji_new() {
         let mut ticket = Ticket::default();
-        ticket.added_on = print_current_date();
-        assert_eq!(ticket.ticket_name_new_emoji(), "🆕");
+        ticket.added_on = print_current_date(Pacific);
+        assert_eq!(ticket.ticket_name_new_emoji(&Config::default()), "🆕");
     }
 
     #[test]
     fn test_ticket_name_new_emoji_not_new() {
         let mut ticket = Ticket::default();
         ticket.added_on = "01/01/01".to_string();
-        assert_eq!(ticket.ticket_name_new_emoji(), "");
+        assert_eq!(ticket.ticket_name_new_emoji(&Config::default()), "");
+    }
+
+    #[test]
+    fn test_ticket_just_inside_new_window() {
+        let mut ticket = Ticket::default();
+        ticket.added_on = (current_date(Pacific) - chrono::Duration::try_days(2).unwrap()).format("%m/%d/%y").to_string();
+        assert!(ticket.is_new_within(3, Pacific));
+    }
+
+    #[test]
+    fn test_ticket_just_outside_new_window() {
+        let mut ticket = Ticket::default();
+        ticket.added_on = (current_date(Pacific) - chrono::Duration::try_days(3).unwrap()).format("%m/%d/%y").to_string();
+        assert!(!ticket.is_new_within(3, Pacific));
+    }
+
+    #[test]
+    fn test_bug_past_sla_is_warned() {
+        let mut ticket = Ticket::default();
+        ticket.details.labels = vec![TicketLabel::FrontEnd, TicketLabel::Bug];
+        ticket.added_on = (current_date(Pacific) - chrono::Duration::try_days(4).unwrap()).format("%m/%d/%y").to_string();
+
+        assert_eq!(ticket.sla_exceeded_warning_within(&[(TicketLabel::Bug, 3), (TicketLabel::FrontEnd, 10)], Pacific), Some(TicketWarning::SlaExceeded));
+
+        ticket.details.state = TicketState::Done;
+        assert_eq!(ticket.sla_exceeded_warning_within(&[(TicketLabel::Bug, 3)], Pacific), None, "Completed tickets have met their SLA");
+    }
+
+    #[test]
+    fn test_bug_within_sla_is_not_warned() {
+        let mut ticket = Ticket::default();
+        ticket.details.labels = vec![TicketLabel::Bug];
+        ticket.added_on = (current_date(Pacific) - chrono::Duration::try_days(3).unwrap()).format("%m/%d/%y").to_string();
+
+        assert_eq!(ticket.sla_exceeded_warning_within(&[(TicketLabel::Bug, 3)], Pacific), None);
+        assert_eq!(ticket.sla_exceeded_warning_within(&[(TicketLabel::FrontEnd, 1)], Pacific), None, "Only the ticket's own labels have an SLA");
+        assert_eq!(ticket.sla_exceeded_warning_within(&[], Pacific), None);
     }
 
     #[test]
@@ -499,13 +805,68 @@ mod tests {
         assert_eq!(ticket.ticket_name_goal_emoji(), "");
     }
 
+    #[test]
+    fn test_ticket_name_reopened_emoji_reopened() {
+        let mut ticket = Ticket::default();
+        ticket.reopened = true;
+        assert_eq!(ticket.ticket_name_reopened_emoji(), "🔁");
+    }
+
+    #[test]
+    fn test_ticket_name_reopened_emoji_not_reopened() {
+        let ticket = Ticket::default();
+        assert_eq!(ticket.ticket_name_reopened_emoji(), "");
+    }
+
+    #[test]
+    fn test_label_blocks_with_multiple_labels() {
+        let mut ticket = Ticket::default();
+        ticket.details.labels = vec![TicketLabel::FrontEnd, TicketLabel::Bug, TicketLabel::Goal, TicketLabel::FrontEnd];
+        assert_eq!(ticket.label_blocks(), vec![text_element(" 📱", None), text_element(" 🐛", None)]);
+
+        let blocks = ticket.into_slack_blocks(&Localization::default(), &Config::default());
+        assert_eq!(blocks[1], text_element(" 📱", None));
+        assert_eq!(blocks[2], text_element(" 🐛", None));
+    }
+
+    #[test]
+    fn test_label_blocks_without_labels() {
+        let mut ticket = Ticket::default();
+        ticket.details.labels = vec![];
+        assert!(ticket.label_blocks().is_empty());
+    }
+
+    #[test]
+    fn test_label_blocks_with_only_goal_label() {
+        let mut ticket = Ticket::default();
+        ticket.details.labels = vec![TicketLabel::Goal];
+        assert!(ticket.label_blocks().is_empty());
+    }
+
+    #[test]
+    fn test_completed_slack_blocks_goal_ticket() {
+        let mut ticket = Ticket::default();
+        ticket.details.labels = vec![TicketLabel::Goal];
+        let blocks = ticket.into_completed_slack_blocks(&Localization::default(), &Config::default());
+        assert_eq!(blocks[0]["text"], "🏆 ");
+        assert_eq!(blocks[1]["text"], "🐌🏁 Mock Task");
+    }
+
+    #[test]
+    fn test_completed_slack_blocks_normal_ticket() {
+        let ticket = Ticket::default();
+        let blocks = ticket.into_completed_slack_blocks(&Localization::default(), &Config::default());
+        assert_eq!(blocks[0]["text"], "✅ ");
+        assert_eq!(blocks[1]["text"], "🐌 Mock Task");
+    }
+
     #[test]
     fn test_annotated_ticket_name_with_emojis() {
         let mut ticket = Ticket::default();
-        ticket.added_on = print_current_date();
+        ticket.added_on = print_current_date(Pacific);
         ticket.details.labels = vec![TicketLabel::Goal];
         ticket.sprint_age = 2;
-        assert_eq!(ticket.annotated_ticket_name(), "🆕🐌🐌🏁 Mock Task");
+        assert_eq!(ticket.annotated_ticket_name(&Config::default()), "🆕🐌🐌🏁 Mock Task");
     }
 
     #[test]
@@ -514,7 +875,7 @@ mod tests {
         ticket.added_on = (chrono::Local::now() - chrono::Duration::try_days(5).unwrap()).format("%m/%d/%y").to_string();
         ticket.details.labels = vec![];
         ticket.sprint_age = 0;
-        assert_eq!(ticket.annotated_ticket_name(), "Mock Task");
+        assert_eq!(ticket.annotated_ticket_name(&Config::default()), "Mock Task");
     }
     
     #[test]
@@ -531,7 +892,7 @@ mod tests {
             "url": "http://example.com/mock_ticket"
         });
 
-        assert_eq!(serde_json::to_value(ticket.ticket_name_block()).unwrap(), expected_blocks);
+        assert_eq!(serde_json::to_value(ticket.ticket_name_block(&Config::default())).unwrap(), expected_blocks);
     }
     
     #[test]
@@ -547,7 +908,7 @@ mod tests {
             "url": "http://example.com/mock_ticket"
         });
 
-        assert_eq!(serde_json::to_value(ticket.ticket_name_block()).unwrap(), expected_blocks);
+        assert_eq!(serde_json::to_value(ticket.ticket_name_block(&Config::default())).unwrap(), expected_blocks);
     }
 
     #[test]
@@ -571,7 +932,7 @@ mod tests {
         let mut ticket = Ticket::default();
         ticket.details.state = TicketState::Done;
         ticket.members = vec![];
-        assert_eq!(ticket.missing_assignees_warning(), Some(" | Missing Assignees".to_string()));
+        assert_eq!(ticket.missing_assignees_warning(), Some(TicketWarning::MissingAssignees));
     }
     
     #[test]
@@ -596,7 +957,7 @@ mod tests {
         let mut ticket = Ticket::default();
         ticket.details.state = TicketState::Done;
         ticket.details.has_description = false;
-        assert_eq!(ticket.missing_description_warning(), Some(" | Missing Description".to_string()));
+        assert_eq!(ticket.missing_description_warning(), Some(TicketWarning::MissingDescription));
     }
 
     #[test]
@@ -620,7 +981,7 @@ mod tests {
         let mut ticket = Ticket::default();
         ticket.details.state = TicketState::Done;
         ticket.details.has_labels = false;
-        assert_eq!(ticket.missing_labels_warning(), Some(" | Missing Labels".to_string()));
+        assert_eq!(ticket.missing_labels_warning(), Some(TicketWarning::MissingLabels));
     }
 
     #[test]
@@ -643,8 +1004,33 @@ mod tests {
     fn test_missing_pr_warning_with_missing_pr() {
         let mut ticket = Ticket::default();
         ticket.details.state = TicketState::Done;
+        ticket.details.pr_url = None;
+        ticket.pr = None;
+        assert_eq!(ticket.missing_pr_warning(), Some(TicketWarning::MissingPr));
+    }
+
+    #[test]
+    fn test_pr_warnings_skipped_pr_details() {
+        let mut ticket = Ticket::default();
+        ticket.details.state = TicketState::Done;
+        ticket.pr = None;
+        assert_eq!(ticket.missing_pr_warning(), None);
+        assert_eq!(ticket.unmerged_pr_warning(), None);
+    }
+    
+    #[test]
+    fn test_pr_warnings_no_code_done_ticket() {
+        let mut ticket = Ticket::default();
+        ticket.details.state = TicketState::Done;
+        ticket.details.labels = vec![TicketLabel::NoCode];
         ticket.pr = None;
-        assert_eq!(ticket.missing_pr_warning(), Some(" | Missing PR".to_string()));
+        assert_eq!(ticket.missing_pr_warning(), None);
+        assert_eq!(ticket.unmerged_pr_warning(), None);
+    }
+
+    #[test]
+    fn test_no_code_label_from_str() {
+        assert_eq!(TicketLabel::from_str("No-Code"), Some(TicketLabel::NoCode));
     }
     
     #[test]
@@ -659,8 +1045,9 @@ mod tests {
     fn test_unmerged_pr_warning_without_pr() {
         let mut ticket = Ticket::default();
         ticket.details.state = TicketState::Done;
+        ticket.details.pr_url = None;
         ticket.pr = None;
-        assert_eq!(ticket.unmerged_pr_warning(), Some(" | PR not merged".to_string()));
+        assert_eq!(ticket.unmerged_pr_warning(), Some(TicketWarning::UnmergedPr));
     }
     
     #[test]
@@ -669,7 +1056,7 @@ mod tests {
         ticket.details.state = TicketState::Done;
         ticket.pr = Some(PullRequest::default());
         ticket.pr.as_mut().unwrap().merged = false;
-        assert_eq!(ticket.unmerged_pr_warning(), Some(" | PR not merged".to_string()));
+        assert_eq!(ticket.unmerged_pr_warning(), Some(TicketWarning::UnmergedPr));
     }   
     
     #[test]
@@ -693,7 +1080,7 @@ mod tests {
             { "style": { "bold": true }, "text": " | Missing Assignees", "type": "text" }
         ]);
 
-        assert_eq!(serde_json::to_value(ticket.warning_blocks()).unwrap(), expected_blocks);
+        assert_eq!(serde_json::to_value(ticket.warning_blocks(&Localization::default(), &Config::default())).unwrap(), expected_blocks);
     }
     
     #[test]
@@ -704,7 +1091,7 @@ mod tests {
         ticket.members = vec!["user1".to_string(), "user2".to_string()];
         ticket.pr = Some(PullRequest::default());
 
-        assert!(ticket.warning_blocks().is_empty());
+        assert!(ticket.warning_blocks(&Localization::default(), &Config::default()).is_empty());
     }  
 
     #[test]
@@ -836,7 +1223,29 @@ mod tests {
                 "url": "http://example-check.com/2"
             }
         ]);
-        assert_eq!(serde_json::to_value(ticket.pr_failing_checks_block(&pr)).unwrap(), expected_blocks);
+        assert_eq!(serde_json::to_value(ticket.pr_failing_checks_block(&pr, None)).unwrap(), expected_blocks);
+    }
+
+    #[test]
+    fn test_pr_failing_checks_block_truncated_with_overflow_link() {
+        let pr = PullRequest {
+            failing_check_runs: (1..=4).map(|index| CheckRunDetails {
+                details_url: format!("http://example-check.com/{}", index),
+                name: format!("check{}", index),
+            }).collect(),
+            ..Default::default()
+        };
+        let ticket = Ticket {
+            details: TicketDetails { pr_url: Some("https://github.com/org/repo/pull/1".to_string()), ..TicketDetails::default() },
+            ..Ticket::default()
+        };
+
+        let blocks = ticket.pr_failing_checks_block(&pr, Some(2));
+
+        assert_eq!(blocks.len(), 5);
+        assert_eq!(blocks[2], link_element("http://example-check.com/2", "check2", Some(json!({"bold": true, "code": true}))));
+        assert_eq!(blocks[4], link_element("https://github.com/org/repo/pull/1/checks", "+2 more, view all on GitHub", None));
+        assert_eq!(ticket.pr_failing_checks_block(&pr, Some(4)).len(), 5);
     }
 
     #[test]
@@ -846,14 +1255,33 @@ mod tests {
             ..Default::default()
         };
         let ticket = Ticket::default();
-        assert!(ticket.pr_failing_checks_block(&pr).is_empty());
+        assert!(ticket.pr_failing_checks_block(&pr, None).is_empty());
     }
 
     #[test]
     fn test_pr_blocks_no_pr() {
         let mut ticket = Ticket::default();
+        ticket.details.pr_url = None;
         ticket.pr = None;
-        assert!(ticket.pr_blocks().is_empty());
+        assert!(ticket.pr_blocks(&Config::default()).is_empty());
+    }
+
+    #[test]
+    fn test_failed_pr_fetch_shows_details_unavailable() {
+        let mut ticket = Ticket::default();
+        ticket.details.state = TicketState::Done;
+        ticket.details.pr_url = Some("http://github.com/example".to_string());
+        ticket.pr = None;
+
+        let expected_blocks = json!([
+            {"type": "text", "text": "\n", "style": {}},
+            {"type": "link", "text": "View PR", "url": "http://github.com/example", "style": {}},
+            {"type": "text", "text": " | PR details unavailable", "style": {}},
+        ]);
+
+        assert_eq!(serde_json::to_value(ticket.pr_blocks(&Config::default())).unwrap(), expected_blocks);
+        assert!(!ticket.warnings(&Config::default()).contains(&TicketWarning::MissingPr));
+        assert!(!ticket.warnings(&Config::default()).contains(&TicketWarning::UnmergedPr));
     }
     
     #[test]
@@ -868,7 +1296,7 @@ mod tests {
             {"type": "text", "text": " | Pending Merge", "style": {}},
         ]);
 
-        assert_eq!(serde_json::to_value(ticket.pr_blocks()).unwrap(), expected_blocks);
+        assert_eq!(serde_json::to_value(ticket.pr_blocks(&Config::default())).unwrap(), expected_blocks);
     }
     
     #[test]
@@ -898,14 +1326,32 @@ mod tests {
             }
         ]);
 
-        assert_eq!(serde_json::to_value(ticket.dependency_blocks()).unwrap(), expected_blocks);
+        assert_eq!(serde_json::to_value(ticket.dependency_blocks(&Localization::default())).unwrap(), expected_blocks);
+    }
+
+    #[test]
+    fn test_ticket_blocks_use_localized_warnings() {
+        let mut ticket = Ticket::default();
+        ticket.details.has_description = true;
+        ticket.members = vec!["user1".to_string()];
+        ticket.pr = Some(PullRequest::default());
+        ticket.details.dependency_of = Some(TicketLink { name: "Greater Objective".to_string(), url: "test.com".to_string() });
+        let localization = Localization::new(std::collections::HashMap::from([
+            (Label::MissingLabelsWarning, "Sin etiquetas".to_string()),
+            (Label::PartOf, "Parte de".to_string()),
+        ]));
+
+        let blocks = serde_json::to_string(&ticket.into_slack_blocks(&localization, &Config::default())).unwrap();
+
+        assert!(blocks.contains(" | Sin etiquetas"));
+        assert!(blocks.contains("Parte de "));
     }
 
     #[test]
     fn test_dependency_blocks_does_not_exist() {
         let mut ticket = Ticket::default();
         ticket.details.dependency_of = None;
-        assert!(ticket.dependency_blocks().is_empty());
+        assert!(ticket.dependency_blocks(&Localization::default()).is_empty());
     }
 
     #[test]
@@ -927,14 +1373,51 @@ mod tests {
             }
         ]);
 
-        assert_eq!(serde_json::to_value(ticket.checklist_blocks()).unwrap(), expected_blocks);
+        assert_eq!(serde_json::to_value(ticket.checklist_blocks(80.0)).unwrap(), expected_blocks);
     }
 
     #[test]
     fn test_checklist_blocks_no_items() {
         let mut ticket = Ticket::default();
         ticket.details.checklist_items = 0;
-        assert!(ticket.checklist_blocks().is_empty());
+        assert!(ticket.checklist_blocks(80.0).is_empty());
+    }
+
+    #[test]
+    fn test_checklist_blocks_nearly_done() {
+        let mut ticket = Ticket::default();
+        ticket.details.state = TicketState::InProgress;
+        ticket.details.checked_checklist_items = 9;
+        ticket.details.checklist_items = 10;
+
+        let blocks = ticket.checklist_blocks(80.0);
+
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[1]["text"], "9/10 completed");
+        assert_eq!(blocks[2]["text"], " 🔜 nearly done");
+    }
+
+    #[test]
+    fn test_checklist_blocks_half_done_not_nearly_done() {
+        let mut ticket = Ticket::default();
+        ticket.details.state = TicketState::InProgress;
+        ticket.details.checked_checklist_items = 5;
+        ticket.details.checklist_items = 10;
+
+        let blocks = ticket.checklist_blocks(80.0);
+
+        assert_eq!(blocks.len(), 2);
+        assert!(!blocks.iter().any(|block| block["text"] == " 🔜 nearly done"));
+    }
+
+    #[test]
+    fn test_checklist_blocks_done_ticket_not_nearly_done() {
+        let mut ticket = Ticket::default();
+        ticket.details.state = TicketState::Done;
+        ticket.details.checked_checklist_items = 9;
+        ticket.details.checklist_items = 10;
+
+        assert_eq!(ticket.checklist_blocks(80.0).len(), 2);
     }
 
     #[test]
@@ -972,10 +1455,10 @@ mod tests {
     fn test_into_slack_blocks() {
         let ticket = Ticket::default();
         let expected = json!([
-            [ticket.ticket_name_block()],
-            ticket.warning_blocks(),     
-            ticket.pr_blocks(),          
-            ticket.checklist_blocks(),   
+            [ticket.ticket_name_block(&Config::default())],
+            ticket.warning_blocks(&Localization::default(), &Config::default()),     
+            ticket.pr_blocks(&Config::default()),          
+            ticket.checklist_blocks(Config::default().nearly_done_checklist_percent),   
             ticket.member_blocks(),      
             [{
                 "style": {},
@@ -988,6 +1471,46 @@ mod tests {
             .flat_map(|x| x.as_array().unwrap().clone())
             .collect::<Vec<_>>();
 
-        assert_eq!(serde_json::to_value(ticket.into_slack_blocks()).unwrap(), json!(merged_blocks));
+        assert_eq!(serde_json::to_value(ticket.into_slack_blocks(&Localization::default(), &Config::default())).unwrap(), json!(merged_blocks));
+    }
+
+    #[test]
+    fn test_warnings_list_every_missing_field() {
+        let ticket = Ticket {
+            details: TicketDetails { state: TicketState::PendingRelease, pr_url: None, ..TicketDetails::default() },
+            pr: None,
+            ..Ticket::default()
+        };
+
+        assert_eq!(ticket.warnings(&Config::default()), vec![TicketWarning::MissingDescription, TicketWarning::MissingLabels, TicketWarning::MissingAssignees, TicketWarning::MissingPr]);
+    }
+
+    #[test]
+    fn test_pr_blocks_show_outdated_checks() {
+        let mut ticket = Ticket::default();
+        assert!(!json!(ticket.pr_blocks(&Config::default())).to_string().contains("Checks outdated"));
+
+        ticket.pr = Some(PullRequest { checks_outdated: true, ..PullRequest::default() });
+        assert!(ticket.pr_blocks(&Config::default()).contains(&text_element(" | ⏳ Checks outdated", None)));
+    }
+
+    #[test]
+    fn test_pr_with_only_optional_failing_checks_is_not_blocked() {
+        let optional_failing = PullRequest {
+            mergeable: Some(true),
+            optional_failing_check_runs: vec![CheckRunDetails { name: "coverage".to_string(), details_url: "examplecheckrun.com".to_string() }],
+            ..PullRequest::default()
+        };
+        let required_failing = PullRequest {
+            mergeable: Some(true),
+            failing_check_runs: vec![CheckRunDetails { name: "build".to_string(), details_url: "examplecheckrun.com".to_string() }],
+            ..PullRequest::default()
+        };
+
+        assert!(!optional_failing.is_blocked());
+        assert!(required_failing.is_blocked());
+
+        let ticket = Ticket::default();
+        assert!(ticket.pr_failing_checks_block(&optional_failing, None).contains(&text_element(" | Failing optional checks: ", None)));
     }
-}
\ No newline at end of file
+}
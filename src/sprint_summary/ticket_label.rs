@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::tracing::warn;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Deserialize, Serialize)]
 pub enum TicketLabel {
@@ -9,6 +10,32 @@ pub enum TicketLabel {
     Bug,
     Minor,
     Blocked,
+    NoCode,
+}
+
+//point estimates are plain labels like "3pts", "5 points" or "1pt" rather than one of the known labels
+pub fn story_points(label_name: &str) -> Option<u32> {
+    let label_name = label_name.trim().to_lowercase();
+
+    ["points", "point", "pts", "pt"].iter()
+        .find_map(|suffix| label_name.strip_suffix(suffix))
+        .and_then(|points| points.trim().parse::<u32>().ok())
+}
+
+//LABEL_SLA_DAYS sets how many days a ticket of a label can stay open, e.g. "Bug=3,Front-End=10"
+pub fn parse_label_sla_days(sla_days: &str) -> Vec<(TicketLabel, i64)> {
+    let mut label_sla_days = vec![];
+
+    for entry in sla_days.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+        let parsed = entry.split_once('=')
+            .and_then(|(label, days)| Some((TicketLabel::from_str(label.trim())?, days.trim().parse::<i64>().ok().filter(|days| *days >= 0)?)));
+        match parsed {
+            Some(label_sla) => label_sla_days.push(label_sla),
+            None => warn!("Ignoring invalid entry {:?} in LABEL_SLA_DAYS", entry),
+        }
+    }
+
+    label_sla_days
 }
 
 impl TicketLabel {
@@ -21,10 +48,24 @@ impl TicketLabel {
             "Bug" => Some(TicketLabel::Bug),
             "Minor" => Some(TicketLabel::Minor),
             "Blocked" => Some(TicketLabel::Blocked),
+            "No-Code" => Some(TicketLabel::NoCode),
             _ => None,
         }
     }
 
+    pub fn name(&self) -> &str {
+        match self {
+            TicketLabel::Goal => "Goal",
+            TicketLabel::FrontEnd => "Front-End",
+            TicketLabel::BackEnd => "Back-End",
+            TicketLabel::Infra => "Infra",
+            TicketLabel::Bug => "Bug",
+            TicketLabel::Minor => "Minor",
+            TicketLabel::Blocked => "Blocked",
+            TicketLabel::NoCode => "No-Code",
+        }
+    }
+
     pub fn emoji(&self) -> &str {
         match self {
             TicketLabel::Goal => "🏁",
@@ -34,6 +75,18 @@ impl TicketLabel {
             TicketLabel::Bug => "🐛",
             TicketLabel::Minor => "🪶",
             TicketLabel::Blocked => "🚧",
+            TicketLabel::NoCode => "📝",
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_label_sla_days() {
+        assert_eq!(parse_label_sla_days("Bug=3, Front-End = 10,Unknown=2,Minor=soon,Infra"), vec![(TicketLabel::Bug, 3), (TicketLabel::FrontEnd, 10)]);
+        assert!(parse_label_sla_days("").is_empty());
+    }
+}
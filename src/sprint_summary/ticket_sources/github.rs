@@ -1,10 +1,12 @@
-use std::env;
+use chrono::{DateTime, Duration, FixedOffset};
 use serde::Deserialize;
+use serde_json::json;
 use anyhow::{Result, Error, anyhow};
+use regex::Regex;
 use reqwest::Client;
-use crate::{sprint_summary::ticket::{CheckRunDetails, PullRequest}, tracing::info};
+use crate::{sprint_summary::ticket::{CheckRunDetails, Issue, MergeableState, PullRequest}, tracing::{info, warn}, utils::{config::Config, retry::{send_with_retry, RetryPolicy}}};
 
-use super::PullRequestClient;
+use super::{IssueClient, PullRequestClient};
 
 #[derive(Deserialize)]
 struct GithubHead {
@@ -17,7 +19,29 @@ struct GithubPullRequest {
     comments: u32,
     draft: bool,
     merged: bool,
-    mergeable: Option<bool>
+    mergeable: Option<bool>,
+    #[serde(default)]
+    mergeable_state: MergeableState,
+}
+
+#[derive(Deserialize)]
+struct GithubCommitter {
+    date: String,
+}
+
+#[derive(Deserialize)]
+struct GithubCommitDetails {
+    committer: GithubCommitter,
+}
+
+#[derive(Deserialize)]
+struct GithubCommit {
+    commit: GithubCommitDetails,
+}
+
+#[derive(Deserialize)]
+struct GithubIssue {
+    state: String,
 }
 
 #[derive(Deserialize, Debug)]
@@ -25,6 +49,8 @@ struct GithubCheckRun {
     name: String,
     conclusion: Option<String>,
     details_url: String,
+    #[serde(default)]
+    completed_at: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -32,18 +58,225 @@ struct GithubCheckRuns {
     check_runs: Vec<GithubCheckRun>,
 }
 
-fn check_overall_status(check_runs: &GithubCheckRuns) -> (String, Vec<CheckRunDetails>, Vec<CheckRunDetails>) {
+const PULL_REQUEST_QUERY: &str = r#"
+query($owner: String!, $repo: String!, $number: Int!) {
+  repository(owner: $owner, name: $repo) {
+    pullRequest(number: $number) {
+      isDraft
+      merged
+      mergeable
+      mergeStateStatus
+      comments { totalCount }
+      commits(last: 1) {
+        nodes {
+          commit {
+            committedDate
+            statusCheckRollup {
+              contexts(first: 100) {
+                nodes {
+                  ... on CheckRun { name conclusion detailsUrl completedAt }
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+}"#;
+
+#[derive(Deserialize)]
+struct GraphqlResponse {
+    data: Option<GraphqlData>,
+    #[serde(default)]
+    errors: Vec<GraphqlError>,
+}
+
+#[derive(Deserialize)]
+struct GraphqlError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct GraphqlData {
+    repository: Option<GraphqlRepository>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlRepository {
+    pull_request: Option<GraphqlPullRequest>,
+}
+
+#[derive(Deserialize)]
+struct GraphqlConnection<T> {
+    #[serde(default = "Vec::new")]
+    nodes: Vec<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlCount {
+    total_count: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlPullRequest {
+    is_draft: bool,
+    merged: bool,
+    mergeable: String,
+    #[serde(default)]
+    merge_state_status: Option<String>,
+    comments: GraphqlCount,
+    commits: GraphqlConnection<GraphqlCommitNode>,
+}
+
+#[derive(Deserialize)]
+struct GraphqlCommitNode {
+    commit: GraphqlCommit,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlCommit {
+    committed_date: String,
+    status_check_rollup: Option<GraphqlStatusCheckRollup>,
+}
+
+#[derive(Deserialize)]
+struct GraphqlStatusCheckRollup {
+    contexts: GraphqlConnection<GraphqlCheckRun>,
+}
+
+//legacy commit statuses also show up as contexts, but only check runs match the fragment and fill these in
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlCheckRun {
+    name: Option<String>,
+    conclusion: Option<String>,
+    details_url: Option<String>,
+    completed_at: Option<String>,
+}
+
+impl GraphqlResponse {
+    fn into_pull_request(self, config: &Config) -> Result<PullRequest> {
+        if let Some(error) = self.errors.first() {
+            return Err(anyhow!("Github GraphQL error: {}", error.message));
+        }
+
+        let pr = self.data
+            .and_then(|data| data.repository)
+            .and_then(|repository| repository.pull_request)
+            .ok_or(anyhow!("Github GraphQL response is missing the pull request"))?;
+        let head_commit = pr.commits.nodes.into_iter().next().map(|node| node.commit);
+
+        //graphql enums are uppercase, while the rest of the bucketing expects the REST spelling
+        let checks = GithubCheckRuns {
+            check_runs: head_commit.as_ref()
+                .and_then(|commit| commit.status_check_rollup.as_ref())
+                .map_or(vec![], |rollup| rollup.contexts.nodes.iter()
+                    .filter_map(|check_run| Some(GithubCheckRun {
+                        name: check_run.name.clone()?,
+                        conclusion: check_run.conclusion.as_ref().map(|conclusion| conclusion.to_lowercase()),
+                        details_url: check_run.details_url.clone().unwrap_or_default(),
+                        completed_at: check_run.completed_at.clone(),
+                    }))
+                    .collect()),
+        };
+
+        let (state, failing_check_runs, action_required_check_runs, optional_failing_check_runs) = check_overall_status(&checks, &config.check_run_name_mappings, &config.required_check_runs);
+
+        let head_committed_at = head_commit.as_ref().and_then(|commit| DateTime::parse_from_rfc3339(&commit.committed_date).ok());
+        let checks_outdated = passing_checks_outdated(&checks, &state, head_committed_at, config.check_max_age_hours);
+
+        Ok(PullRequest {
+            state: if checks_outdated { "checks_outdated".to_string() } else { state },
+            comments: pr.comments.total_count,
+            merged: pr.merged,
+            mergeable: match pr.mergeable.as_str() {
+                "MERGEABLE" => Some(true),
+                "CONFLICTING" => Some(false),
+                _ => None,
+            },
+            //graphql spells these in uppercase, the REST names are their lowercase form
+            mergeable_state: pr.merge_state_status
+                .and_then(|status| serde_json::from_value(serde_json::Value::String(status.to_lowercase())).ok())
+                .unwrap_or_default(),
+            is_draft: pr.is_draft,
+            action_required_check_runs,
+            failing_check_runs,
+            optional_failing_check_runs,
+            checks_outdated,
+        })
+    }
+}
+
+//a check run name or regex, compiled once when the config is loaded rather than for every check run
+#[derive(Deserialize, Debug, Clone)]
+#[serde(from = "String")]
+pub struct CheckRunPattern {
+    pub pattern: String,
+    regex: Option<Regex>,
+}
+
+impl From<String> for CheckRunPattern {
+    fn from(pattern: String) -> Self {
+        let regex = Regex::new(&pattern).ok();
+        CheckRunPattern { pattern, regex }
+    }
+}
+
+impl CheckRunPattern {
+    pub fn new(pattern: &str) -> Self {
+        CheckRunPattern::from(pattern.to_string())
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        self.pattern == name || self.regex.as_ref().is_some_and(|regex| regex.is_match(name))
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CheckRunNameMapping {
+    pattern: CheckRunPattern,
+    label: String,
+}
+
+impl CheckRunNameMapping {
+    fn matches(&self, name: &str) -> bool {
+        self.pattern.matches(name)
+    }
+}
+
+//without a configured list every check is treated as required, like branch protection requiring all of them
+fn is_required_check(name: &str, required_check_runs: &[CheckRunPattern]) -> bool {
+    required_check_runs.is_empty() || required_check_runs.iter().any(|pattern| pattern.matches(name))
+}
+
+fn friendly_check_run_name(name: &str, mappings: &[CheckRunNameMapping]) -> String {
+    mappings.iter()
+        .find(|mapping| mapping.matches(name))
+        .map_or(name.to_string(), |mapping| mapping.label.clone())
+}
+
+fn check_overall_status(check_runs: &GithubCheckRuns, name_mappings: &[CheckRunNameMapping], required_check_runs: &[CheckRunPattern]) -> (String, Vec<CheckRunDetails>, Vec<CheckRunDetails>, Vec<CheckRunDetails>) {
     let mut failing_check_runs = Vec::new();
     let mut action_required_check_runs = Vec::new();
+    let mut optional_failing_check_runs = Vec::new();
 
     for check_run in &check_runs.check_runs {
         match check_run.conclusion.as_deref() {
-            Some("failure") => failing_check_runs.push(CheckRunDetails { 
-                name: check_run.name.clone(), 
+            Some("failure") if is_required_check(&check_run.name, required_check_runs) => failing_check_runs.push(CheckRunDetails { 
+                name: friendly_check_run_name(&check_run.name, name_mappings), 
+                details_url: check_run.details_url.clone(),
+            }),
+            Some("failure") => optional_failing_check_runs.push(CheckRunDetails {
+                name: friendly_check_run_name(&check_run.name, name_mappings),
                 details_url: check_run.details_url.clone(),
             }),
             Some("action_required") | None => action_required_check_runs.push(CheckRunDetails {
-                name: check_run.name.clone(),
+                name: friendly_check_run_name(&check_run.name, name_mappings),
                 details_url: check_run.details_url.clone(),
             }),
             _ => (),
@@ -52,37 +285,113 @@ fn check_overall_status(check_runs: &GithubCheckRuns) -> (String, Vec<CheckRunDe
 
     let state = if !failing_check_runs.is_empty() {
         "failure".to_string()
-    } else if action_required_check_runs.is_empty() {
-        "success".to_string()
-    } else {
+    } else if !action_required_check_runs.is_empty() {
         "action_required".to_string()
+    } else if !optional_failing_check_runs.is_empty() {
+        "optional_failure".to_string()
+    } else {
+        "success".to_string()
     };
 
-    (state, failing_check_runs, action_required_check_runs)
+    (state, failing_check_runs, action_required_check_runs, optional_failing_check_runs)
+}
+
+//checks that finished long before the head commit was made don't reflect the code being reviewed
+fn checks_outdated(check_runs: &GithubCheckRuns, head_committed_at: DateTime<FixedOffset>, max_age: Duration) -> bool {
+    check_runs.check_runs.iter()
+        .filter_map(|check_run| check_run.completed_at.as_deref())
+        .filter_map(|completed_at| DateTime::parse_from_rfc3339(completed_at).ok())
+        .max()
+        .is_some_and(|last_completed_at| head_committed_at - last_completed_at > max_age)
+}
+
+//only passing checks can be outdated, and without the head commit's date their freshness is unknown so they're trusted
+fn passing_checks_outdated(check_runs: &GithubCheckRuns, state: &str, head_committed_at: Option<DateTime<FixedOffset>>, check_max_age_hours: Option<i64>) -> bool {
+    match (check_max_age_hours, head_committed_at) {
+        (Some(max_age_hours), Some(head_committed_at)) if state == "success" => checks_outdated(check_runs, head_committed_at, Duration::hours(max_age_hours)),
+        _ => false,
+    }
+}
+
+pub struct GithubClient<'a> {
+    client: &'a Client,
+    config: &'a Config,
+}
+
+impl<'a> GithubClient<'a> {
+    pub fn new(client: &'a Client, config: &'a Config) -> Self {
+        GithubClient { client, config }
+    }
+
+    async fn fetch_committed_at(&self, owner: &str, repo: &str, sha: &str) -> Result<DateTime<FixedOffset>> {
+        let commit_url = format!("https://api.github.com/repos/{}/{}/commits/{}", owner, repo, sha);
+
+        info!("Fetching head commit: {:?}", commit_url);
+
+        let commit: GithubCommit = send_with_retry(&RetryPolicy::default(), || self.client.get(&commit_url)
+            .bearer_auth(&self.config.github_token)
+            .header("User-Agent", "daily_summary_request")
+            .header("Accept", "application/vnd.github.v3+json")
+            .send())
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        DateTime::parse_from_rfc3339(&commit.commit.committer.date).map_err(|e| anyhow!("Failed to parse commit date {}: {}", commit.commit.committer.date, e))
+    }
+
+    //fetches the pr and its head commit's checks in one request instead of two or three REST calls
+    async fn fetch_pr_details_graphql(&self, owner: &str, repo: &str, number: &str) -> Result<PullRequest> {
+        let number: i64 = number.parse()?;
+
+        info!("Fetching details for PR {}/{}#{} with GraphQL", owner, repo, number);
+
+        let query = json!({
+            "query": PULL_REQUEST_QUERY,
+            "variables": { "owner": owner, "repo": repo, "number": number },
+        });
+
+        let response: GraphqlResponse = send_with_retry(&RetryPolicy::default(), || self.client.post("https://api.github.com/graphql")
+            .bearer_auth(&self.config.github_token)
+            .header("User-Agent", "daily_summary_request")
+            .json(&query)
+            .send())
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        response.into_pull_request(self.config)
+    }
 }
 
-impl PullRequestClient for Client {
+impl PullRequestClient for GithubClient<'_> {
     async fn fetch_pr_details(&self, pr_url: &str) -> Result<PullRequest, Error> {
-        let github_token = env::var("USER_GITHUB_TOKEN").expect("USER_GITHUB_TOKEN environment variable should exist");
+        let github_token = &self.config.github_token;
     
         info!("Going to get status checks from Github PR: {:?}", pr_url);
     
         let re = regex::Regex::new(r"github\.com/(?P<owner>[^/]+)/(?P<repo>[^/]+)/pull/(?P<number>\d+)").unwrap();
-        let caps = re.captures(pr_url).expect("Failed to parse GitHub PR URL");
+        let caps = re.captures(pr_url).ok_or(anyhow!("Failed to parse GitHub PR URL: {}", pr_url))?;
     
         let owner = caps.name("owner").unwrap().as_str();
         let repo = caps.name("repo").unwrap().as_str();
         let number = caps.name("number").unwrap().as_str();
+
+        if self.config.github_graphql {
+            return self.fetch_pr_details_graphql(owner, repo, number).await;
+        }
     
         let pr_details_url = format!("https://api.github.com/repos/{}/{}/pulls/{}", owner, repo, number);
         
         info!("Fetching details for PR: {:?}", pr_details_url);
     
-        let pr_response = self.get(&pr_details_url)
-            .bearer_auth(github_token.clone())
+        let pr_response = send_with_retry(&RetryPolicy::default(), || self.client.get(&pr_details_url)
+            .bearer_auth(github_token)
             .header("User-Agent", "daily_summary_request")
             .header("Accept", "application/vnd.github.v3+json")
-            .send()
+            .send())
             .await?
             .error_for_status()?;
         
@@ -94,25 +403,40 @@ impl PullRequestClient for Client {
     
         info!("Fetching status checks for commit: {:?}", checks_url);
     
-        let checks_response = self.get(&checks_url)
-            .bearer_auth(github_token.clone())
+        let checks_response = send_with_retry(&RetryPolicy::default(), || self.client.get(&checks_url)
+            .bearer_auth(github_token)
             .header("User-Agent", "daily_summary_request")
             .header("Accept", "application/vnd.github.v3+json")
-            .send()
+            .send())
             .await;
             
         info!("Github status checks response body: {:?}", checks_response);
     
-        let (state, failing_check_runs, action_required_check_runs) = match checks_response {
+        let (state, failing_check_runs, action_required_check_runs, optional_failing_check_runs, checks_outdated) = match checks_response {
             Ok(response) => {
                 if response.status() == reqwest::StatusCode::FORBIDDEN {
                     // Directly set the state to "success" if a 403 Forbidden response is encountered
-                    ("success".to_string(), vec![], vec![])
+                    ("success".to_string(), vec![], vec![], vec![], false)
                 } else {
                     // Process normally if response is not 403 Forbidden
                     let checks_response = response.error_for_status()?;
                     let checks = checks_response.json::<GithubCheckRuns>().await?;
-                    check_overall_status(&checks)
+                    let (state, failing_check_runs, action_required_check_runs, optional_failing_check_runs) = check_overall_status(&checks, &self.config.check_run_name_mappings, &self.config.required_check_runs);
+
+                    //the commit is only looked up when its date could change the outcome
+                    let head_committed_at = match self.config.check_max_age_hours {
+                        Some(_) if state == "success" => self.fetch_committed_at(owner, repo, &pr.head.sha).await
+                            .map_err(|e| warn!("Failed to fetch head commit {} of {}: {}", pr.head.sha, pr_url, e))
+                            .ok(),
+                        _ => None,
+                    };
+                    let checks_outdated = passing_checks_outdated(&checks, &state, head_committed_at, self.config.check_max_age_hours);
+
+                    if checks_outdated {
+                        ("checks_outdated".to_string(), failing_check_runs, action_required_check_runs, optional_failing_check_runs, true)
+                    } else {
+                        (state, failing_check_runs, action_required_check_runs, optional_failing_check_runs, false)
+                    }
                 }
             },
             Err(e) => {
@@ -129,14 +453,46 @@ impl PullRequestClient for Client {
                 comments: pr.comments,
                 merged: pr.merged,
                 mergeable: pr.mergeable,
+                mergeable_state: pr.mergeable_state,
                 is_draft: pr.draft,
                 action_required_check_runs,
-                failing_check_runs
+                failing_check_runs,
+                optional_failing_check_runs,
+                checks_outdated,
             }
         )
     }    
 }
 
+impl IssueClient for GithubClient<'_> {
+    async fn fetch_issue_details(&self, issue_url: &str) -> Result<Issue, Error> {
+        let github_token = &self.config.github_token;
+
+        let re = regex::Regex::new(r"github\.com/(?P<owner>[^/]+)/(?P<repo>[^/]+)/issues/(?P<number>\d+)").unwrap();
+        let caps = re.captures(issue_url).ok_or(anyhow!("Failed to parse GitHub issue URL: {}", issue_url))?;
+
+        let owner = caps.name("owner").unwrap().as_str();
+        let repo = caps.name("repo").unwrap().as_str();
+        let number = caps.name("number").unwrap().as_str();
+
+        let issue_details_url = format!("https://api.github.com/repos/{}/{}/issues/{}", owner, repo, number);
+
+        info!("Fetching details for issue: {:?}", issue_details_url);
+
+        let issue: GithubIssue = self.client.get(&issue_details_url)
+            .bearer_auth(github_token)
+            .header("User-Agent", "daily_summary_request")
+            .header("Accept", "application/vnd.github.v3+json")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(Issue { state: issue.state })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -149,11 +505,12 @@ mod tests {
                     name: "Build".to_string(),
                     details_url: "http://example.com/build".to_string(),
                     conclusion: Some("success".to_string()),
+                    completed_at: None,
                 },
             ],
         };
 
-        let (state, failing, action_required) = check_overall_status(&checks);
+        let (state, failing, action_required, _) = check_overall_status(&checks, &[], &[]);
         assert_eq!(state, "success");
         assert!(failing.is_empty());
         assert!(action_required.is_empty());
@@ -167,11 +524,12 @@ mod tests {
                     name: "Build".to_string(),
                     details_url: "http://example.com/build".to_string(),
                     conclusion: Some("failure".to_string()),
+                    completed_at: None,
                 },
             ],
         };
 
-        let (state, failing, action_required) = check_overall_status(&checks);
+        let (state, failing, action_required, _) = check_overall_status(&checks, &[], &[]);
         assert_eq!(state, "failure");
         assert_eq!(failing[0], CheckRunDetails { name: "Build".to_string(), details_url: "http://example.com/build".to_string() });
         assert!(action_required.is_empty());
@@ -185,11 +543,12 @@ mod tests {
                     name: "Deploy".to_string(),
                     details_url: "http://example.com/deploy".to_string(),
                     conclusion: None,
+                    completed_at: None,
                 },
             ],
         };
 
-        let (state, failing, action_required) = check_overall_status(&checks);
+        let (state, failing, action_required, _) = check_overall_status(&checks, &[], &[]);
         assert_eq!(state, "action_required");
         assert!(failing.is_empty());
         assert_eq!(action_required[0], CheckRunDetails { name: "Deploy".to_string(), details_url: "http://example.com/deploy".to_string() });
@@ -203,16 +562,18 @@ mod tests {
                     name: "Build".to_string(),
                     details_url: "http://example.com/build".to_string(),
                     conclusion: Some("failure".to_string()),
+                    completed_at: None,
                 },
                 GithubCheckRun {
                     name: "Deploy".to_string(),
                     details_url: "http://example.com/deploy".to_string(),
                     conclusion: None,
+                    completed_at: None,
                 },
             ],
         };
 
-        let (state, failing, action_required) = check_overall_status(&checks);
+        let (state, failing, action_required, _) = check_overall_status(&checks, &[], &[]);
         assert_eq!(state, "failure");
         assert_eq!(failing[0], CheckRunDetails { name: "Build".to_string(), details_url: "http://example.com/build".to_string() });
         assert_eq!(action_required[0], CheckRunDetails { name: "Deploy".to_string(), details_url: "http://example.com/deploy".to_string() });
@@ -224,9 +585,191 @@ mod tests {
             check_runs: vec![],
         };
 
-        let (state, failing, action_required) = check_overall_status(&checks);
+        let (state, failing, action_required, _) = check_overall_status(&checks, &[], &[]);
         assert_eq!(state, "success");
         assert!(failing.is_empty());
         assert!(action_required.is_empty());
     }
+
+    #[test]
+    fn test_check_run_names_mapped_to_friendly_labels() {
+        let checks = GithubCheckRuns {
+            check_runs: vec![
+                GithubCheckRun {
+                    name: "build (ubuntu-latest, 18.x)".to_string(),
+                    details_url: "http://example.com/build".to_string(),
+                    conclusion: Some("failure".to_string()),
+                    completed_at: None,
+                },
+                GithubCheckRun {
+                    name: "lint".to_string(),
+                    details_url: "http://example.com/lint".to_string(),
+                    conclusion: None,
+                    completed_at: None,
+                },
+            ],
+        };
+        let name_mappings = vec![
+            CheckRunNameMapping { pattern: CheckRunPattern::new(r"^build \(.*\)$"), label: "Build".to_string() },
+            CheckRunNameMapping { pattern: CheckRunPattern::new("lint"), label: "Lint".to_string() },
+        ];
+
+        let (_, failing, action_required, _) = check_overall_status(&checks, &name_mappings, &[]);
+        assert_eq!(failing[0], CheckRunDetails { name: "Build".to_string(), details_url: "http://example.com/build".to_string() });
+        assert_eq!(action_required[0], CheckRunDetails { name: "Lint".to_string(), details_url: "http://example.com/lint".to_string() });
+    }
+
+    #[test]
+    fn test_unmapped_check_run_name_unchanged() {
+        let name_mappings = vec![
+            CheckRunNameMapping { pattern: CheckRunPattern::new("^deploy$"), label: "Deploy".to_string() },
+        ];
+
+        assert_eq!(friendly_check_run_name("build (ubuntu-latest, 18.x)", &name_mappings), "build (ubuntu-latest, 18.x)");
+    }
+
+    fn completed_checks(completed_at: &[&str]) -> GithubCheckRuns {
+        GithubCheckRuns {
+            check_runs: completed_at.iter().map(|completed_at| GithubCheckRun {
+                name: "Build".to_string(),
+                details_url: "http://example.com/build".to_string(),
+                conclusion: Some("success".to_string()),
+                completed_at: Some(completed_at.to_string()),
+            }).collect(),
+        }
+    }
+
+    #[test]
+    fn test_fresh_checks_are_not_outdated() {
+        let head_committed_at = DateTime::parse_from_rfc3339("2024-05-03T12:00:00Z").unwrap();
+        let checks = completed_checks(&["2024-05-01T09:00:00Z", "2024-05-03T11:30:00Z"]);
+
+        assert!(!checks_outdated(&checks, head_committed_at, Duration::hours(24)));
+    }
+
+    #[test]
+    fn test_stale_checks_are_outdated() {
+        let head_committed_at = DateTime::parse_from_rfc3339("2024-05-03T12:00:00Z").unwrap();
+        let checks = completed_checks(&["2024-05-01T09:00:00Z", "2024-05-01T10:00:00Z"]);
+
+        assert!(checks_outdated(&checks, head_committed_at, Duration::hours(24)));
+        assert!(!checks_outdated(&GithubCheckRuns { check_runs: vec![] }, head_committed_at, Duration::hours(24)));
+    }
+
+    #[test]
+    fn test_checks_trusted_when_head_commit_date_unknown() {
+        let head_committed_at = DateTime::parse_from_rfc3339("2024-05-03T12:00:00Z").unwrap();
+        let checks = completed_checks(&["2024-05-01T09:00:00Z"]);
+
+        assert!(passing_checks_outdated(&checks, "success", Some(head_committed_at), Some(24)));
+        assert!(!passing_checks_outdated(&checks, "success", None, Some(24)));
+        assert!(!passing_checks_outdated(&checks, "failure", Some(head_committed_at), Some(24)));
+        assert!(!passing_checks_outdated(&checks, "success", Some(head_committed_at), None));
+    }
+
+    #[test]
+    fn test_failing_checks_split_into_required_and_optional() {
+        let checks = GithubCheckRuns {
+            check_runs: vec![
+                GithubCheckRun {
+                    name: "build".to_string(),
+                    details_url: "http://example.com/build".to_string(),
+                    conclusion: Some("failure".to_string()),
+                    completed_at: None,
+                },
+                GithubCheckRun {
+                    name: "coverage".to_string(),
+                    details_url: "http://example.com/coverage".to_string(),
+                    conclusion: Some("failure".to_string()),
+                    completed_at: None,
+                },
+            ],
+        };
+
+        let (state, failing, _, optional_failing) = check_overall_status(&checks, &[], &[CheckRunPattern::new("^build$")]);
+        assert_eq!(state, "failure");
+        assert_eq!(failing, vec![CheckRunDetails { name: "build".to_string(), details_url: "http://example.com/build".to_string() }]);
+        assert_eq!(optional_failing, vec![CheckRunDetails { name: "coverage".to_string(), details_url: "http://example.com/coverage".to_string() }]);
+    }
+
+    #[test]
+    fn test_only_optional_checks_failing() {
+        let checks = GithubCheckRuns {
+            check_runs: vec![
+                GithubCheckRun {
+                    name: "coverage".to_string(),
+                    details_url: "http://example.com/coverage".to_string(),
+                    conclusion: Some("failure".to_string()),
+                    completed_at: None,
+                },
+            ],
+        };
+
+        let (state, failing, _, optional_failing) = check_overall_status(&checks, &[], &[CheckRunPattern::new("build")]);
+        assert_eq!(state, "optional_failure");
+        assert!(failing.is_empty());
+        assert_eq!(optional_failing.len(), 1);
+
+        let (state, failing, _, optional_failing) = check_overall_status(&checks, &[], &[]);
+        assert_eq!(state, "failure");
+        assert_eq!(failing.len(), 1);
+        assert!(optional_failing.is_empty());
+    }
+
+    fn graphql_response(mergeable: &str, contexts: &str) -> GraphqlResponse {
+        serde_json::from_str(&format!(r#"{{
+            "data": {{
+                "repository": {{
+                    "pullRequest": {{
+                        "isDraft": false,
+                        "merged": false,
+                        "mergeable": "{}",
+                        "comments": {{ "totalCount": 3 }},
+                        "commits": {{ "nodes": [{{ "commit": {{
+                            "committedDate": "2024-05-03T12:00:00Z",
+                            "statusCheckRollup": {{ "contexts": {{ "nodes": {} }} }}
+                        }} }}] }}
+                    }}
+                }}
+            }}
+        }}"#, mergeable, contexts)).unwrap()
+    }
+
+    #[test]
+    fn test_graphql_response_into_pull_request() {
+        let response = graphql_response("MERGEABLE", r#"[
+            { "name": "build", "conclusion": "FAILURE", "detailsUrl": "http://example.com/build", "completedAt": "2024-05-03T12:30:00Z" },
+            { "name": "deploy", "conclusion": null, "detailsUrl": "http://example.com/deploy", "completedAt": null },
+            {}
+        ]"#);
+
+        let pr = response.into_pull_request(&Config::default()).unwrap();
+        assert_eq!(pr.state, "failure");
+        assert_eq!(pr.comments, 3);
+        assert_eq!(pr.mergeable, Some(true));
+        assert!(!pr.merged && !pr.is_draft);
+        assert_eq!(pr.failing_check_runs, vec![CheckRunDetails { name: "build".to_string(), details_url: "http://example.com/build".to_string() }]);
+        assert_eq!(pr.action_required_check_runs, vec![CheckRunDetails { name: "deploy".to_string(), details_url: "http://example.com/deploy".to_string() }]);
+    }
+
+    #[test]
+    fn test_graphql_response_with_outdated_checks() {
+        let config = Config { check_max_age_hours: Some(24), ..Config::default() };
+        let response = graphql_response("CONFLICTING", r#"[
+            { "name": "build", "conclusion": "SUCCESS", "detailsUrl": "http://example.com/build", "completedAt": "2024-05-01T09:00:00Z" }
+        ]"#);
+
+        let pr = response.into_pull_request(&config).unwrap();
+        assert_eq!(pr.state, "checks_outdated");
+        assert!(pr.checks_outdated);
+        assert_eq!(pr.mergeable, Some(false));
+    }
+
+    #[test]
+    fn test_graphql_errors_are_surfaced() {
+        let response: GraphqlResponse = serde_json::from_str(r#"{"data": {"repository": null}, "errors": [{"message": "Could not resolve to a Repository"}]}"#).unwrap();
+
+        let error = response.into_pull_request(&Config::default()).unwrap_err();
+        assert_eq!(error.to_string(), "Github GraphQL error: Could not resolve to a Repository");
+    }
 }
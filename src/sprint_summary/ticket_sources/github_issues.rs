@@ -0,0 +1,391 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use reqwest::{header::LINK, Client};
+use anyhow::{Result, Error};
+use futures::stream::{self, StreamExt};
+use crate::{sprint_summary::{ticket::{Issue, PullRequest, TicketDetails}, ticket_label::{story_points, TicketLabel}, ticket_state::TicketState}, tracing::{info, warn}};
+use crate::utils::config::Config;
+use crate::utils::date::{current_date, parse_flexible_date};
+use crate::utils::time_budget::TimeBudget;
+
+use super::github::GithubClient;
+use super::{IssueClient, PullRequestClient, TicketDetailsClient, MAX_CONCURRENT_GITHUB_REQUESTS};
+
+#[derive(Debug, Deserialize)]
+struct GithubIssueLabel {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubIssueAssignee {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRepoIssue {
+    id: u64,
+    number: u64,
+    title: String,
+    state: String,
+    html_url: String,
+    body: Option<String>,
+    labels: Vec<GithubIssueLabel>,
+    assignees: Vec<GithubIssueAssignee>,
+    #[serde(default)]
+    closed_at: Option<String>,
+    //the issues endpoint lists pull requests too, they're told apart by this field
+    pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubTimelineIssue {
+    html_url: String,
+    pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubTimelineSource {
+    issue: Option<GithubTimelineIssue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubTimelineEvent {
+    event: String,
+    source: Option<GithubTimelineSource>,
+}
+
+fn issue_state(issue: &GithubRepoIssue, in_progress_label: &str) -> TicketState {
+    if issue.state == "closed" {
+        TicketState::Done
+    } else if issue.labels.iter().any(|label| label.name.eq_ignore_ascii_case(in_progress_label)) {
+        TicketState::InProgress
+    } else {
+        TicketState::InScope
+    }
+}
+
+fn closed_since(issue: &GithubRepoIssue, sprint_start: NaiveDate, timezone: Tz) -> bool {
+    issue.closed_at.as_deref()
+        .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+        .is_some_and(|date| date.with_timezone(&timezone).date_naive() >= sprint_start)
+}
+
+//the sprint starts at local midnight, which github's since parameter wants in utc
+fn start_of_day_utc(date: NaiveDate, timezone: Tz) -> String {
+    let midnight = date.and_time(NaiveTime::MIN);
+
+    timezone.from_local_datetime(&midnight)
+        .earliest()
+        .map_or(midnight, |start| start.naive_utc())
+        .format("%Y-%m-%dT%H:%M:%SZ")
+        .to_string()
+}
+
+//github paginates with a header like <https://api.github.com/...&page=2>; rel="next", <...>; rel="last"
+fn next_page_url(link_header: &str) -> Option<String> {
+    link_header.split(',')
+        .find(|link| link.split(';').skip(1).any(|param| param.trim() == r#"rel="next""#))
+        .and_then(|link| link.split(';').next())
+        .map(|url| url.trim().trim_start_matches('<').trim_end_matches('>').to_string())
+}
+
+//task list items in the issue body stand in for trello checklists
+fn count_task_list_items(body: &str) -> (u32, u32) {
+    body.lines()
+        .map(str::trim_start)
+        .fold((0, 0), |(total, checked), line| {
+            if line.starts_with("- [ ]") || line.starts_with("* [ ]") {
+                (total + 1, checked)
+            } else if line.starts_with("- [x]") || line.starts_with("- [X]") || line.starts_with("* [x]") || line.starts_with("* [X]") {
+                (total + 1, checked + 1)
+            } else {
+                (total, checked)
+            }
+        })
+}
+
+//the most recently referencing pull request is treated as the one working on the issue
+fn linked_pr_url(events: &[GithubTimelineEvent]) -> Option<String> {
+    events.iter()
+        .filter(|event| event.event == "cross-referenced")
+        .filter_map(|event| event.source.as_ref()?.issue.as_ref())
+        .filter(|issue| issue.pull_request.is_some())
+        .map(|issue| issue.html_url.clone())
+        .last()
+}
+
+fn into_ticket_details(issue: GithubRepoIssue, in_progress_label: &str, pr_url: Option<String>) -> TicketDetails {
+    let state = issue_state(&issue, in_progress_label);
+    let (checklist_items, checked_checklist_items) = issue.body.as_deref().map_or((0, 0), count_task_list_items);
+
+    TicketDetails {
+        id: issue.id.to_string(),
+        name: issue.title,
+        state,
+        url: issue.html_url,
+        member_ids: issue.assignees.into_iter().map(|assignee| assignee.login).collect(),
+        has_description: issue.body.as_ref().is_some_and(|body| !body.trim().is_empty()),
+        has_labels: issue.labels.iter().any(|label| !label.name.eq_ignore_ascii_case(in_progress_label)),
+        labels: issue.labels.iter().filter_map(|label| TicketLabel::from_str(&label.name)).collect(),
+        checklist_items,
+        checked_checklist_items,
+        points: issue.labels.iter().find_map(|label| story_points(&label.name)),
+        pr_url,
+        issue_url: None,
+        dependency_of: None,
+    }
+}
+
+pub struct GithubIssuesClient<'a> {
+    client: &'a Client,
+    config: &'a Config,
+    repo: &'a str,
+    github_client: GithubClient<'a>,
+    sprint_start_date: Option<&'a str>,
+}
+
+impl<'a> GithubIssuesClient<'a> {
+    pub fn new(client: &'a Client, config: &'a Config, sprint_start_date: Option<&'a str>) -> Self {
+        GithubIssuesClient {
+            client,
+            config,
+            repo: config.github_issues_repo.as_deref().unwrap_or_default(),
+            github_client: GithubClient::new(client, config),
+            sprint_start_date,
+        }
+    }
+
+    async fn get<T: for<'de> Deserialize<'de>>(&self, url: &str) -> Result<T> {
+        Ok(self.get_page(url).await?.0)
+    }
+
+    async fn get_page<T: for<'de> Deserialize<'de>>(&self, url: &str) -> Result<(T, Option<String>)> {
+        let response = self.client.get(url)
+            .bearer_auth(&self.config.github_token)
+            .header("User-Agent", "daily_summary_request")
+            .header("Accept", "application/vnd.github.v3+json")
+            .send()
+            .await?
+            .error_for_status()?;
+        let next_url = response.headers().get(LINK)
+            .and_then(|link| link.to_str().ok())
+            .and_then(next_page_url);
+
+        Ok((response.json().await?, next_url))
+    }
+
+    async fn get_all_pages<T: for<'de> Deserialize<'de>>(&self, url: &str) -> Result<Vec<T>> {
+        let mut items = vec![];
+        let mut next_url = Some(url.to_string());
+
+        while let Some(url) = next_url {
+            let (page, next_page): (Vec<T>, Option<String>) = self.get_page(&url).await?;
+            items.extend(page);
+            next_url = next_page;
+        }
+
+        Ok(items)
+    }
+
+    //a preview or kickoff starts the sprint today, so only issues closed from then on count as completed
+    fn sprint_start(&self) -> NaiveDate {
+        self.sprint_start_date
+            .and_then(|start_date| parse_flexible_date(start_date).ok())
+            .unwrap_or_else(|| current_date(self.config.summary_timezone))
+    }
+
+    //closed issues only count if they were closed during the sprint, older history isn't sprint scope
+    async fn fetch_issues(&self) -> Result<Vec<GithubRepoIssue>> {
+        let sprint_start = self.sprint_start();
+        let open_issues_url = format!("https://api.github.com/repos/{}/issues?state=open&per_page=100", self.repo);
+        //since only narrows by last update, closed_at is checked below
+        let closed_issues_url = format!("https://api.github.com/repos/{}/issues?state=closed&since={}&per_page=100", self.repo, start_of_day_utc(sprint_start, self.config.summary_timezone));
+
+        info!("Fetching GitHub issues: {:?}", open_issues_url);
+
+        let mut issues: Vec<GithubRepoIssue> = self.get_all_pages(&open_issues_url).await?;
+        issues.extend(self.get_all_pages::<GithubRepoIssue>(&closed_issues_url).await?.into_iter().filter(|issue| closed_since(issue, sprint_start, self.config.summary_timezone)));
+
+        Ok(issues.into_iter().filter(|issue| issue.pull_request.is_none()).collect())
+    }
+
+    async fn fetch_linked_pr_url(&self, number: u64) -> Result<Option<String>> {
+        let timeline_url = format!("https://api.github.com/repos/{}/issues/{}/timeline?per_page=100", self.repo, number);
+
+        info!("Fetching linked PRs for issue: {:?}", timeline_url);
+
+        let events: Vec<GithubTimelineEvent> = self.get(&timeline_url).await?;
+
+        Ok(linked_pr_url(&events))
+    }
+}
+
+impl PullRequestClient for GithubIssuesClient<'_> {
+    async fn fetch_pr_details(&self, pr_url: &str) -> Result<PullRequest, Error> {
+        self.github_client.fetch_pr_details(pr_url).await
+    }
+}
+
+impl IssueClient for GithubIssuesClient<'_> {
+    async fn fetch_issue_details(&self, issue_url: &str) -> Result<Issue, Error> {
+        self.github_client.fetch_issue_details(issue_url).await
+    }
+}
+
+impl TicketDetailsClient for GithubIssuesClient<'_> {
+    async fn fetch_ticket_details(&self, time_budget: &TimeBudget) -> Result<Vec<TicketDetails>, Error> {
+        let issues = self.fetch_issues().await?;
+
+        //timeline lookups run a few at a time and stop once the budget runs low, those issues are listed without their pr
+        let pr_urls: Vec<Option<String>> = stream::iter(issues.iter())
+            .map(|issue| async move {
+                //closed issues are done, so their pull requests aren't looked up
+                if issue.state != "open" || time_budget.is_nearly_exceeded() {
+                    return None;
+                }
+
+                //an issue whose timeline can't be read is listed without its pr rather than failing the summary
+                self.fetch_linked_pr_url(issue.number).await.unwrap_or_else(|e| {
+                    warn!("Failed to fetch linked PR for issue {}: {}", issue.number, e);
+                    None
+                })
+            })
+            .buffered(MAX_CONCURRENT_GITHUB_REQUESTS)
+            .collect()
+            .await;
+
+        Ok(issues.into_iter()
+            .zip(pr_urls)
+            .map(|(issue, pr_url)| into_ticket_details(issue, &self.config.github_in_progress_label, pr_url))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::US::Pacific;
+
+    const SAMPLE_ISSUES: &str = r#"[
+        {
+            "id": 1001,
+            "number": 12,
+            "title": "Add onboarding flow",
+            "state": "open",
+            "html_url": "https://github.com/owner/repo/issues/12",
+            "body": "Steps\n- [x] design\n- [ ] build\n  - [X] copy",
+            "labels": [{"name": "Front-End"}, {"name": "In Progress"}],
+            "assignees": [{"login": "octocat"}, {"login": "hubot"}]
+        },
+        {
+            "id": 1002,
+            "number": 13,
+            "title": "Fix crash on launch",
+            "state": "closed",
+            "html_url": "https://github.com/owner/repo/issues/13",
+            "body": null,
+            "labels": [{"name": "Bug"}],
+            "assignees": []
+        },
+        {
+            "id": 1003,
+            "number": 14,
+            "title": "Bump dependencies",
+            "state": "open",
+            "html_url": "https://github.com/owner/repo/pull/14",
+            "body": "",
+            "labels": [],
+            "assignees": [],
+            "pull_request": {"url": "https://api.github.com/repos/owner/repo/pulls/14"}
+        }
+    ]"#;
+
+    #[test]
+    fn test_sample_issues_into_ticket_details() {
+        let issues: Vec<GithubRepoIssue> = serde_json::from_str(SAMPLE_ISSUES).unwrap();
+        let tickets: Vec<TicketDetails> = issues.into_iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .map(|issue| into_ticket_details(issue, "in progress", None))
+            .collect();
+
+        assert_eq!(tickets.len(), 2);
+
+        assert_eq!(tickets[0].id, "1001");
+        assert_eq!(tickets[0].name, "Add onboarding flow");
+        assert_eq!(tickets[0].state, TicketState::InProgress);
+        assert_eq!(tickets[0].url, "https://github.com/owner/repo/issues/12");
+        assert_eq!(tickets[0].member_ids, vec!["octocat".to_string(), "hubot".to_string()]);
+        assert!(tickets[0].has_description);
+        assert!(tickets[0].has_labels);
+        assert_eq!(tickets[0].labels, vec![TicketLabel::FrontEnd]);
+        assert_eq!((tickets[0].checklist_items, tickets[0].checked_checklist_items), (3, 2));
+
+        assert_eq!(tickets[1].state, TicketState::Done);
+        assert!(!tickets[1].has_description);
+        assert_eq!(tickets[1].labels, vec![TicketLabel::Bug]);
+        assert_eq!(tickets[1].pr_url, None);
+    }
+
+    #[test]
+    fn test_open_issue_without_progress_label_is_in_scope() {
+        let issue: GithubRepoIssue = serde_json::from_str(r#"{
+            "id": 1, "number": 1, "title": "Triage", "state": "open", "html_url": "https://github.com/owner/repo/issues/1",
+            "body": "Needs a look", "labels": [{"name": "In Progress"}], "assignees": []
+        }"#).unwrap();
+
+        let ticket = into_ticket_details(issue, "doing", Some("https://github.com/owner/repo/pull/2".to_string()));
+
+        assert_eq!(ticket.state, TicketState::InScope);
+        assert_eq!(ticket.pr_url.as_deref(), Some("https://github.com/owner/repo/pull/2"));
+    }
+
+    #[test]
+    fn test_linked_pr_url_from_timeline() {
+        let events: Vec<GithubTimelineEvent> = serde_json::from_str(r#"[
+            {"event": "labeled"},
+            {"event": "cross-referenced", "source": {"type": "issue", "issue": {"html_url": "https://github.com/owner/repo/issues/20"}}},
+            {"event": "cross-referenced", "source": {"type": "issue", "issue": {"html_url": "https://github.com/owner/repo/pull/21", "pull_request": {}}}},
+            {"event": "cross-referenced", "source": {"type": "issue", "issue": {"html_url": "https://github.com/owner/repo/pull/22", "pull_request": {}}}}
+        ]"#).unwrap();
+
+        assert_eq!(linked_pr_url(&events).as_deref(), Some("https://github.com/owner/repo/pull/22"));
+        assert_eq!(linked_pr_url(&[]), None);
+    }
+
+    #[test]
+    fn test_next_page_url_from_link_header() {
+        let link_header = r#"<https://api.github.com/repositories/1/issues?state=open&per_page=100&page=2>; rel="next", <https://api.github.com/repositories/1/issues?state=open&per_page=100&page=5>; rel="last""#;
+
+        assert_eq!(next_page_url(link_header).as_deref(), Some("https://api.github.com/repositories/1/issues?state=open&per_page=100&page=2"));
+        assert_eq!(next_page_url(r#"<https://api.github.com/repositories/1/issues?page=1>; rel="prev", <https://api.github.com/repositories/1/issues?page=1>; rel="first""#), None);
+    }
+
+    #[test]
+    fn test_only_issues_closed_during_the_sprint_count() {
+        let issues: Vec<GithubRepoIssue> = serde_json::from_str(r#"[
+            {"id": 1, "number": 1, "title": "Closed last sprint", "state": "closed", "html_url": "https://github.com/owner/repo/issues/1",
+             "body": null, "labels": [], "assignees": [], "closed_at": "2024-04-28T18:00:00Z"},
+            {"id": 2, "number": 2, "title": "Closed this sprint", "state": "closed", "html_url": "https://github.com/owner/repo/issues/2",
+             "body": null, "labels": [], "assignees": [], "closed_at": "2024-05-02T18:00:00Z"},
+            {"id": 3, "number": 3, "title": "Missing close date", "state": "closed", "html_url": "https://github.com/owner/repo/issues/3",
+             "body": null, "labels": [], "assignees": []}
+        ]"#).unwrap();
+        let sprint_start = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+
+        let closed_titles: Vec<&str> = issues.iter()
+            .filter(|issue| closed_since(issue, sprint_start, Pacific))
+            .map(|issue| issue.title.as_str())
+            .collect();
+
+        assert_eq!(closed_titles, vec!["Closed this sprint"]);
+    }
+
+    #[test]
+    fn test_closed_issues_are_fetched_from_local_midnight() {
+        let sprint_start = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+
+        assert_eq!(start_of_day_utc(sprint_start, Pacific), "2024-05-01T07:00:00Z");
+        assert_eq!(start_of_day_utc(sprint_start, chrono_tz::Asia::Tokyo), "2024-04-30T15:00:00Z");
+    }
+}
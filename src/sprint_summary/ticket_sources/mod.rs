@@ -1,13 +1,22 @@
 mod github;
+mod github_issues;
 mod trello;
 
+pub use github::{CheckRunNameMapping, CheckRunPattern};
+pub use github_issues::GithubIssuesClient;
+pub use trello::{TrelloCredentialsResolver, TrelloTicketClient};
+
 use std;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use anyhow::{Error, Result};
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use crate::tracing::error;
+use crate::utils::config::Config;
 use crate::utils::date::print_current_date;
+use crate::utils::time_budget::TimeBudget;
 use super::sprint_records::{CumulativeSprintContexts, DailyTicketContext, DailyTicketContexts};
-use super::ticket::{Ticket, TicketDetails, PullRequest};
+use super::ticket::{Issue, Ticket, TicketDetails, PullRequest};
 use super::ticket_state::TicketState;
 use super::ticket_summary::TicketSummary;
 
@@ -15,8 +24,55 @@ pub trait PullRequestClient {
     async fn fetch_pr_details(&self, pr_url: &str) -> Result<PullRequest, Error>;
 }
 
+pub trait IssueClient {
+    async fn fetch_issue_details(&self, issue_url: &str) -> Result<Issue, Error>;
+}
+
 pub trait TicketDetailsClient {
-    async fn fetch_ticket_details(&self) -> Result<Vec<TicketDetails>, Error>;
+    async fn fetch_ticket_details(&self, time_budget: &TimeBudget) -> Result<Vec<TicketDetails>, Error>;
+}
+
+const MAX_CONCURRENT_GITHUB_REQUESTS: usize = 8;
+
+//the ticket source picked by TICKET_SOURCE
+pub enum TicketClient<'a> {
+    Trello(TrelloTicketClient<'a>),
+    GithubIssues(GithubIssuesClient<'a>),
+}
+
+impl PullRequestClient for TicketClient<'_> {
+    async fn fetch_pr_details(&self, pr_url: &str) -> Result<PullRequest, Error> {
+        match self {
+            TicketClient::Trello(client) => client.fetch_pr_details(pr_url).await,
+            TicketClient::GithubIssues(client) => client.fetch_pr_details(pr_url).await,
+        }
+    }
+}
+
+impl IssueClient for TicketClient<'_> {
+    async fn fetch_issue_details(&self, issue_url: &str) -> Result<Issue, Error> {
+        match self {
+            TicketClient::Trello(client) => client.fetch_issue_details(issue_url).await,
+            TicketClient::GithubIssues(client) => client.fetch_issue_details(issue_url).await,
+        }
+    }
+}
+
+impl TicketDetailsClient for TicketClient<'_> {
+    async fn fetch_ticket_details(&self, time_budget: &TimeBudget) -> Result<Vec<TicketDetails>, Error> {
+        match self {
+            TicketClient::Trello(client) => client.fetch_ticket_details(time_budget).await,
+            TicketClient::GithubIssues(client) => client.fetch_ticket_details(time_budget).await,
+        }
+    }
+}
+
+#[derive(Default)]
+struct LinkedDetails {
+    pr: Option<PullRequest>,
+    issue: Option<Issue>,
+    pr_skipped: bool,
+    pr_unavailable: bool,
 }
 
 struct TicketContext {
@@ -24,73 +80,117 @@ struct TicketContext {
     added_in_sprint: String,
     sprint_age: usize,
     last_moved_on: String,
+    reopened: bool,
 }
 
 impl TicketContext {
-    fn new_context(ticket_details: &TicketDetails, previous_version: Option<&DailyTicketContext>, current_sprint_name: &str, historical_records: &CumulativeSprintContexts) -> Self {
+    fn new_context(ticket_details: &TicketDetails, previous_version: Option<&DailyTicketContext>, current_sprint_name: &str, historical_records: &CumulativeSprintContexts, config: &Config) -> Self {
         if let Some(previous) = previous_version {
             TicketContext {
                 added_on: previous.added_on.clone(),
                 added_in_sprint: previous.added_in_sprint.clone(),
-                sprint_age: historical_records.count_sprints_since(&previous.added_in_sprint),
-                last_moved_on: if previous.state != ticket_details.state {
-                    print_current_date()
+                sprint_age: historical_records.sprint_age(&previous.added_in_sprint, &previous.added_on, current_sprint_name, config.sprint_name_matching),
+                last_moved_on: if previous.state.is_terminal() && ticket_details.state.is_terminal() {
+                    previous.last_moved_on.clone()
+                } else if previous.state != ticket_details.state {
+                    print_current_date(config.summary_timezone)
                 } else {
                     previous.last_moved_on.clone()
                 },
+                reopened: previous.state.is_terminal() && !ticket_details.state.is_terminal(),
             }
         } else {
             TicketContext {
-                added_on: print_current_date(),
+                added_on: print_current_date(config.summary_timezone),
                 added_in_sprint: current_sprint_name.to_string(),
                 sprint_age: 0,
-                last_moved_on: print_current_date(),
+                last_moved_on: print_current_date(config.summary_timezone),
+                reopened: false,
             }
         }
     }
 }
 
+//what was stored about the tickets before this fetch, so carried over tickets keep where and when they were added
+pub struct TicketHistory<'a> {
+    pub historical_records: &'a CumulativeSprintContexts,
+    pub previous_ticket_data: &'a DailyTicketContexts,
+    pub manual_deferrals: &'a BTreeSet<String>,
+}
 
 #[async_trait(?Send)]
 pub trait TicketSummaryClient {
-    async fn fetch_ticket_summary(&self, current_sprint_name: &str, historical_records: &CumulativeSprintContexts, previous_ticket_data: &DailyTicketContexts, user_mapping: HashMap<String, String>) -> Result<TicketSummary>;
+    async fn fetch_ticket_summary(&self, current_sprint_name: &str, history: TicketHistory<'_>, user_mapping: HashMap<String, String>, time_budget: &TimeBudget, config: &Config) -> Result<TicketSummary>;
 }
 
 #[async_trait(?Send)]
 impl<T> TicketSummaryClient for T
 where
-    T: TicketDetailsClient + PullRequestClient + Sync + Send {
-    async fn fetch_ticket_summary(&self, current_sprint_name: &str, historical_records: &CumulativeSprintContexts, previous_ticket_data: &DailyTicketContexts, user_mapping: HashMap<String, String>) -> Result<TicketSummary> {    
-        let current_ticket_details = self.fetch_ticket_details().await?;
-        let mut current_ticket_ids: Vec<String> = vec![];
+    T: TicketDetailsClient + PullRequestClient + IssueClient + Sync + Send {
+    async fn fetch_ticket_summary(&self, current_sprint_name: &str, history: TicketHistory<'_>, user_mapping: HashMap<String, String>, time_budget: &TimeBudget, config: &Config) -> Result<TicketSummary> {
+        let TicketHistory { historical_records, previous_ticket_data, manual_deferrals } = history;
+        let current_ticket_details = self.fetch_ticket_details(time_budget).await?;
+        let current_ticket_ids: Vec<String> = current_ticket_details.iter().map(|ticket_details| ticket_details.id.clone()).collect();
+
+        //github lookups run a few at a time, buffered keeps the results in ticket order
+        let linked_details: Vec<LinkedDetails> = stream::iter(current_ticket_details.iter())
+            .map(|ticket_details| async move {
+                let mut linked = LinkedDetails::default();
+
+                match &ticket_details.pr_url {
+                    Some(_) if time_budget.is_nearly_exceeded() => linked.pr_skipped = true,
+                    //a deleted or private pr shouldn't take the whole summary down with it
+                    Some(url) => match self.fetch_pr_details(url).await {
+                        Ok(pr) => linked.pr = Some(pr),
+                        Err(e) => {
+                            error!("Failed to fetch PR details for {}: {}", url, e);
+                            linked.pr_unavailable = true;
+                        },
+                    },
+                    None => {},
+                };
+
+                match &ticket_details.issue_url {
+                    Some(_) if time_budget.is_nearly_exceeded() => {},
+                    Some(url) => match self.fetch_issue_details(url).await {
+                        Ok(issue) => linked.issue = Some(issue),
+                        Err(e) => error!("Failed to fetch issue details for {}: {}", url, e),
+                    },
+                    None => {},
+                };
+
+                linked
+            })
+            .buffered(MAX_CONCURRENT_GITHUB_REQUESTS)
+            .collect()
+            .await;
+        let pr_details_skipped = linked_details.iter().any(|linked| linked.pr_skipped);
+        let pr_details_unavailable = linked_details.iter().any(|linked| linked.pr_unavailable);
 
         Ok(async {
             let mut result_tickets = Vec::new();
         
-            for ticket_details in current_ticket_details {
-                current_ticket_ids.push(ticket_details.id.clone());
-        
-                let pr = if let Some(url) = &ticket_details.pr_url {
-                    Some(self.fetch_pr_details(url).await.expect("Should get GitHub PR details successfully"))
-                } else {
-                    None
-                };
-                
+            for (ticket_details, LinkedDetails { pr, issue, .. }) in current_ticket_details.into_iter().zip(linked_details) {
                 let previous_version = previous_ticket_data.tickets.iter().find(|record| record.id == ticket_details.id);
 
-                let context = TicketContext::new_context(&ticket_details, previous_version, current_sprint_name, historical_records);
+                let context = TicketContext::new_context(&ticket_details, previous_version, current_sprint_name, historical_records, config);
         
                 result_tickets.push(Ticket {
                     pr,
-                    moved_out_of_sprint: previous_version.is_some() && ticket_details.state <= TicketState::InScope,
+                    issue,
+                    moved_out_of_sprint: (previous_version.is_some() && ticket_details.state <= TicketState::InScope) || manual_deferrals.contains(&ticket_details.id),
                     sprint_age: context.sprint_age,
                     added_on: context.added_on,
                     added_in_sprint: context.added_in_sprint,
                     last_moved_on: context.last_moved_on,
+                    reopened: context.reopened,
+                    //sorted so mentions render in the same order whatever order trello returns members in
                     members: ticket_details.member_ids.iter()
                         .filter_map(|id| user_mapping.get(id)
                             .map(|name| name.to_string()))
-                        .collect::<Vec<String>>(),
+                        .collect::<BTreeSet<String>>()
+                        .into_iter()
+                        .collect(),
                     details: ticket_details,
                 });
             }
@@ -102,7 +202,10 @@ where
 
             result_tickets.extend(orphaned_tickets);
         
-            result_tickets.into()
+            let mut summary = TicketSummary::with_config(result_tickets, config);
+            summary.pr_details_skipped = pr_details_skipped;
+            summary.pr_details_unavailable = pr_details_unavailable;
+            summary
         }.await)
     }
 }
@@ -110,7 +213,7 @@ where
 #[cfg(test)]
 mod ticket_context_tests {
     use super::*;
-    use crate::sprint_summary::sprint_records::{CumulativeSprintContexts, DailyTicketContext};
+    use crate::sprint_summary::sprint_records::{CumulativeSprintContexts, DailyTicketContext, SprintNameMatching};
     use crate::sprint_summary::ticket::TicketDetails;
     use crate::sprint_summary::ticket_state::TicketState;
     use crate::utils::date::print_current_date;
@@ -123,12 +226,13 @@ mod ticket_context_tests {
         let previous_context = Some(&ticket_context_default);
         let historical_records = CumulativeSprintContexts::default();
 
-        let context = TicketContext::new_context(&ticket_details, previous_context, "", &historical_records);
+        let context = TicketContext::new_context(&ticket_details, previous_context, "", &historical_records, &Config::default());
 
         assert_eq!(context.added_on, "04/01/24");
         assert_eq!(context.last_moved_on, "04/05/24");
         assert_eq!(context.sprint_age, 2);
         assert_eq!(context.added_in_sprint, "Sprint 101");
+        assert!(!context.reopened);
     }
     
     #[test]
@@ -139,12 +243,54 @@ mod ticket_context_tests {
         let previous_context = Some(&ticket_context_default);
         let historical_records = CumulativeSprintContexts::default();
 
-        let context = TicketContext::new_context(&ticket_details, previous_context, "", &historical_records);
+        let context = TicketContext::new_context(&ticket_details, previous_context, "", &historical_records, &Config::default());
 
         assert_eq!(context.added_on, "04/01/24");
-        assert_eq!(context.last_moved_on, print_current_date());
+        assert_eq!(context.last_moved_on, print_current_date(Config::default().summary_timezone));
         assert_eq!(context.sprint_age, 2);
         assert_eq!(context.added_in_sprint, "Sprint 101");
+        assert!(context.reopened, "Moving out of a terminal state should mark the ticket as reopened");
+    }
+
+    #[test]
+    fn context_with_previous_non_terminal_version_not_reopened() {
+        let mut ticket_details = TicketDetails::default();
+        ticket_details.state = TicketState::PendingRelease;
+        let ticket_context_default = DailyTicketContext { state: TicketState::InProgress, ..DailyTicketContext::default() };
+        let historical_records = CumulativeSprintContexts::default();
+
+        let context = TicketContext::new_context(&ticket_details, Some(&ticket_context_default), "", &historical_records, &Config::default());
+
+        assert_eq!(context.last_moved_on, print_current_date(Config::default().summary_timezone));
+        assert!(!context.reopened);
+    }
+
+    #[test]
+    fn context_with_pre_history_version_is_not_new() {
+        let ticket_details = TicketDetails { state: TicketState::InProgress, ..TicketDetails::default() };
+        let pre_history_context = DailyTicketContext { state: TicketState::InProgress, added_in_sprint: "Sprint 90".to_string(), added_on: "12/01/23".to_string(), ..DailyTicketContext::default() };
+        let historical_records = CumulativeSprintContexts::default();
+
+        let context = TicketContext::new_context(&ticket_details, Some(&pre_history_context), "Sprint 103", &historical_records, &Config::default());
+        let ticket = Ticket { added_on: context.added_on.clone(), sprint_age: context.sprint_age, ..Ticket::default() };
+
+        assert_eq!(context.sprint_age, 3);
+        assert_eq!(context.added_in_sprint, "Sprint 90");
+        assert!(!ticket.is_new(&Config::default()));
+    }
+
+    #[test]
+    fn context_sprint_age_uses_configured_name_matching() {
+        let ticket_details = TicketDetails { state: TicketState::InProgress, ..TicketDetails::default() };
+        let renamed_context = DailyTicketContext { state: TicketState::InProgress, added_in_sprint: "sprint 101".to_string(), added_on: "01/01/30".to_string(), ..DailyTicketContext::default() };
+        let historical_records = CumulativeSprintContexts::default();
+        let case_insensitive = Config { sprint_name_matching: SprintNameMatching::CaseInsensitive, ..Config::default() };
+
+        let exact_context = TicketContext::new_context(&ticket_details, Some(&renamed_context), "Sprint 103", &historical_records, &Config::default());
+        let case_insensitive_context = TicketContext::new_context(&ticket_details, Some(&renamed_context), "Sprint 103", &historical_records, &case_insensitive);
+
+        assert_eq!(exact_context.sprint_age, 0);
+        assert_eq!(case_insensitive_context.sprint_age, 2);
     }
 
     #[test]
@@ -154,10 +300,10 @@ mod ticket_context_tests {
         let historical_records = CumulativeSprintContexts::default();
         let current_sprint_name = "Sprint 103";
 
-        let context = TicketContext::new_context(&ticket_details, previous_context, current_sprint_name, &historical_records);
+        let context = TicketContext::new_context(&ticket_details, previous_context, current_sprint_name, &historical_records, &Config::default());
 
-        assert_eq!(context.added_on, print_current_date());
-        assert_eq!(context.last_moved_on, print_current_date());
+        assert_eq!(context.added_on, print_current_date(Config::default().summary_timezone));
+        assert_eq!(context.last_moved_on, print_current_date(Config::default().summary_timezone));
         assert_eq!(context.sprint_age, 0);
         assert_eq!(context.added_in_sprint, "Sprint 103");
     }
@@ -168,8 +314,9 @@ pub mod ticket_summary_mocks {
     use std::collections::HashMap;
 
     use anyhow::{anyhow, Error};
-    use crate::sprint_summary::ticket::{PullRequest, TicketDetails};
-    use super::{PullRequestClient, TicketDetailsClient};
+    use crate::sprint_summary::ticket::{Issue, PullRequest, TicketDetails};
+    use crate::utils::time_budget::TimeBudget;
+    use super::{IssueClient, PullRequestClient, TicketDetailsClient};
 
     pub struct MockPullRequestClient {
         pub responses: HashMap<String, PullRequest>,
@@ -191,6 +338,26 @@ pub mod ticket_summary_mocks {
         }
     }
     
+    pub struct MockIssueClient {
+        pub responses: HashMap<String, Issue>,
+    }
+
+    impl MockIssueClient {
+        pub fn new(responses: HashMap<String, Issue>) -> Self {
+            Self { responses }
+        }
+    }
+
+    impl IssueClient for MockIssueClient {
+        async fn fetch_issue_details(&self, issue_url: &str) -> Result<Issue, Error> {
+            if let Some(response) = self.responses.get(issue_url) {
+                Ok(response.clone())
+            } else {
+                Err(anyhow!("Issue not found"))
+            }
+        }
+    }
+    
     pub struct MockTicketDetailsClient {
         pub response: Vec<TicketDetails>,
     }
@@ -202,14 +369,14 @@ pub mod ticket_summary_mocks {
     }
 
     impl TicketDetailsClient for MockTicketDetailsClient {
-        async fn fetch_ticket_details(&self) -> Result<Vec<TicketDetails>, Error> {
+        async fn fetch_ticket_details(&self, _time_budget: &TimeBudget) -> Result<Vec<TicketDetails>, Error> {
             Ok(self.response.clone())
         }
     }
     
     impl TicketDetailsClient for MockTicketSummaryClient {
-        async fn fetch_ticket_details(&self) -> Result<Vec<TicketDetails>, Error> {
-            self.ticket_details_client.fetch_ticket_details().await
+        async fn fetch_ticket_details(&self, time_budget: &TimeBudget) -> Result<Vec<TicketDetails>, Error> {
+            self.ticket_details_client.fetch_ticket_details(time_budget).await
         }
     }
     
@@ -219,32 +386,47 @@ pub mod ticket_summary_mocks {
         }
     }
     
+    impl IssueClient for MockTicketSummaryClient {
+        async fn fetch_issue_details(&self, url: &str) -> Result<Issue, Error> {
+            self.issue_client.fetch_issue_details(url).await
+        }
+    }
+    
     pub struct MockTicketSummaryClient {
         ticket_details_client: MockTicketDetailsClient,
         pull_request_client: MockPullRequestClient,
+        issue_client: MockIssueClient,
     }
     
     impl MockTicketSummaryClient {
         pub fn new(ticket_details_client: MockTicketDetailsClient, pull_request_client: MockPullRequestClient) -> Self {
+            Self::with_issues(ticket_details_client, pull_request_client, MockIssueClient::new(HashMap::new()))
+        }
+
+        pub fn with_issues(ticket_details_client: MockTicketDetailsClient, pull_request_client: MockPullRequestClient, issue_client: MockIssueClient) -> Self {
             Self { 
                 ticket_details_client,
-                pull_request_client
+                pull_request_client,
+                issue_client
             }
         }
     }
 }
 #[cfg(test)]
 mod ticket_summary_tests {
-    use std::collections::{HashMap, VecDeque};
+    use std::collections::{BTreeSet, HashMap, VecDeque};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use anyhow::{Error, Result, anyhow};
     use serde_json::json;
-    use crate::{sprint_summary::{sprint_records::{CumulativeSprintContexts, DailyTicketContext, DailyTicketContexts}, ticket::{PullRequest, Ticket, TicketDetails}, ticket_sources::{ticket_summary_mocks::{MockPullRequestClient, MockTicketDetailsClient, MockTicketSummaryClient}, TicketSummaryClient}, ticket_state::TicketState}, utils::date::print_current_date};
+    use std::time::Duration;
+    use crate::{sprint_summary::{sprint_records::{CumulativeSprintContexts, DailyTicketContext, DailyTicketContexts}, ticket::{Issue, PullRequest, Ticket, TicketDetails}, ticket_sources::{ticket_summary_mocks::{MockIssueClient, MockPullRequestClient, MockTicketDetailsClient, MockTicketSummaryClient}, IssueClient, PullRequestClient, TicketDetailsClient, TicketHistory, TicketSummaryClient}, ticket_state::TicketState}, utils::{config::Config, date::print_current_date, time_budget::{mocks::MockClock, TimeBudget}}};
     
     #[tokio::test]
     async fn fetch_summary_combines_data_correctly() {
         let mut pull_request_responses = HashMap::new();
         pull_request_responses.insert("https://default-url.com".to_string(), PullRequest::default());
         pull_request_responses.insert("https://merged-url.com".to_string(), PullRequest { merged: true, ..PullRequest::default() });
-        pull_request_responses.insert("https://unmergeable-url.com".to_string(), PullRequest { mergeable: None, merged: false, ..PullRequest::default() });
+        pull_request_responses.insert("https://unmergeable-url.com".to_string(), PullRequest { mergeable: Some(false), merged: false, ..PullRequest::default() });
 
         let client = MockTicketSummaryClient::new(
             MockTicketDetailsClient::new(vec![
@@ -287,7 +469,8 @@ mod ticket_summary_tests {
         };
         let user_mapping = HashMap::new();
 
-        let summary = client.fetch_ticket_summary("Current Sprint", &historical_records, &previous_ticket_data, user_mapping).await.unwrap();
+        let today = print_current_date(Config::default().summary_timezone);
+        let summary = client.fetch_ticket_summary("Current Sprint", TicketHistory { historical_records: &historical_records, previous_ticket_data: &previous_ticket_data, manual_deferrals: &BTreeSet::new() }, user_mapping, &TimeBudget::default(), &Config::default()).await.unwrap();
 
         let summary_json = serde_json::to_value(&summary).expect("summary should be parseable");
 
@@ -317,8 +500,8 @@ mod ticket_summary_tests {
                   },
                   pr: Some(PullRequest::default()),
                   added_in_sprint: "Current Sprint".to_string(), 
-                  added_on: print_current_date(), 
-                  last_moved_on: print_current_date(), 
+                  added_on: today.clone(), 
+                  last_moved_on: today.clone(), 
                   sprint_age: 0,
                   ..Ticket::default()
               }).unwrap(),
@@ -330,8 +513,8 @@ mod ticket_summary_tests {
                   },
                   pr: Some(PullRequest { merged: true, ..PullRequest::default() }),
                   added_in_sprint: "Current Sprint".to_string(), 
-                  added_on: print_current_date(), 
-                  last_moved_on: print_current_date(), 
+                  added_on: today.clone(), 
+                  last_moved_on: today.clone(), 
                   sprint_age: 0,
                   ..Ticket::default()
               }).unwrap(),
@@ -343,10 +526,10 @@ mod ticket_summary_tests {
                     pr_url: Some("https://unmergeable-url.com".to_string()),
                     ..TicketDetails::default()
                 },
-                pr: Some(PullRequest { mergeable: None, ..PullRequest::default() }),
+                pr: Some(PullRequest { mergeable: Some(false), ..PullRequest::default() }),
                 added_in_sprint: "Current Sprint".to_string(), 
-                added_on: print_current_date(), 
-                last_moved_on: print_current_date(), 
+                added_on: today.clone(), 
+                last_moved_on: today.clone(), 
                 sprint_age: 0,
                 ..Ticket::default()
             }).unwrap(),
@@ -369,8 +552,211 @@ mod ticket_summary_tests {
         };
         let user_mapping = HashMap::new();
 
-        let summary = client.fetch_ticket_summary("CurrentSprint", &historical_records, &previous_ticket_data, user_mapping).await.unwrap();
+        let summary = client.fetch_ticket_summary("CurrentSprint", TicketHistory { historical_records: &historical_records, previous_ticket_data: &previous_ticket_data, manual_deferrals: &BTreeSet::new() }, user_mapping, &TimeBudget::default(), &Config::default()).await.unwrap();
 
         assert!(summary.deferred_tickets.iter().any(|ticket| ticket.details.id == "orphan123")); //need to make a test-only impl to check that an orphan ticket exists
     }
+
+    #[tokio::test]
+    async fn fetch_summary_skips_pr_details_near_time_budget() {
+        let mut pull_request_responses = HashMap::new();
+        pull_request_responses.insert("https://default-url.com".to_string(), PullRequest::default());
+
+        let client = MockTicketSummaryClient::new(
+            MockTicketDetailsClient::new(vec![
+                TicketDetails {
+                    name: "Mock Task Default".to_string(),
+                    pr_url: Some("https://default-url.com".to_string()),
+                    ..TicketDetails::default()
+                },
+            ]),
+            MockPullRequestClient::new(pull_request_responses)
+        );
+        let clock = MockClock::default();
+        let time_budget = TimeBudget::new(Box::new(clock.clone()), Duration::from_secs(25), Duration::from_secs(5));
+
+        let summary = client.fetch_ticket_summary("Current Sprint", TicketHistory { historical_records: &CumulativeSprintContexts::default(), previous_ticket_data: &DailyTicketContexts::default(), manual_deferrals: &BTreeSet::new() }, HashMap::new(), &time_budget, &Config::default()).await.unwrap();
+        assert!(!summary.pr_details_skipped);
+        assert_eq!(serde_json::to_value(&summary).unwrap()["open_prs"].as_array().unwrap().len(), 1);
+
+        clock.advance(Duration::from_secs(21));
+
+        let degraded_summary = client.fetch_ticket_summary("Current Sprint", TicketHistory { historical_records: &CumulativeSprintContexts::default(), previous_ticket_data: &DailyTicketContexts::default(), manual_deferrals: &BTreeSet::new() }, HashMap::new(), &time_budget, &Config::default()).await.unwrap();
+        assert!(degraded_summary.pr_details_skipped, "PR details should be skipped once the time budget is nearly exceeded");
+        assert_eq!(serde_json::to_value(&degraded_summary).unwrap()["open_prs"].as_array().unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn fetch_summary_continues_when_a_pr_fetch_fails() {
+        let pull_request_responses = HashMap::from([("https://github.com/owner/repo/pull/1".to_string(), PullRequest::default())]);
+
+        let client = MockTicketSummaryClient::new(
+            MockTicketDetailsClient::new(vec![
+                TicketDetails {
+                    id: "available".to_string(),
+                    pr_url: Some("https://github.com/owner/repo/pull/1".to_string()),
+                    ..TicketDetails::default()
+                },
+                TicketDetails {
+                    id: "deleted".to_string(),
+                    pr_url: Some("https://github.com/owner/repo/pull/404".to_string()),
+                    ..TicketDetails::default()
+                },
+                TicketDetails {
+                    id: "no_pr".to_string(),
+                    pr_url: None,
+                    ..TicketDetails::default()
+                },
+            ]),
+            MockPullRequestClient::new(pull_request_responses)
+        );
+
+        let summary = client.fetch_ticket_summary("Current Sprint", TicketHistory { historical_records: &CumulativeSprintContexts::default(), previous_ticket_data: &DailyTicketContexts::default(), manual_deferrals: &BTreeSet::new() }, HashMap::new(), &TimeBudget::default(), &Config::default()).await.unwrap();
+
+        assert!(summary.pr_details_unavailable);
+        assert!(!summary.pr_details_skipped);
+
+        let summary_json = serde_json::to_value(&summary).unwrap();
+        let open_prs = summary_json["open_prs"].as_array().unwrap();
+        let open_tickets = summary_json["open_tickets"].as_array().unwrap();
+        assert_eq!(open_prs.len(), 1);
+        assert_eq!(open_prs[0]["details"]["id"], "available");
+        assert_eq!(open_tickets.len(), 2);
+        assert!(open_tickets.iter().any(|ticket| ticket["details"]["id"] == "deleted" && ticket["pr"].is_null()));
+        assert!(open_tickets.iter().any(|ticket| ticket["details"]["id"] == "no_pr"));
+    }
+
+    #[tokio::test]
+    async fn fetch_summary_defers_manually_deferred_tickets() {
+        let client = MockTicketSummaryClient::new(
+            MockTicketDetailsClient::new(vec![
+                TicketDetails {
+                    id: "deferred123".to_string(),
+                    state: TicketState::InProgress,
+                    pr_url: None,
+                    ..TicketDetails::default()
+                },
+                TicketDetails {
+                    id: "open123".to_string(),
+                    state: TicketState::InProgress,
+                    pr_url: None,
+                    ..TicketDetails::default()
+                },
+            ]),
+            MockPullRequestClient::new(HashMap::new())
+        );
+        let manual_deferrals = BTreeSet::from(["deferred123".to_string()]);
+
+        let previous_ticket_data = DailyTicketContexts { tickets: VecDeque::new() };
+
+        let summary = client.fetch_ticket_summary("Current Sprint", TicketHistory { historical_records: &CumulativeSprintContexts::default(), previous_ticket_data: &previous_ticket_data, manual_deferrals: &manual_deferrals }, HashMap::new(), &TimeBudget::default(), &Config::default()).await.unwrap();
+
+        assert_eq!(summary.deferred_tickets.len(), 1);
+        assert_eq!(summary.deferred_tickets[0].details.id, "deferred123");
+        assert_eq!(summary.open_ticket_count, 1);
+    }
+
+    #[tokio::test]
+    async fn fetch_summary_includes_issue_details() {
+        let mut issue_responses = HashMap::new();
+        issue_responses.insert("https://github.com/owner/repo/issues/1".to_string(), Issue { state: "closed".to_string() });
+
+        let client = MockTicketSummaryClient::with_issues(
+            MockTicketDetailsClient::new(vec![
+                TicketDetails {
+                    pr_url: None,
+                    issue_url: Some("https://github.com/owner/repo/issues/1".to_string()),
+                    ..TicketDetails::default()
+                },
+            ]),
+            MockPullRequestClient::new(HashMap::new()),
+            MockIssueClient::new(issue_responses)
+        );
+        let previous_ticket_data = DailyTicketContexts { tickets: VecDeque::new() };
+
+        let summary = client.fetch_ticket_summary("Current Sprint", TicketHistory { historical_records: &CumulativeSprintContexts::default(), previous_ticket_data: &previous_ticket_data, manual_deferrals: &BTreeSet::new() }, HashMap::new(), &TimeBudget::default(), &Config::default()).await.unwrap();
+
+        let summary_json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(summary_json["open_tickets"][0]["issue"]["state"], "closed");
+    }
+
+    #[tokio::test]
+    async fn fetch_summary_orders_members_alphabetically() {
+        for member_ids in [vec!["trello_b", "trello_a", "trello_c"], vec!["trello_c", "trello_b", "trello_a"]] {
+            let client = MockTicketSummaryClient::new(
+                MockTicketDetailsClient::new(vec![
+                    TicketDetails {
+                        pr_url: None,
+                        member_ids: member_ids.iter().map(|id| id.to_string()).collect(),
+                        ..TicketDetails::default()
+                    },
+                ]),
+                MockPullRequestClient::new(HashMap::new())
+            );
+            let user_mapping = HashMap::from([
+                ("trello_a".to_string(), "U2".to_string()),
+                ("trello_b".to_string(), "U1".to_string()),
+                ("trello_c".to_string(), "U2".to_string()),
+            ]);
+
+            let summary = client.fetch_ticket_summary("Current Sprint", TicketHistory { historical_records: &CumulativeSprintContexts::default(), previous_ticket_data: &DailyTicketContexts { tickets: VecDeque::new() }, manual_deferrals: &BTreeSet::new() }, user_mapping, &TimeBudget::default(), &Config::default()).await.unwrap();
+
+            let summary_json = serde_json::to_value(&summary).unwrap();
+            assert_eq!(summary_json["open_tickets"][0]["members"], json!(["U1", "U2"]));
+        }
+    }
+
+    struct ConcurrencyTrackingClient {
+        ticket_details_client: MockTicketDetailsClient,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    impl TicketDetailsClient for ConcurrencyTrackingClient {
+        async fn fetch_ticket_details(&self, time_budget: &TimeBudget) -> Result<Vec<TicketDetails>, Error> {
+            self.ticket_details_client.fetch_ticket_details(time_budget).await
+        }
+    }
+
+    impl PullRequestClient for ConcurrencyTrackingClient {
+        async fn fetch_pr_details(&self, pr_url: &str) -> Result<PullRequest, Error> {
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            Ok(PullRequest { comments: pr_url.trim_start_matches("https://github.com/owner/repo/pull/").parse()?, ..PullRequest::default() })
+        }
+    }
+
+    impl IssueClient for ConcurrencyTrackingClient {
+        async fn fetch_issue_details(&self, _issue_url: &str) -> Result<Issue, Error> {
+            Err(anyhow!("Issue not found"))
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_summary_fetches_pr_details_concurrently() {
+        let client = ConcurrencyTrackingClient {
+            ticket_details_client: MockTicketDetailsClient::new((1..=5).map(|number| TicketDetails {
+                id: format!("ticket{}", number),
+                name: format!("Ticket {}", number),
+                pr_url: Some(format!("https://github.com/owner/repo/pull/{}", number)),
+                ..TicketDetails::default()
+            }).collect()),
+            in_flight: AtomicUsize::new(0),
+            max_in_flight: AtomicUsize::new(0),
+        };
+
+        let summary = client.fetch_ticket_summary("Current Sprint", TicketHistory { historical_records: &CumulativeSprintContexts::default(), previous_ticket_data: &DailyTicketContexts::default(), manual_deferrals: &BTreeSet::new() }, HashMap::new(), &TimeBudget::default(), &Config::default()).await.unwrap();
+
+        assert!(client.max_in_flight.load(Ordering::SeqCst) > 1, "PR details should be fetched concurrently");
+
+        let summary_json = serde_json::to_value(&summary).unwrap();
+        let open_prs = summary_json["open_prs"].as_array().unwrap();
+        assert_eq!(open_prs.len(), 5);
+        for ticket in open_prs {
+            assert_eq!(ticket["details"]["name"], format!("Ticket {}", ticket["pr"]["comments"]));
+        }
+    }
 }
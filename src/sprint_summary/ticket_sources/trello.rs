@@ -1,10 +1,80 @@
-use std::{collections::HashMap, env};
+use std::{cell::RefCell, collections::HashMap};
+use chrono::{DateTime, NaiveDate};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use reqwest::Client;
-use anyhow::{Result, Error};
-use crate::{sprint_summary::{ticket::{TicketDetails, TicketLink}, ticket_label::TicketLabel, ticket_state::TicketState}, tracing::info};
+use anyhow::{Context, Result, Error, anyhow};
+use crate::{sprint_summary::{sprint_records::SprintNameMatching, ticket::{Issue, PullRequest, TicketDetails, TicketLink}, ticket_label::{story_points, TicketLabel}, ticket_state::TicketState}, tracing::info};
+use crate::utils::config::Config;
+use crate::utils::date::parse_flexible_date;
+use crate::utils::secrets::SecretsClient;
+use crate::utils::time_budget::TimeBudget;
 
-use super::TicketDetailsClient;
+use super::github::GithubClient;
+use super::{IssueClient, PullRequestClient, TicketDetailsClient};
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TrelloCredentials {
+    #[serde(alias = "TRELLO_API_KEY")]
+    pub api_key: String,
+    #[serde(alias = "TRELLO_API_TOKEN")]
+    pub api_token: String,
+}
+
+//reads the credentials from Secrets Manager when a secret is configured, at most once per invocation
+pub struct TrelloCredentialsResolver<'a, S: SecretsClient> {
+    secrets_client: &'a S,
+    config: &'a Config,
+    cache: RefCell<Option<TrelloCredentials>>,
+}
+
+impl<'a, S: SecretsClient> TrelloCredentialsResolver<'a, S> {
+    pub fn new(secrets_client: &'a S, config: &'a Config) -> Self {
+        TrelloCredentialsResolver { secrets_client, config, cache: RefCell::new(None) }
+    }
+
+    pub async fn resolve(&self) -> Result<TrelloCredentials> {
+        if let Some(credentials) = self.cache.borrow().as_ref() {
+            return Ok(credentials.clone());
+        }
+
+        let credentials = match (&self.config.trello_secret_arn, &self.config.trello_api_key, &self.config.trello_api_token) {
+            (Some(secret_arn), _, _) => {
+                let secret = self.secrets_client.get_secret_string(secret_arn).await?;
+                serde_json::from_str::<TrelloCredentials>(&secret).context("Failed to parse Trello credentials secret")?
+            },
+            (None, Some(api_key), Some(api_token)) => TrelloCredentials { api_key: api_key.clone(), api_token: api_token.clone() },
+            _ => return Err(anyhow!("Trello credentials are not configured")),
+        };
+        self.cache.borrow_mut().replace(credentials.clone());
+
+        Ok(credentials)
+    }
+}
+
+pub struct TrelloTicketClient<'a> {
+    client: &'a Client,
+    config: &'a Config,
+    board_id: &'a str,
+    sprint_name: &'a str,
+    sprint_start_date: Option<&'a str>,
+    credentials: TrelloCredentials,
+    github_client: GithubClient<'a>,
+}
+
+impl<'a> TrelloTicketClient<'a> {
+    pub fn new(client: &'a Client, config: &'a Config, credentials: TrelloCredentials, sprint_name: &'a str, sprint_start_date: Option<&'a str>) -> Self {
+        TrelloTicketClient {
+            client,
+            config,
+            board_id: &config.trello_board_id,
+            sprint_name,
+            sprint_start_date,
+            credentials,
+            github_client: GithubClient::new(client, config),
+        }
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 struct TrelloAttachment {
@@ -23,6 +93,30 @@ struct TrelloBadges {
     checkItemsChecked: u32,
 }
 
+//text and number fields keep their value under "text" or "number", dropdowns point at one of the field's options
+#[derive(Debug, Serialize, Deserialize)]
+struct TrelloCustomFieldItem {
+    #[serde(rename = "idCustomField")]
+    id_custom_field: String,
+    #[serde(rename = "idValue")]
+    id_value: Option<String>,
+    value: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TrelloCustomFieldOption {
+    id: String,
+    value: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TrelloCustomField {
+    id: String,
+    name: String,
+    #[serde(default)]
+    options: Vec<TrelloCustomFieldOption>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct TrelloCard {
     id: String,
@@ -34,6 +128,12 @@ struct TrelloCard {
     desc: Option<String>,
     attachments: Vec<TrelloAttachment>,
     badges: TrelloBadges,
+    #[serde(default, rename = "customFieldItems")]
+    custom_field_items: Vec<TrelloCustomFieldItem>,
+    #[serde(default)]
+    closed: bool,
+    #[serde(default, rename = "dateLastActivity")]
+    date_last_activity: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -49,10 +149,82 @@ struct TrelloList {
     name: String,
 }
 
-async fn fetch_trello_lists(client: &Client) -> Result<Vec<TrelloList>, Error> {
-    let trello_board_id = env::var("TRELLO_BOARD_ID").expect("TRELLO_BOARD_ID environment variable should exist");
-    let trello_api_key = env::var("TRELLO_API_KEY").expect("TRELLO_API_KEY environment variable should exist");
-    let trello_api_token = env::var("TRELLO_API_TOKEN").expect("TRELLO_API_TOKEN environment variable should exist");
+//the first points label wins if a card has been given more than one
+fn label_points(labels: &[TrelloLabel]) -> Option<u32> {
+    labels.iter().find_map(|label| story_points(&label.name))
+}
+
+fn find_github_attachment_url(attachments: &[TrelloAttachment], path_segment: &str) -> Option<String> {
+    attachments.iter()
+        .find_map(|attachment| {
+            if attachment.url.contains("github.com") && attachment.url.contains(path_segment) {
+                Some(attachment.url.clone())
+            } else {
+                None
+            }
+        })
+}
+
+fn custom_field_text(value: &HashMap<String, String>) -> Option<String> {
+    value.get("text").or(value.get("number")).cloned()
+}
+
+fn card_custom_field_value(card: &TrelloCard, field: &TrelloCustomField) -> Option<String> {
+    let item = card.custom_field_items.iter().find(|item| item.id_custom_field == field.id)?;
+
+    match &item.id_value {
+        Some(option_id) => field.options.iter()
+            .find(|option| &option.id == option_id)
+            .and_then(|option| custom_field_text(&option.value)),
+        None => item.value.as_ref().and_then(custom_field_text),
+    }
+}
+
+//without a sprint field every card on a known list counts, with one only cards tagged with the current sprint do
+fn is_in_sprint(card: &TrelloCard, sprint_field: Option<&TrelloCustomField>, sprint_name: &str, name_matching: SprintNameMatching) -> bool {
+    match sprint_field {
+        Some(field) if !sprint_name.is_empty() => card_custom_field_value(card, field)
+            .is_some_and(|value| name_matching.matches(&value, sprint_name)),
+        _ => true,
+    }
+}
+
+//trello has no archive date, so the card's last activity stands in for when it was archived
+fn archived_since(card: &TrelloCard, sprint_start: NaiveDate, timezone: Tz) -> bool {
+    card.closed && card.date_last_activity.as_deref()
+        .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+        .is_some_and(|date| date.with_timezone(&timezone).date_naive() >= sprint_start)
+}
+
+//archived cards only count when the option is on, as completed, and only if archived during the current sprint
+fn card_state(card: &TrelloCard, list_states: &HashMap<String, Option<TicketState>>, archived_since_date: Option<NaiveDate>, timezone: Tz) -> Option<TicketState> {
+    if card.closed {
+        return archived_since_date
+            .filter(|sprint_start| archived_since(card, *sprint_start, timezone))
+            .map(|_| TicketState::Done);
+    }
+
+    list_states.get(&card.idList).cloned().flatten()
+}
+
+async fn fetch_trello_custom_field(client: &Client, trello_board_id: &str, credentials: &TrelloCredentials, field_name: &str) -> Result<TrelloCustomField, Error> {
+    let custom_fields_url = format!("https://api.trello.com/1/boards/{}/customFields?key={}&token={}", trello_board_id, credentials.api_key, credentials.api_token);
+
+    let custom_fields: Vec<TrelloCustomField> = client.get(&custom_fields_url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    custom_fields.into_iter()
+        .find(|field| field.name == field_name)
+        .ok_or(anyhow!("Trello custom field {} was not found on the board", field_name))
+}
+
+async fn fetch_trello_lists(client: &Client, trello_board_id: &str, credentials: &TrelloCredentials) -> Result<Vec<TrelloList>, Error> {
+    let trello_api_key = &credentials.api_key;
+    let trello_api_token = &credentials.api_token;
 
     let lists_url = format!("https://api.trello.com/1/boards/{}/lists?key={}&token={}", trello_board_id, trello_api_key, trello_api_token);
 
@@ -68,12 +240,12 @@ async fn fetch_trello_lists(client: &Client) -> Result<Vec<TrelloList>, Error> {
     Ok(serde_json::from_str(&body).expect("Failed to parse Trello lists"))
 }
 
-async fn fetch_trello_cards(client: &Client) -> Result<Vec<TrelloCard>, Error> {
-    let trello_board_id = env::var("TRELLO_BOARD_ID").expect("TRELLO_BOARD_ID environment variable should exist");
-    let trello_api_key = env::var("TRELLO_API_KEY").expect("TRELLO_API_KEY environment variable should exist");
-    let trello_api_token = env::var("TRELLO_API_TOKEN").expect("TRELLO_API_TOKEN environment variable should exist");
+async fn fetch_trello_cards(client: &Client, trello_board_id: &str, credentials: &TrelloCredentials, include_archived: bool) -> Result<Vec<TrelloCard>, Error> {
+    let trello_api_key = &credentials.api_key;
+    let trello_api_token = &credentials.api_token;
 
-    let cards_url = format!("https://api.trello.com/1/boards/{}/cards?fields=badges,name,desc,idList,idMembers,url,labels&attachments=true&key={}&token={}", trello_board_id, trello_api_key, trello_api_token);
+    let filter = if include_archived { "all" } else { "open" };
+    let cards_url = format!("https://api.trello.com/1/boards/{}/cards?filter={}&fields=badges,name,desc,idList,idMembers,url,labels,closed,dateLastActivity&attachments=true&customFieldItems=true&key={}&token={}", trello_board_id, filter, trello_api_key, trello_api_token);
 
     let response = client.get(&cards_url)
         .send()
@@ -87,37 +259,52 @@ async fn fetch_trello_cards(client: &Client) -> Result<Vec<TrelloCard>, Error> {
     Ok(serde_json::from_str(&body).expect("Failed to parse Trello cards"))
 }
 
-impl TicketDetailsClient for Client {
-    async fn fetch_ticket_details(&self) -> Result<Vec<TicketDetails>, Error> {
-        let lists = fetch_trello_lists(&self).await?;
+impl PullRequestClient for TrelloTicketClient<'_> {
+    async fn fetch_pr_details(&self, pr_url: &str) -> Result<PullRequest, Error> {
+        self.github_client.fetch_pr_details(pr_url).await
+    }
+}
+
+impl IssueClient for TrelloTicketClient<'_> {
+    async fn fetch_issue_details(&self, issue_url: &str) -> Result<Issue, Error> {
+        self.github_client.fetch_issue_details(issue_url).await
+    }
+}
+
+impl TicketDetailsClient for TrelloTicketClient<'_> {
+    async fn fetch_ticket_details(&self, _time_budget: &TimeBudget) -> Result<Vec<TicketDetails>, Error> {
+        let lists = fetch_trello_lists(self.client, self.board_id, &self.credentials).await?;
         let list_name_to_ticket_state_map: HashMap<_, _> = lists.into_iter().map(|list| (list.id, TicketState::from_str(&list.name))).collect();
-        let cards = fetch_trello_cards(&self).await?;
+        let archived_since_date = self.sprint_start_date
+            .filter(|_| self.config.trello_archived_as_done)
+            .and_then(|start_date| parse_flexible_date(start_date).ok());
+        let cards = fetch_trello_cards(self.client, self.board_id, &self.credentials, archived_since_date.is_some()).await?;
+        let sprint_field = match &self.config.trello_sprint_field {
+            Some(field_name) => Some(fetch_trello_custom_field(self.client, self.board_id, &self.credentials, field_name).await?),
+            None => None,
+        };
 
         let card_url_to_name_map: HashMap<String, String> = cards.iter()
             .map(|card| (card.url.clone(), card.name.clone()))
             .collect();
         
-        Ok(cards.into_iter().filter_map(|card| {
-            list_name_to_ticket_state_map.get(&card.idList).and_then(|list_name_option| {
-                list_name_option.as_ref().map(|state| TicketDetails {
+        Ok(cards.into_iter()
+            .filter(|card| is_in_sprint(card, sprint_field.as_ref(), self.sprint_name, self.config.sprint_name_matching))
+            .filter_map(|card| {
+                card_state(&card, &list_name_to_ticket_state_map, archived_since_date, self.config.summary_timezone).map(|state| TicketDetails {
                     id: card.id.clone(),
                     name: card.name,
                     member_ids: card.idMembers,
-                    state: state.clone(),
+                    state,
                     url: card.url,
                     has_labels: !card.labels.is_empty(),
                     has_description: card.desc.as_ref().map_or(false, |d| !d.is_empty()),
                     labels: card.labels.iter().filter_map(|label| TicketLabel::from_str(&label.name)).collect(),
                     checklist_items: card.badges.checkItems,
                     checked_checklist_items: card.badges.checkItemsChecked,
-                    pr_url: card.attachments.iter()
-                        .find_map(|attachment| {
-                            if attachment.url.contains("github.com") && attachment.url.contains("/pull/") {
-                                Some(attachment.url.clone())
-                            } else {
-                                None
-                            }
-                        }),
+                    points: label_points(&card.labels),
+                    pr_url: find_github_attachment_url(&card.attachments, "/pull/"),
+                    issue_url: find_github_attachment_url(&card.attachments, "/issues/"),
                     dependency_of: card.attachments.iter()
                         .find_map(|attachment| {
                             if attachment.url.contains("trello.com/c") {
@@ -131,6 +318,186 @@ impl TicketDetailsClient for Client {
                         }),
                 })
             })
-        }).collect::<Vec<TicketDetails>>())
+            .collect::<Vec<TicketDetails>>())
     }    
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use anyhow::anyhow;
+    use chrono_tz::US::Pacific;
+    use super::*;
+
+    struct MockSecretsClient {
+        secret: Option<String>,
+        calls: Cell<u32>,
+    }
+
+    impl SecretsClient for MockSecretsClient {
+        async fn get_secret_string(&self, _secret_id: &str) -> Result<String> {
+            self.calls.set(self.calls.get() + 1);
+            self.secret.clone().ok_or(anyhow!("ResourceNotFoundException"))
+        }
+    }
+
+    fn attachment(url: &str) -> TrelloAttachment {
+        TrelloAttachment { name: "attachment".to_string(), url: url.to_string() }
+    }
+
+    #[test]
+    fn test_find_github_issue_url() {
+        let attachments = vec![
+            attachment("https://github.com/owner/repo/pull/2"),
+            attachment("https://github.com/owner/repo/issues/1"),
+        ];
+
+        assert_eq!(find_github_attachment_url(&attachments, "/issues/"), Some("https://github.com/owner/repo/issues/1".to_string()));
+        assert_eq!(find_github_attachment_url(&attachments, "/pull/"), Some("https://github.com/owner/repo/pull/2".to_string()));
+    }
+
+    #[test]
+    fn test_find_github_issue_url_without_issue() {
+        let attachments = vec![
+            attachment("https://github.com/owner/repo/pull/2"),
+            attachment("https://example.com/issues/1"),
+        ];
+
+        assert_eq!(find_github_attachment_url(&attachments, "/issues/"), None);
+    }
+
+    #[test]
+    fn test_points_from_card_labels() {
+        let labels = |names: &[&str]| -> Vec<TrelloLabel> { names.iter().map(|name| TrelloLabel { name: name.to_string() }).collect() };
+
+        assert_eq!(label_points(&labels(&["Front-End", "3pts"])), Some(3));
+        assert_eq!(label_points(&labels(&["5 Points"])), Some(5));
+        assert_eq!(label_points(&labels(&["1pt", "8pts"])), Some(1));
+        assert_eq!(label_points(&labels(&["Bug", "pts", "many pts"])), None);
+        assert_eq!(label_points(&[]), None);
+    }
+
+    const SPRINT_FIELD: &str = r#"{"id": "field1", "name": "Sprint", "type": "list", "options": [
+        {"id": "option21", "value": {"text": "21-Pascal"}},
+        {"id": "option22", "value": {"text": "22-Turing"}}
+    ]}"#;
+
+    fn card_with_custom_fields(custom_field_items: &str) -> TrelloCard {
+        serde_json::from_str(&format!(r#"{{
+            "id": "card1", "name": "Card", "idMembers": [], "idList": "list1", "url": "https://trello.com/c/card1",
+            "labels": [], "desc": null, "attachments": [], "badges": {{"checkItems": 0, "checkItemsChecked": 0}},
+            "customFieldItems": {}
+        }}"#, custom_field_items)).unwrap()
+    }
+
+    #[test]
+    fn test_card_included_by_custom_field_sprint() {
+        let field: TrelloCustomField = serde_json::from_str(SPRINT_FIELD).unwrap();
+        let card = card_with_custom_fields(r#"[{"idCustomField": "field1", "idValue": "option21"}]"#);
+
+        assert!(is_in_sprint(&card, Some(&field), "21-Pascal", SprintNameMatching::Exact));
+    }
+
+    #[test]
+    fn test_card_excluded_by_custom_field_sprint() {
+        let field: TrelloCustomField = serde_json::from_str(SPRINT_FIELD).unwrap();
+        let other_sprint_card = card_with_custom_fields(r#"[{"idCustomField": "field1", "idValue": "option22"}]"#);
+        let untagged_card = card_with_custom_fields(r#"[{"idCustomField": "field2", "value": {"text": "21-Pascal"}}]"#);
+
+        assert!(!is_in_sprint(&other_sprint_card, Some(&field), "21-Pascal", SprintNameMatching::Exact));
+        assert!(!is_in_sprint(&untagged_card, Some(&field), "21-Pascal", SprintNameMatching::Exact));
+        assert!(is_in_sprint(&untagged_card, None, "21-Pascal", SprintNameMatching::Exact), "Without a sprint field every card is included");
+    }
+
+    #[test]
+    fn test_text_custom_field_sprint_value() {
+        let field: TrelloCustomField = serde_json::from_str(r#"{"id": "field1", "name": "Sprint", "type": "text"}"#).unwrap();
+        let card = card_with_custom_fields(r#"[{"idCustomField": "field1", "value": {"text": "21-pascal "}}]"#);
+
+        assert_eq!(card_custom_field_value(&card, &field).as_deref(), Some("21-pascal "));
+        assert!(!is_in_sprint(&card, Some(&field), "21-Pascal", SprintNameMatching::Exact));
+        assert!(is_in_sprint(&card, Some(&field), "21-Pascal", SprintNameMatching::CaseInsensitive));
+    }
+
+    fn archived_card(closed: bool, date_last_activity: &str) -> TrelloCard {
+        TrelloCard { closed, date_last_activity: Some(date_last_activity.to_string()), ..card_with_custom_fields("[]") }
+    }
+
+    #[test]
+    fn test_archived_card_counts_as_completed_under_option() {
+        let list_states = HashMap::from([("list1".to_string(), Some(TicketState::InProgress))]);
+        let sprint_start = NaiveDate::from_ymd_opt(2024, 5, 1);
+        let card = archived_card(true, "2024-05-03T17:00:00.000Z");
+
+        assert_eq!(card_state(&card, &list_states, sprint_start, Pacific), Some(TicketState::Done));
+        assert_eq!(card_state(&card, &list_states, None, Pacific), None, "Archived cards are ignored without the option");
+    }
+
+    #[test]
+    fn test_card_archived_before_sprint_is_excluded() {
+        let list_states = HashMap::from([("list1".to_string(), Some(TicketState::InProgress))]);
+        let card = archived_card(true, "2024-04-30T17:00:00.000Z");
+
+        assert_eq!(card_state(&card, &list_states, NaiveDate::from_ymd_opt(2024, 5, 1), Pacific), None);
+    }
+
+    #[test]
+    fn test_open_card_state_comes_from_its_list() {
+        let list_states = HashMap::from([("list1".to_string(), Some(TicketState::InProgress))]);
+        let card = archived_card(false, "2024-04-30T17:00:00.000Z");
+
+        assert_eq!(card_state(&card, &list_states, NaiveDate::from_ymd_opt(2024, 5, 1), Pacific), Some(TicketState::InProgress));
+    }
+
+    #[tokio::test]
+    async fn test_credentials_resolved_from_secret_once() {
+        let secrets_client = MockSecretsClient {
+            secret: Some(r#"{"api_key": "secret-key", "api_token": "secret-token"}"#.to_string()),
+            calls: Cell::new(0),
+        };
+        let config = Config { trello_secret_arn: Some("arn:aws:secretsmanager:us-west-2:123:secret:trello".to_string()), ..Config::default() };
+        let resolver = TrelloCredentialsResolver::new(&secrets_client, &config);
+
+        let credentials = resolver.resolve().await.unwrap();
+        assert_eq!(credentials, TrelloCredentials { api_key: "secret-key".to_string(), api_token: "secret-token".to_string() });
+
+        let _ = resolver.resolve().await.unwrap();
+        assert_eq!(secrets_client.calls.get(), 1);
+
+        let client = Client::new();
+        let ticket_client = TrelloTicketClient::new(&client, &config, credentials, "Sprint 1", None);
+        assert_eq!(ticket_client.credentials.api_token, "secret-token");
+        assert_eq!(ticket_client.board_id, "TestBoardID");
+    }
+
+    #[tokio::test]
+    async fn test_credentials_secret_accepts_env_style_keys() {
+        let secrets_client = MockSecretsClient {
+            secret: Some(r#"{"TRELLO_API_KEY": "secret-key", "TRELLO_API_TOKEN": "secret-token"}"#.to_string()),
+            calls: Cell::new(0),
+        };
+        let config = Config { trello_secret_arn: Some("trello".to_string()), ..Config::default() };
+        let resolver = TrelloCredentialsResolver::new(&secrets_client, &config);
+
+        assert_eq!(resolver.resolve().await.unwrap().api_key, "secret-key");
+    }
+
+    #[tokio::test]
+    async fn test_credentials_secret_fetch_failure() {
+        let secrets_client = MockSecretsClient { secret: None, calls: Cell::new(0) };
+        let config = Config { trello_secret_arn: Some("trello".to_string()), ..Config::default() };
+        let resolver = TrelloCredentialsResolver::new(&secrets_client, &config);
+
+        assert!(resolver.resolve().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_credentials_resolved_from_config_without_secret() {
+        let secrets_client = MockSecretsClient { secret: None, calls: Cell::new(0) };
+        let config = Config::default();
+        let resolver = TrelloCredentialsResolver::new(&secrets_client, &config);
+
+        assert_eq!(resolver.resolve().await.unwrap(), TrelloCredentials { api_key: "TestTrelloKey".to_string(), api_token: "TestTrelloToken".to_string() });
+        assert_eq!(secrets_client.calls.get(), 0);
+    }
+}
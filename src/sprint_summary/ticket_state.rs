@@ -22,4 +22,27 @@ impl TicketState {
             _ => None,
         }
     }
+
+    pub fn is_terminal(&self) -> bool {
+        *self == TicketState::Done
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_done_is_terminal() {
+        assert!(TicketState::Done.is_terminal());
+    }
+
+    #[test]
+    fn test_active_states_are_not_terminal() {
+        assert!(!TicketState::InScope.is_terminal());
+        assert!(!TicketState::InvestigationDiscussion.is_terminal());
+        assert!(!TicketState::InProgress.is_terminal());
+        assert!(!TicketState::PendingRelease.is_terminal());
+        assert!(!TicketState::DemoFinalApproval.is_terminal());
+    }
 }
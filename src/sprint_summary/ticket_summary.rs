@@ -1,9 +1,12 @@
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize, Serializer};
 use serde_json::Value;
-use crate::utils::slack_components::{divider_block, list_block, section_block};
-use super::{sprint_records::{DailyTicketContext, DailyTicketContexts}, ticket::Ticket, ticket_state::TicketState};
+use crate::tracing::warn;
+use crate::utils::config::Config;
+use crate::utils::localization::{Label, Localization};
+use crate::utils::slack_components::{context_block, divider_block, list_block, section_block};
+use super::{sprint_records::{DailyTicketContext, DailyTicketContexts}, ticket::{Ticket, TicketWarning}, ticket_label::TicketLabel, ticket_state::TicketState};
 
 trait PrioritizedPush {
     fn prioritized_push(&mut self, ticket: Ticket);
@@ -19,12 +22,85 @@ impl PrioritizedPush for VecDeque<Ticket> {
     }
 }
 
-#[derive(Debug, Serialize)]
+//an empty sprint has nothing to complete, which reads better as 0% than NaN%
+fn completed_percentage(completed_count: usize, ticket_count: u32) -> f64 {
+    if ticket_count == 0 {
+        return 0.0;
+    }
+
+    (completed_count as f64 / ticket_count as f64) * 100.0
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SummarySection {
+    OpenPrs,
+    BlockedPrs,
+    Demoes,
+    OpenTickets,
+    AssignedUnstarted,
+    Investigations,
+    CompletedTickets,
+    DeferredTickets,
+}
+
+const DEFAULT_SECTION_ORDER: [SummarySection; 8] = [
+    SummarySection::OpenPrs,
+    SummarySection::BlockedPrs,
+    SummarySection::Demoes,
+    SummarySection::OpenTickets,
+    SummarySection::AssignedUnstarted,
+    SummarySection::Investigations,
+    SummarySection::CompletedTickets,
+    SummarySection::DeferredTickets,
+];
+
+impl SummarySection {
+    pub fn from_str(key: &str) -> Option<Self> {
+        match key.trim() {
+            "open_prs" => Some(SummarySection::OpenPrs),
+            "blocked_prs" => Some(SummarySection::BlockedPrs),
+            "demoes" => Some(SummarySection::Demoes),
+            "open_tickets" => Some(SummarySection::OpenTickets),
+            "assigned_unstarted" => Some(SummarySection::AssignedUnstarted),
+            "investigations" => Some(SummarySection::Investigations),
+            "completed_tickets" => Some(SummarySection::CompletedTickets),
+            "deferred_tickets" => Some(SummarySection::DeferredTickets),
+            _ => None,
+        }
+    }
+
+    //sections left out of the configured order keep their default relative order at the end
+    pub fn parse_order(keys: &str) -> Vec<SummarySection> {
+        let mut order: Vec<SummarySection> = vec![];
+
+        for key in keys.split(',').map(str::trim).filter(|key| !key.is_empty()) {
+            match SummarySection::from_str(key) {
+                Some(section) if !order.contains(&section) => order.push(section),
+                Some(_) => {},
+                None => warn!("Ignoring unknown summary section {:?} in SUMMARY_SECTION_ORDER", key),
+            }
+        }
+        for section in DEFAULT_SECTION_ORDER {
+            if !order.contains(&section) {
+                order.push(section);
+            }
+        }
+
+        order
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TicketSummary {
     demoes: VecDeque<Ticket>,
     blocked_prs: VecDeque<Ticket>,
     open_prs: VecDeque<Ticket>,
     open_tickets: VecDeque<Ticket>,
+    #[serde(default, skip_serializing_if = "VecDeque::is_empty")]
+    investigations: VecDeque<Ticket>,
+    //in scope tickets that already have someone on them, they're outside the sprint counts
+    #[serde(default, skip_serializing_if = "VecDeque::is_empty")]
+    assigned_unstarted: VecDeque<Ticket>,
     pub deferred_tickets: VecDeque<Ticket>,
     pub completed_tickets: VecDeque<Ticket>,
     pub sprint_ticket_count: u32,
@@ -32,49 +108,211 @@ pub struct TicketSummary {
     project_ticket_count: u32,
     pub project_ticket_count_in_scope: u32,
     pub completed_percentage: f64,
+    //estimates of the tickets counted in the sprint, tickets without one add nothing
+    #[serde(default)]
+    pub completed_points: u32,
+    #[serde(default)]
+    pub total_points: u32,
+    #[serde(skip)]
+    pub pr_details_skipped: bool,
+    #[serde(skip)]
+    pub pr_details_unavailable: bool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub member_display_names: BTreeMap<String, String>,
 }
 
 impl TicketSummary {
+    pub fn member_ids(&self) -> BTreeSet<&str> {
+        [&self.demoes, &self.blocked_prs, &self.open_prs, &self.open_tickets, &self.assigned_unstarted, &self.investigations, &self.deferred_tickets, &self.completed_tickets].into_iter()
+            .flatten()
+            .flat_map(|ticket| ticket.members.iter().map(String::as_str))
+            .collect()
+    }
+
+    //most common labels first, e.g. "12 Front-End · 8 Back-End · 3 Bug"
+    pub fn label_breakdown(&self) -> Option<String> {
+        let mut label_counts: BTreeMap<&TicketLabel, u32> = BTreeMap::new();
+        for ticket in [&self.demoes, &self.blocked_prs, &self.open_prs, &self.open_tickets, &self.investigations, &self.deferred_tickets, &self.completed_tickets].into_iter().flatten() {
+            for label in &ticket.details.labels {
+                *label_counts.entry(label).or_default() += 1;
+            }
+        }
+
+        let mut label_counts: Vec<(&TicketLabel, u32)> = label_counts.into_iter().collect();
+        label_counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        if label_counts.is_empty() {
+            None
+        } else {
+            Some(label_counts.iter()
+                .map(|(label, count)| format!("{} {}", count, label.name()))
+                .collect::<Vec<String>>()
+                .join(" · "))
+        }
+    }
+
+    //every goal ticket left in the sprint and whether it was completed, deferred goals count as not done
+    pub fn goal_statuses(&self) -> Vec<(&Ticket, bool)> {
+        let completed_goals = self.completed_tickets.iter()
+            .filter(|ticket| ticket.is_goal())
+            .map(|ticket| (ticket, true));
+        let open_goals = [&self.demoes, &self.blocked_prs, &self.open_prs, &self.open_tickets, &self.investigations, &self.deferred_tickets].into_iter()
+            .flatten()
+            .filter(|ticket| ticket.is_goal())
+            .map(|ticket| (ticket, false));
+
+        completed_goals.chain(open_goals).collect()
+    }
+
+    pub fn into_goal_checklist_block(&self, localization: &Localization) -> Option<Value> {
+        let goal_statuses = self.goal_statuses();
+        if goal_statuses.is_empty() {
+            return None;
+        }
+
+        let checklist = goal_statuses.iter()
+            .map(|(ticket, done)| format!("{} <{}|{}>", if *done { "✅" } else { "❌" }, ticket.details.url, ticket.details.name))
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        Some(section_block(&format!("{}\n{}", localization.text(Label::GoalsHeader), checklist)))
+    }
+
+    //tickets grouped by each warning they trigger, so a ticket can show up under several
+    pub fn tickets_by_warning(&self, config: &Config) -> BTreeMap<TicketWarning, Vec<&Ticket>> {
+        let mut tickets_by_warning: BTreeMap<TicketWarning, Vec<&Ticket>> = BTreeMap::new();
+        for ticket in [&self.demoes, &self.blocked_prs, &self.open_prs, &self.open_tickets, &self.investigations, &self.deferred_tickets, &self.completed_tickets].into_iter().flatten() {
+            for warning in ticket.warnings(config) {
+                tickets_by_warning.entry(warning).or_default().push(ticket);
+            }
+        }
+
+        tickets_by_warning
+    }
+
+    //only the tickets that weren't already done in the last snapshot stay in the completed section
+    pub fn newly_completed_only(&self, previous_ticket_data: &DailyTicketContexts) -> TicketSummary {
+        let mut summary = self.clone();
+        summary.completed_tickets.retain(|ticket| !previous_ticket_data.was_completed(&ticket.details.id));
+        summary
+    }
+
+    //research isn't committed scope for some teams, so it gets its own section and stays out of the counts
+    pub fn excluding_investigations(mut self) -> TicketSummary {
+        for tickets in [&mut self.blocked_prs, &mut self.open_prs, &mut self.open_tickets] {
+            let (investigations, others): (VecDeque<Ticket>, VecDeque<Ticket>) = tickets.drain(..)
+                .partition(|ticket| ticket.details.state == TicketState::InvestigationDiscussion);
+            *tickets = others;
+            self.investigations.extend(investigations);
+        }
+
+        let investigation_count = self.investigations.len() as u32;
+        self.total_points -= self.investigations.iter().filter_map(|ticket| ticket.details.points).sum::<u32>();
+        self.sprint_ticket_count -= investigation_count;
+        self.open_ticket_count -= investigation_count;
+        self.completed_percentage = completed_percentage(self.completed_tickets.len(), self.sprint_ticket_count);
+        self
+    }
+
+    //deferred tickets have left the sprint, so some teams don't want them counted against the completion percentage
+    pub fn excluding_deferred_from_percentage(mut self) -> TicketSummary {
+        let percentage_base = self.sprint_ticket_count - self.deferred_tickets.len() as u32;
+        self.completed_percentage = completed_percentage(self.completed_tickets.len(), percentage_base);
+        self
+    }
+
+    pub fn without_assigned_unstarted(mut self) -> TicketSummary {
+        self.assigned_unstarted.clear();
+        self
+    }
+
     pub fn clear_completed_and_deferred(&mut self) {
         self.completed_tickets.clear();
         self.deferred_tickets.clear();
     }
+
+    //buckets fill in whatever order the ticket source returns, so stored json keeps goals first and then sorts each bucket by ticket id
+    pub fn serialize_sorted<S: Serializer>(summary: &TicketSummary, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut summary = summary.clone();
+        for tickets in [&mut summary.demoes, &mut summary.blocked_prs, &mut summary.open_prs, &mut summary.open_tickets, &mut summary.investigations, &mut summary.assigned_unstarted, &mut summary.deferred_tickets, &mut summary.completed_tickets] {
+            tickets.make_contiguous().sort_by(|a, b| b.is_goal().cmp(&a.is_goal()).then_with(|| a.details.id.cmp(&b.details.id)));
+        }
+
+        summary.serialize(serializer)
+    }
 }
 
-impl From<Vec<Ticket>> for TicketSummary {
-    fn from(tickets: Vec<Ticket>) -> Self {
+impl TicketSummary {
+    pub fn with_config(tickets: Vec<Ticket>, config: &Config) -> Self {
+        let summary = TicketSummary::from_tickets(tickets, config.include_draft_prs_in_blocked);
+
+        let summary = if config.exclude_investigations_from_scope {
+            summary.excluding_investigations()
+        } else {
+            summary
+        };
+
+        let summary = if config.exclude_deferred_from_percentage {
+            summary.excluding_deferred_from_percentage()
+        } else {
+            summary
+        };
+
+        //they're always bucketed, but only shown to teams that ask for them
+        if config.show_assigned_unstarted {
+            summary
+        } else {
+            summary.without_assigned_unstarted()
+        }
+    }
+
+    pub fn from_tickets(tickets: Vec<Ticket>, include_draft_prs_in_blocked: bool) -> Self {
         let mut demoes = VecDeque::new();
         let mut blocked_prs = VecDeque::new();
         let mut open_prs = VecDeque::new();
         let mut open_tickets = VecDeque::new();
         let mut completed_tickets = VecDeque::new();
         let mut deferred_tickets = VecDeque::new();
+        let mut assigned_unstarted = VecDeque::new();
 
         let mut sprint_ticket_count = 0;
         let mut project_ticket_count_in_scope = 0;
 
         let project_ticket_count = tickets.len() as u32;
+        let mut completed_points = 0;
+        let mut total_points = 0;
         
         for ticket in tickets {
+            let points = ticket.details.points.unwrap_or(0);
+
             if ticket.details.state == TicketState::Done {
                 sprint_ticket_count += 1;
+                completed_points += points;
+                total_points += points;
                 completed_tickets.prioritized_push(ticket);
-            } else if ticket.details.state <= TicketState::InScope || ticket.moved_out_of_sprint {
+            } else if ticket.moved_out_of_sprint {
+                //deferred tickets stay in the sprint count but never count as open, even if they're back in scope
                 if ticket.details.state == TicketState::InScope {
                     project_ticket_count_in_scope += 1;
                 }
-                
-                if ticket.moved_out_of_sprint {
-                    sprint_ticket_count += 1;
-                    deferred_tickets.prioritized_push(ticket);
+
+                sprint_ticket_count += 1;
+                total_points += points;
+                deferred_tickets.prioritized_push(ticket);
+            } else if ticket.details.state <= TicketState::InScope {
+                project_ticket_count_in_scope += 1;
+                if ticket.details.state == TicketState::InScope && !ticket.members.is_empty() {
+                    assigned_unstarted.prioritized_push(ticket);
                 }
             } else if ticket.details.state == TicketState::DemoFinalApproval {
                 sprint_ticket_count += 1;
+                total_points += points;
                 demoes.prioritized_push(ticket);
             } else {
                 sprint_ticket_count += 1;
+                total_points += points;
                 match &ticket.pr {
-                    Some(pr) if !pr.is_draft && pr.is_blocked() => {
+                    Some(pr) if (!pr.is_draft || include_draft_prs_in_blocked) && pr.is_blocked() => {
                         blocked_prs.prioritized_push(ticket);
                     },
                     Some(pr) if !pr.is_draft => {
@@ -87,55 +325,138 @@ impl From<Vec<Ticket>> for TicketSummary {
             }
         }
 
+        let open_ticket_count = (demoes.len() + blocked_prs.len() + open_prs.len() + open_tickets.len()) as u32;
+
         TicketSummary {
             demoes,
             blocked_prs,
             open_prs,
             open_tickets,
+            investigations: VecDeque::new(),
+            assigned_unstarted,
             sprint_ticket_count,
-            completed_percentage: (completed_tickets.len() as f64 / sprint_ticket_count as f64) * 100.0,
+            completed_percentage: completed_percentage(completed_tickets.len(), sprint_ticket_count),
+            completed_points,
+            total_points,
             project_ticket_count,
             project_ticket_count_in_scope,
-            open_ticket_count: sprint_ticket_count - completed_tickets.len() as u32 - deferred_tickets.len() as u32,
+            open_ticket_count,
             completed_tickets,
             deferred_tickets,
+            pr_details_skipped: false,
+            pr_details_unavailable: false,
+            member_display_names: BTreeMap::new(),
         }
     }
 }
 
 impl TicketSummary {
-    pub fn into_slack_blocks(&self) -> Vec<Value> {
-        let mut blocks: Vec<serde_json::Value> = vec![];
+    pub fn into_slack_blocks(&self, localization: &Localization, config: &Config) -> Vec<Value> {
+        self.build_slack_blocks(localization, config, false, &config.summary_section_order)
+    }
 
-        if !self.open_prs.is_empty() {
-            blocks.push(divider_block());
-            blocks.push(section_block("\n*📢 Open PRs*"));
-            blocks.push(list_block(self.open_prs.iter().map(|ticket| ticket.into_slack_blocks()).collect()));
+    pub fn into_review_slack_blocks(&self, localization: &Localization, config: &Config) -> Vec<Value> {
+        self.build_slack_blocks(localization, config, config.review_group_completed_by_assignee, &config.summary_section_order)
+    }
+
+    pub fn into_blocked_slack_blocks(&self, localization: &Localization, config: &Config) -> Option<Vec<Value>> {
+        if self.blocked_prs.is_empty() {
+            return None;
         }
-        if !self.blocked_prs.is_empty() {
-            blocks.push(divider_block());
-            blocks.push(section_block("\n*🚨 Blocked PRs*"));
-            blocks.push(list_block(self.blocked_prs.iter().map(|ticket| ticket.into_slack_blocks()).collect()));
+
+        Some(self.build_slack_blocks(localization, config, false, &[SummarySection::BlockedPrs]))
+    }
+
+    pub fn into_hygiene_slack_blocks(&self, localization: &Localization, config: &Config) -> Vec<Value> {
+        let tickets_by_warning = self.tickets_by_warning(config);
+        if tickets_by_warning.is_empty() {
+            return vec![section_block(&localization.text(Label::HygieneClean))];
         }
-        if !self.demoes.is_empty() {
+
+        let mut blocks = vec![];
+        for (warning, tickets) in tickets_by_warning {
             blocks.push(divider_block());
-            blocks.push(section_block("\n*🎥 Demo Available*"));
-            blocks.push(list_block(self.demoes.iter().map(|ticket| ticket.into_slack_blocks()).collect()));
+            blocks.push(section_block(&format!("\n*⚠️ {}*", localization.text(warning.label()))));
+            blocks.push(list_block(tickets.iter().map(|ticket| ticket.into_hygiene_slack_blocks(config)).collect()));
         }
-        if !self.open_tickets.is_empty() {
-            blocks.push(divider_block());
-            blocks.push(section_block("\n*Open Tickets*"));
-            blocks.push(list_block(self.open_tickets.iter().map(|ticket| ticket.into_slack_blocks()).collect()));
+        blocks.push(divider_block());
+
+        blocks
+    }
+
+    fn completed_tickets_by_member(&self) -> (BTreeMap<&str, Vec<&Ticket>>, Vec<&Ticket>) {
+        let mut tickets_by_member: BTreeMap<&str, Vec<&Ticket>> = BTreeMap::new();
+        let mut unassigned_tickets = vec![];
+
+        for ticket in &self.completed_tickets {
+            if ticket.members.is_empty() {
+                unassigned_tickets.push(ticket);
+            }
+
+            for member in &ticket.members {
+                tickets_by_member.entry(member.as_str()).or_default().push(ticket);
+            }
         }
-        if !self.completed_tickets.is_empty() {
-            blocks.push(divider_block());
-            blocks.push(section_block("\n*✅ Completed Tickets*"));
-            blocks.push(list_block(self.completed_tickets.iter().map(|ticket| ticket.into_slack_blocks()).collect()));
+
+        (tickets_by_member, unassigned_tickets)
+    }
+
+    fn completed_tickets_by_member_blocks(&self, localization: &Localization, config: &Config) -> Vec<Value> {
+        let mut blocks = vec![];
+        let (tickets_by_member, unassigned_tickets) = self.completed_tickets_by_member();
+
+        for (member, tickets) in tickets_by_member {
+            blocks.push(section_block(&format!("<@{}>", member)));
+            blocks.push(list_block(tickets.iter().map(|ticket| ticket.into_completed_slack_blocks(localization, config)).collect()));
+        }
+        if !unassigned_tickets.is_empty() {
+            blocks.push(section_block(&localization.text(Label::Unassigned)));
+            blocks.push(list_block(unassigned_tickets.iter().map(|ticket| ticket.into_completed_slack_blocks(localization, config)).collect()));
+        }
+
+        blocks
+    }
+
+    fn build_slack_blocks(&self, localization: &Localization, config: &Config, group_completed_by_member: bool, section_order: &[SummarySection]) -> Vec<Value> {
+        let mut blocks: Vec<serde_json::Value> = vec![];
+
+        if self.pr_details_skipped {
+            blocks.push(context_block(&localization.text(Label::PrDetailsSkipped)));
+        }
+
+        if self.pr_details_unavailable {
+            blocks.push(context_block(&localization.text(Label::PrDetailsUnavailable)));
         }
-        if !self.deferred_tickets.is_empty() {
+
+        for section in section_order {
+            let (tickets, header) = match section {
+                SummarySection::OpenPrs => (&self.open_prs, Label::OpenPrsHeader),
+                SummarySection::BlockedPrs => (&self.blocked_prs, Label::BlockedPrsHeader),
+                SummarySection::Demoes => (&self.demoes, Label::DemoesHeader),
+                SummarySection::OpenTickets => (&self.open_tickets, Label::OpenTicketsHeader),
+                SummarySection::AssignedUnstarted => (&self.assigned_unstarted, Label::AssignedUnstartedHeader),
+                SummarySection::Investigations => (&self.investigations, Label::InvestigationsHeader),
+                SummarySection::CompletedTickets => (&self.completed_tickets, Label::CompletedTicketsHeader),
+                SummarySection::DeferredTickets => (&self.deferred_tickets, Label::DeferredTicketsHeader),
+            };
+
+            if tickets.is_empty() {
+                continue;
+            }
+
             blocks.push(divider_block());
-            blocks.push(section_block("\n*Deferred Tickets*"));
-            blocks.push(list_block(self.deferred_tickets.iter().map(|ticket| ticket.into_slack_blocks()).collect()));
+            blocks.push(section_block(&format!("\n*{}*", localization.text(header))));
+            match section {
+                SummarySection::CompletedTickets if group_completed_by_member => {
+                    blocks.extend(self.completed_tickets_by_member_blocks(localization, config));
+                },
+                SummarySection::CompletedTickets => {
+                    blocks.push(list_block(tickets.iter().map(|ticket| ticket.into_completed_slack_blocks(localization, config)).collect()));
+                },
+                _ => {
+                    blocks.push(list_block(tickets.iter().map(|ticket| ticket.into_slack_blocks(localization, config)).collect()));
+                },
+            }
         }
 
         blocks.push(divider_block());
@@ -152,9 +473,11 @@ impl From<&TicketSummary> for DailyTicketContexts {
             tickets.extend(vec.iter().map(|ticket| DailyTicketContext::from(ticket)));
         };
 
+        extend_tickets(&summary.demoes);
         extend_tickets(&summary.blocked_prs);
         extend_tickets(&summary.open_prs);
         extend_tickets(&summary.open_tickets);
+        extend_tickets(&summary.investigations);
         extend_tickets(&summary.completed_tickets);
         extend_tickets(&summary.deferred_tickets);
 
@@ -164,7 +487,7 @@ impl From<&TicketSummary> for DailyTicketContexts {
 
 #[cfg(test)]
 pub mod mocks {
-    use std::collections::VecDeque;
+    use std::collections::{BTreeMap, VecDeque};
 
     use crate::sprint_summary::ticket::{Ticket, TicketDetails};
 
@@ -218,6 +541,8 @@ pub mod mocks {
                         ..Ticket::default() 
                     }
                 ]),
+                investigations: VecDeque::new(),
+                assigned_unstarted: VecDeque::new(),
                 deferred_tickets: VecDeque::from(vec![
                     Ticket {
                         details: TicketDetails {
@@ -232,6 +557,11 @@ pub mod mocks {
                 sprint_ticket_count: 15,
                 project_ticket_count_in_scope: 80,
                 completed_percentage: 0.5,
+                completed_points: 0,
+                total_points: 0,
+                pr_details_skipped: false,
+                pr_details_unavailable: false,
+                member_display_names: BTreeMap::new(),
             }
         }
     }
@@ -239,10 +569,11 @@ pub mod mocks {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use serde_json::json;
 
     use super::*;
-    use crate::sprint_summary::{ticket::{PullRequest, TicketDetails}, ticket_label::TicketLabel};
+    use crate::sprint_summary::{ticket::{CheckRunDetails, PullRequest, TicketDetails}, ticket_label::TicketLabel};
 
     #[test]
     fn test_prioritized_push() {
@@ -263,6 +594,104 @@ mod tests {
         assert_eq!(tickets.back().unwrap().is_goal(), false);
     }
 
+    #[test]
+    fn test_ticket_summary_counts_with_deferred_in_scope_tickets() {
+        let ticket_in_state = |state: TicketState, moved_out_of_sprint: bool| Ticket {
+            details: TicketDetails { state, ..TicketDetails::default() },
+            moved_out_of_sprint,
+            pr: None,
+            ..Ticket::default()
+        };
+
+        let summary = TicketSummary::with_config(vec![
+            ticket_in_state(TicketState::InScope, true),
+            ticket_in_state(TicketState::InScope, true),
+            ticket_in_state(TicketState::InProgress, true),
+            ticket_in_state(TicketState::InScope, false),
+            ticket_in_state(TicketState::InProgress, false),
+            ticket_in_state(TicketState::DemoFinalApproval, false),
+            ticket_in_state(TicketState::Done, false),
+        ], &Config::default());
+
+        assert_eq!(summary.deferred_tickets.len(), 3);
+        assert_eq!(summary.completed_tickets.len(), 1);
+        assert_eq!(summary.sprint_ticket_count, 6);
+        assert_eq!(summary.open_ticket_count, 2);
+        assert_eq!(summary.open_ticket_count, summary.sprint_ticket_count - summary.completed_tickets.len() as u32 - summary.deferred_tickets.len() as u32);
+        assert_eq!(summary.project_ticket_count_in_scope, 3, "Deferred tickets back in scope still count towards project scope");
+    }
+
+    #[test]
+    fn test_completed_percentage_with_and_without_deferrals() {
+        let ticket_in_state = |state: TicketState, moved_out_of_sprint: bool| Ticket {
+            details: TicketDetails { state, ..TicketDetails::default() },
+            moved_out_of_sprint,
+            pr: None,
+            ..Ticket::default()
+        };
+        let tickets = vec![
+            ticket_in_state(TicketState::Done, false),
+            ticket_in_state(TicketState::Done, false),
+            ticket_in_state(TicketState::InProgress, false),
+            ticket_in_state(TicketState::InProgress, true),
+            ticket_in_state(TicketState::InScope, true),
+        ];
+
+        let summary = TicketSummary::from_tickets(tickets.clone(), false);
+        assert_eq!(summary.sprint_ticket_count, 5);
+        assert_eq!(summary.completed_percentage, 40.0);
+
+        let summary = TicketSummary::from_tickets(tickets, false).excluding_deferred_from_percentage();
+        assert_eq!(summary.sprint_ticket_count, 5, "Deferred tickets still count towards the sprint");
+        assert_eq!(summary.open_ticket_count, 1);
+        assert!((summary.completed_percentage - 200.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_points_totals_skip_unestimated_tickets() {
+        let ticket = |state: TicketState, moved_out_of_sprint: bool, points: Option<u32>| Ticket {
+            details: TicketDetails { state, points, ..TicketDetails::default() },
+            moved_out_of_sprint,
+            pr: None,
+            ..Ticket::default()
+        };
+
+        let summary = TicketSummary::from_tickets(vec![
+            ticket(TicketState::Done, false, Some(3)),
+            ticket(TicketState::Done, false, None),
+            ticket(TicketState::InProgress, false, Some(5)),
+            ticket(TicketState::InProgress, false, None),
+            ticket(TicketState::InProgress, true, Some(2)),
+            ticket(TicketState::InScope, false, Some(8)),
+        ], false);
+
+        assert_eq!(summary.completed_points, 3);
+        assert_eq!(summary.total_points, 10, "Deferred tickets count towards the sprint, unstarted ones in scope don't");
+
+        let summary = TicketSummary::from_tickets(vec![ticket(TicketState::Done, false, None)], false);
+        assert_eq!((summary.completed_points, summary.total_points), (0, 0));
+    }
+
+    #[test]
+    fn test_member_ids_across_sections() {
+        let summary = TicketSummary {
+            open_tickets: VecDeque::from(vec![Ticket { members: vec!["U1".to_string(), "U2".to_string()], ..Ticket::default() }]),
+            completed_tickets: VecDeque::from(vec![Ticket { members: vec!["U2".to_string(), "U3".to_string()], ..Ticket::default() }]),
+            ..TicketSummary::default()
+        };
+
+        assert_eq!(summary.member_ids(), BTreeSet::from(["U1", "U2", "U3"]));
+    }
+
+    #[test]
+    fn test_member_display_names_serialized_when_resolved() {
+        let mut summary = TicketSummary::with_config(vec![], &Config::default());
+        assert!(serde_json::to_value(&summary).unwrap().get("member_display_names").is_none());
+
+        summary.member_display_names = BTreeMap::from([("U1".to_string(), "Jay".to_string())]);
+        assert_eq!(serde_json::to_value(&summary).unwrap()["member_display_names"]["U1"], "Jay");
+    }
+
     #[test]
     fn test_clear_completed_and_deferred() {
         let mut summary = TicketSummary {
@@ -281,7 +710,7 @@ mod tests {
     fn test_ticket_summary_from_empty_vec() {
         let tickets = vec![];
 
-        let summary = TicketSummary::from(tickets);
+        let summary = TicketSummary::with_config(tickets, &Config::default());
         assert_eq!(serde_json::to_value(&summary).expect("summary should be parseable"), json!({
             "demoes": [],
             "blocked_prs": [],
@@ -293,10 +722,12 @@ mod tests {
             "sprint_ticket_count": 0,
             "open_ticket_count": 0,
             "project_ticket_count_in_scope": 0,
-            "completed_percentage": null
+            "completed_percentage": 0.0,
+            "completed_points": 0,
+            "total_points": 0
           }));
 
-        let blocks = summary.into_slack_blocks();
+        let blocks = summary.into_slack_blocks(&Localization::default(), &Config::default());
         assert_eq!(serde_json::to_value(&blocks).expect("blocks should be parseable"), json!([
             {
               "type": "divider"
@@ -371,7 +802,7 @@ mod tests {
             in_scope_ticket.clone(),
         ];
 
-        let summary = TicketSummary::from(tickets);
+        let summary = TicketSummary::with_config(tickets, &Config::default());
         
         let summary_json = serde_json::to_value(&summary).expect("summary should be parseable");
 
@@ -391,4 +822,375 @@ mod tests {
         assert_eq!(summary_json["blocked_prs"], json!(vec![serde_json::to_value(&pr_blocked_ticket).unwrap()]), "Tickets with blocked PRs should match");
         assert_eq!(summary_json["deferred_tickets"], json!(vec![serde_json::to_value(&in_scope_and_deferred_ticket).unwrap(), serde_json::to_value(&deferred_ticket).unwrap()]), "Deferred tickets should match");
     }
+
+    #[test]
+    fn test_completed_tickets_grouped_by_member() {
+        let shared_ticket = Ticket {
+            details: TicketDetails { name: "Shared Ticket".to_string(), ..TicketDetails::default() },
+            members: vec!["U2".to_string(), "U1".to_string()],
+            ..Ticket::default()
+        };
+        let solo_ticket = Ticket {
+            details: TicketDetails { name: "Solo Ticket".to_string(), ..TicketDetails::default() },
+            members: vec!["U1".to_string()],
+            ..Ticket::default()
+        };
+        let unassigned_ticket = Ticket {
+            details: TicketDetails { name: "Unassigned Ticket".to_string(), ..TicketDetails::default() },
+            members: vec![],
+            ..Ticket::default()
+        };
+        let summary = TicketSummary {
+            completed_tickets: VecDeque::from(vec![shared_ticket, solo_ticket, unassigned_ticket]),
+            ..TicketSummary::default()
+        };
+
+        let (tickets_by_member, unassigned_tickets) = summary.completed_tickets_by_member();
+        let names_by_member: Vec<(&str, Vec<&str>)> = tickets_by_member.iter()
+            .map(|(member, tickets)| (*member, tickets.iter().map(|ticket| ticket.details.name.as_str()).collect()))
+            .collect();
+
+        assert_eq!(names_by_member, vec![
+            ("U1", vec!["Shared Ticket", "Solo Ticket"]),
+            ("U2", vec!["Shared Ticket"]),
+        ]);
+        assert_eq!(unassigned_tickets.len(), 1);
+        assert_eq!(unassigned_tickets[0].details.name, "Unassigned Ticket");
+    }
+
+    #[test]
+    fn test_review_slack_blocks_group_completed_by_member() {
+        let summary = TicketSummary {
+            completed_tickets: VecDeque::from(vec![Ticket {
+                details: TicketDetails { name: "Pairing Ticket".to_string(), ..TicketDetails::default() },
+                members: vec!["U1".to_string(), "U2".to_string()],
+                ..Ticket::default()
+            }]),
+            ..TicketSummary::default()
+        };
+
+        let grouped_blocks = serde_json::to_string(&summary.into_review_slack_blocks(&Localization::default(), &Config { review_group_completed_by_assignee: true, ..Config::default() })).unwrap();
+        assert!(grouped_blocks.contains("<@U1>"));
+        assert!(grouped_blocks.contains("<@U2>"));
+        assert_eq!(grouped_blocks.matches("Pairing Ticket").count(), 2, "Tickets with multiple members should appear under each member");
+
+        let ungrouped_blocks = serde_json::to_string(&summary.into_review_slack_blocks(&Localization::default(), &Config::default())).unwrap();
+        assert!(!ungrouped_blocks.contains("<@U1>"));
+        assert_eq!(ungrouped_blocks.matches("Pairing Ticket").count(), 1);
+    }
+
+    #[test]
+    fn test_slack_blocks_use_localized_section_headers() {
+        let localization = Localization::new(HashMap::from([
+            (Label::OpenTicketsHeader, "Tickets abiertos".to_string()),
+            (Label::CompletedTicketsHeader, "✅ Tickets completados".to_string()),
+        ]));
+
+        let blocks = serde_json::to_string(&TicketSummary::default().into_slack_blocks(&localization, &Config::default())).unwrap();
+
+        assert!(blocks.contains("*Tickets abiertos*"));
+        assert!(blocks.contains("*✅ Tickets completados*"));
+        assert!(!blocks.contains("*Open Tickets*"));
+        assert!(blocks.contains("*Deferred Tickets*"), "Labels without an override should fall back to English");
+    }
+
+    #[test]
+    fn test_slack_blocks_note_skipped_pr_details() {
+        let summary = TicketSummary {
+            pr_details_skipped: true,
+            ..TicketSummary::default()
+        };
+
+        let blocks = summary.into_slack_blocks(&Localization::default(), &Config::default());
+        assert!(blocks[0].to_string().contains("PR details were skipped"));
+
+        let complete_blocks = serde_json::to_string(&TicketSummary::default().into_slack_blocks(&Localization::default(), &Config::default())).unwrap();
+        assert!(!complete_blocks.contains("PR details were skipped"));
+    }
+
+    #[test]
+    fn test_slack_blocks_note_unavailable_pr_details() {
+        let summary = TicketSummary {
+            pr_details_unavailable: true,
+            ..TicketSummary::default()
+        };
+
+        let blocks = summary.into_slack_blocks(&Localization::default(), &Config::default());
+        assert!(blocks[0].to_string().contains("Some PR details couldn't be fetched"));
+    }
+
+    #[test]
+    fn test_parse_section_order() {
+        assert_eq!(SummarySection::parse_order(""), DEFAULT_SECTION_ORDER.to_vec());
+        assert_eq!(SummarySection::parse_order("blocked_prs, completed_tickets,unknown,blocked_prs"), vec![
+            SummarySection::BlockedPrs,
+            SummarySection::CompletedTickets,
+            SummarySection::OpenPrs,
+            SummarySection::Demoes,
+            SummarySection::OpenTickets,
+            SummarySection::AssignedUnstarted,
+            SummarySection::Investigations,
+            SummarySection::DeferredTickets,
+        ]);
+    }
+
+    #[test]
+    fn test_slack_blocks_follow_section_order() {
+        let summary = TicketSummary::default();
+        let section_order = SummarySection::parse_order("deferred_tickets,blocked_prs");
+
+        let blocks = serde_json::to_string(&summary.build_slack_blocks(&Localization::default(), &Config::default(), false, &section_order)).unwrap();
+
+        let deferred_position = blocks.find("*Deferred Tickets*").unwrap();
+        let blocked_position = blocks.find("*🚨 Blocked PRs*").unwrap();
+        let open_prs_position = blocks.find("*📢 Open PRs*").unwrap();
+        assert!(deferred_position < blocked_position);
+        assert!(blocked_position < open_prs_position);
+    }
+
+    #[test]
+    fn test_failing_draft_pr_excluded_from_blocked_by_default() {
+        let failing_draft_ticket = Ticket {
+            details: TicketDetails { name: "Failing Draft Ticket".to_string(), state: TicketState::InProgress, ..TicketDetails::default() },
+            pr: Some(PullRequest { is_draft: true, mergeable: Some(true), failing_check_runs: vec![CheckRunDetails { name: "build".to_string(), details_url: "https://example.com/build".to_string() }], ..PullRequest::default() }),
+            ..Ticket::default()
+        };
+
+        let summary = TicketSummary::from_tickets(vec![failing_draft_ticket], false);
+
+        assert!(summary.blocked_prs.is_empty());
+        assert_eq!(summary.open_tickets.len(), 1);
+    }
+
+    #[test]
+    fn test_failing_draft_pr_included_in_blocked_when_configured() {
+        let failing_draft_ticket = Ticket {
+            details: TicketDetails { name: "Failing Draft Ticket".to_string(), state: TicketState::InProgress, ..TicketDetails::default() },
+            pr: Some(PullRequest { is_draft: true, mergeable: Some(true), failing_check_runs: vec![CheckRunDetails { name: "build".to_string(), details_url: "https://example.com/build".to_string() }], ..PullRequest::default() }),
+            ..Ticket::default()
+        };
+        let passing_draft_ticket = Ticket {
+            details: TicketDetails { name: "Passing Draft Ticket".to_string(), state: TicketState::InProgress, ..TicketDetails::default() },
+            pr: Some(PullRequest { is_draft: true, mergeable: Some(true), ..PullRequest::default() }),
+            ..Ticket::default()
+        };
+
+        let summary = TicketSummary::from_tickets(vec![failing_draft_ticket, passing_draft_ticket], true);
+
+        assert_eq!(summary.blocked_prs.len(), 1);
+        assert_eq!(summary.blocked_prs[0].details.name, "Failing Draft Ticket");
+        assert_eq!(summary.open_tickets.len(), 1);
+        assert_eq!(summary.open_ticket_count, 2);
+    }
+
+    #[test]
+    fn test_label_breakdown_counts_labels_most_common_first() {
+        let labeled_ticket = |state: TicketState, labels: Vec<TicketLabel>| Ticket {
+            details: TicketDetails { state, labels, ..TicketDetails::default() },
+            ..Ticket::default()
+        };
+
+        let summary = TicketSummary::from_tickets(vec![
+            labeled_ticket(TicketState::InProgress, vec![TicketLabel::BackEnd, TicketLabel::Bug]),
+            labeled_ticket(TicketState::InProgress, vec![TicketLabel::FrontEnd]),
+            labeled_ticket(TicketState::Done, vec![TicketLabel::FrontEnd]),
+            labeled_ticket(TicketState::DemoFinalApproval, vec![TicketLabel::FrontEnd, TicketLabel::BackEnd]),
+            labeled_ticket(TicketState::InProgress, vec![]),
+        ], false);
+
+        assert_eq!(summary.label_breakdown(), Some("3 Front-End · 2 Back-End · 1 Bug".to_string()));
+    }
+
+    #[test]
+    fn test_label_breakdown_without_labels() {
+        let summary = TicketSummary::from_tickets(vec![Ticket {
+            details: TicketDetails { state: TicketState::InProgress, labels: vec![], ..TicketDetails::default() },
+            ..Ticket::default()
+        }], false);
+
+        assert_eq!(summary.label_breakdown(), None);
+    }
+
+    #[test]
+    fn test_goal_statuses_track_each_goal() {
+        let goal_ticket = |name: &str, state: TicketState, moved_out_of_sprint: bool| Ticket {
+            details: TicketDetails { name: name.to_string(), state, labels: vec![TicketLabel::Goal], ..TicketDetails::default() },
+            moved_out_of_sprint,
+            ..Ticket::default()
+        };
+
+        let summary = TicketSummary::from_tickets(vec![
+            goal_ticket("Open Goal", TicketState::InProgress, false),
+            goal_ticket("Done Goal", TicketState::Done, false),
+            goal_ticket("Deferred Goal", TicketState::InProgress, true),
+            Ticket { details: TicketDetails { state: TicketState::Done, labels: vec![], ..TicketDetails::default() }, ..Ticket::default() },
+        ], false);
+
+        let goal_statuses: Vec<(&str, bool)> = summary.goal_statuses().into_iter()
+            .map(|(ticket, done)| (ticket.details.name.as_str(), done))
+            .collect();
+        assert_eq!(goal_statuses, vec![("Done Goal", true), ("Open Goal", false), ("Deferred Goal", false)]);
+        assert!(TicketSummary::from_tickets(vec![], false).into_goal_checklist_block(&Localization::default()).is_none());
+    }
+
+    #[test]
+    fn test_hygiene_blocks_group_only_warned_tickets() {
+        let complete_details = TicketDetails { has_description: true, has_labels: true, ..TicketDetails::default() };
+        let summary = TicketSummary::from_tickets(vec![
+            Ticket { details: TicketDetails { name: "Complete Ticket".to_string(), ..complete_details.clone() }, members: vec!["U1".to_string()], ..Ticket::default() },
+            Ticket { details: TicketDetails { name: "Unassigned Ticket".to_string(), ..complete_details.clone() }, ..Ticket::default() },
+            Ticket { details: TicketDetails { name: "Bare Ticket".to_string(), ..TicketDetails::default() }, members: vec!["U2".to_string()], ..Ticket::default() },
+        ], false);
+
+        let tickets_by_warning = summary.tickets_by_warning(&Config::default());
+        let names = |warning: TicketWarning| tickets_by_warning[&warning].iter().map(|ticket| ticket.details.name.as_str()).collect::<Vec<&str>>();
+
+        assert_eq!(tickets_by_warning.keys().copied().collect::<Vec<TicketWarning>>(), vec![TicketWarning::MissingDescription, TicketWarning::MissingLabels, TicketWarning::MissingAssignees]);
+        assert_eq!(names(TicketWarning::MissingDescription), vec!["Bare Ticket"]);
+        assert_eq!(names(TicketWarning::MissingLabels), vec!["Bare Ticket"]);
+        assert_eq!(names(TicketWarning::MissingAssignees), vec!["Unassigned Ticket"]);
+
+        let blocks = serde_json::to_string(&summary.into_hygiene_slack_blocks(&Localization::default(), &Config::default())).unwrap();
+        assert!(blocks.contains("Missing Assignees"));
+        assert!(!blocks.contains("Complete Ticket"));
+
+        let localization = Localization::new(std::collections::HashMap::from([(Label::MissingAssigneesWarning, "Sin responsables".to_string())]));
+        let blocks = serde_json::to_string(&summary.into_hygiene_slack_blocks(&localization, &Config::default())).unwrap();
+        assert!(blocks.contains("*⚠️ Sin responsables*"));
+    }
+
+    #[test]
+    fn test_hygiene_blocks_without_warnings() {
+        let summary = TicketSummary::from_tickets(vec![
+            Ticket { details: TicketDetails { has_description: true, has_labels: true, ..TicketDetails::default() }, members: vec!["U1".to_string()], ..Ticket::default() },
+        ], false);
+
+        assert_eq!(summary.into_hygiene_slack_blocks(&Localization::default(), &Config::default()), vec![section_block("✨ Every ticket has the information it needs.")]);
+    }
+
+    #[test]
+    fn test_newly_completed_only_drops_previously_completed_tickets() {
+        let done_ticket = |id: &str| Ticket {
+            details: TicketDetails { id: id.to_string(), name: id.to_string(), state: TicketState::Done, ..TicketDetails::default() },
+            ..Ticket::default()
+        };
+        let summary = TicketSummary::from_tickets(vec![done_ticket("done_before"), done_ticket("done_today"), done_ticket("new_and_done")], false);
+        let previous_ticket_data = DailyTicketContexts {
+            tickets: VecDeque::from(vec![
+                DailyTicketContext { id: "done_before".to_string(), state: TicketState::Done, ..DailyTicketContext::default() },
+                DailyTicketContext { id: "done_today".to_string(), state: TicketState::InProgress, ..DailyTicketContext::default() },
+            ]),
+        };
+
+        let newly_completed = summary.newly_completed_only(&previous_ticket_data);
+
+        let completed_ids: Vec<&str> = newly_completed.completed_tickets.iter().map(|ticket| ticket.details.id.as_str()).collect();
+        assert_eq!(completed_ids, vec!["done_today", "new_and_done"]);
+        assert_eq!(newly_completed.sprint_ticket_count, 3);
+        assert_eq!(summary.completed_tickets.len(), 3);
+    }
+
+    #[test]
+    fn test_assigned_unstarted_tickets_shown_only_when_enabled() {
+        let tickets = vec![
+            Ticket { details: TicketDetails { name: "Claimed Ticket".to_string(), state: TicketState::InScope, ..TicketDetails::default() }, members: vec!["U1".to_string()], ..Ticket::default() },
+            Ticket { details: TicketDetails { name: "Unclaimed Ticket".to_string(), state: TicketState::InScope, ..TicketDetails::default() }, members: vec![], ..Ticket::default() },
+            Ticket { details: TicketDetails { name: "Started Ticket".to_string(), state: TicketState::InProgress, ..TicketDetails::default() }, members: vec!["U2".to_string()], ..Ticket::default() },
+        ];
+
+        let summary = TicketSummary::from_tickets(tickets, false);
+        assert_eq!(summary.assigned_unstarted.iter().map(|ticket| ticket.details.name.as_str()).collect::<Vec<_>>(), vec!["Claimed Ticket"]);
+        assert_eq!(summary.sprint_ticket_count, 1, "Assigned in scope tickets aren't part of the sprint yet");
+        assert_eq!(summary.project_ticket_count_in_scope, 2);
+
+        let blocks = serde_json::to_string(&summary.into_slack_blocks(&Localization::default(), &Config::default())).unwrap();
+        assert!(blocks.contains("Assigned, Not Started"));
+        assert!(blocks.contains("Claimed Ticket"));
+        assert!(!blocks.contains("Unclaimed Ticket"));
+
+        let blocks = serde_json::to_string(&summary.without_assigned_unstarted().into_slack_blocks(&Localization::default(), &Config::default())).unwrap();
+        assert!(!blocks.contains("Assigned, Not Started"));
+        assert!(!blocks.contains("Claimed Ticket"));
+    }
+
+    #[test]
+    fn test_summary_json_is_identical_for_shuffled_tickets() {
+        let ticket = |id: &str, state: TicketState, labels: Vec<TicketLabel>| Ticket {
+            details: TicketDetails { id: id.to_string(), state, labels, ..TicketDetails::default() },
+            pr: None,
+            ..Ticket::default()
+        };
+        let tickets = vec![
+            ticket("c", TicketState::InProgress, vec![]),
+            ticket("a", TicketState::InProgress, vec![]),
+            ticket("d", TicketState::InProgress, vec![TicketLabel::Goal]),
+            ticket("b", TicketState::Done, vec![]),
+            ticket("e", TicketState::Done, vec![]),
+            ticket("f", TicketState::InProgress, vec![]),
+        ];
+        let mut shuffled = tickets.clone();
+        shuffled.swap(0, 4);
+        shuffled.swap(1, 5);
+        shuffled.reverse();
+
+        let to_json = |summary: TicketSummary| TicketSummary::serialize_sorted(&summary, serde_json::value::Serializer).map(|value| value.to_string()).unwrap();
+        let summary_json = to_json(TicketSummary::from_tickets(tickets, false));
+        let shuffled_json = to_json(TicketSummary::from_tickets(shuffled, false));
+
+        assert_eq!(summary_json, shuffled_json);
+        let summary_value: Value = serde_json::from_str(&summary_json).unwrap();
+        let open_ids: Vec<&str> = summary_value["open_tickets"].as_array().unwrap().iter().map(|ticket| ticket["details"]["id"].as_str().unwrap()).collect();
+        assert_eq!(open_ids, vec!["d", "a", "c", "f"]);
+    }
+
+    fn investigation_tickets() -> Vec<Ticket> {
+        vec![
+            Ticket { details: TicketDetails { name: "Research Ticket".to_string(), state: TicketState::InvestigationDiscussion, ..TicketDetails::default() }, ..Ticket::default() },
+            Ticket { details: TicketDetails { name: "Research PR".to_string(), state: TicketState::InvestigationDiscussion, ..TicketDetails::default() }, pr: Some(PullRequest::default()), ..Ticket::default() },
+            Ticket { details: TicketDetails { name: "Feature Ticket".to_string(), state: TicketState::InProgress, ..TicketDetails::default() }, ..Ticket::default() },
+            Ticket { details: TicketDetails { name: "Done Ticket".to_string(), state: TicketState::Done, ..TicketDetails::default() }, ..Ticket::default() },
+        ]
+    }
+
+    #[test]
+    fn test_completed_percentage_with_only_deferred_tickets() {
+        let deferred_ticket = Ticket {
+            details: TicketDetails { state: TicketState::InProgress, ..TicketDetails::default() },
+            moved_out_of_sprint: true,
+            pr: None,
+            ..Ticket::default()
+        };
+
+        let summary = TicketSummary::from_tickets(vec![deferred_ticket.clone(), deferred_ticket.clone()], false);
+        assert_eq!(summary.sprint_ticket_count, 2);
+        assert_eq!(summary.completed_percentage, 0.0);
+
+        let summary = TicketSummary::from_tickets(vec![deferred_ticket.clone(), deferred_ticket], false).excluding_deferred_from_percentage();
+        assert_eq!(summary.completed_percentage, 0.0, "Leaving out every deferred ticket leaves nothing to divide by");
+    }
+
+    #[test]
+    fn test_investigations_counted_in_scope_by_default() {
+        let summary = TicketSummary::from_tickets(investigation_tickets(), false);
+
+        assert_eq!(summary.sprint_ticket_count, 4);
+        assert_eq!(summary.open_ticket_count, 3);
+        assert_eq!(summary.completed_percentage, 25.0);
+        assert!(summary.investigations.is_empty());
+    }
+
+    #[test]
+    fn test_investigations_excluded_from_scope() {
+        let summary = TicketSummary::with_config(investigation_tickets(), &Config { exclude_investigations_from_scope: true, ..Config::default() });
+
+        assert_eq!(summary.sprint_ticket_count, 2);
+        assert_eq!(summary.open_ticket_count, 1);
+        assert_eq!(summary.completed_percentage, 50.0);
+        assert_eq!(summary.investigations.iter().map(|ticket| ticket.details.name.as_str()).collect::<Vec<_>>(), vec!["Research Ticket", "Research PR"]);
+
+        let blocks = serde_json::to_string(&summary.into_slack_blocks(&Localization::default(), &Config::default())).unwrap();
+        assert!(blocks.contains("Investigations"));
+        assert!(blocks.contains("Research PR"));
+        assert_eq!(DailyTicketContexts::from(&summary).tickets.len(), 4);
+    }
 }
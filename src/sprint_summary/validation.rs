@@ -5,33 +5,97 @@ use anyhow::{Result, anyhow};
 
 type HmacSha256 = Hmac<Sha256>;
 
+//slack's recommended window, older signed requests could be replays
+const MAX_REQUEST_AGE_SECONDS: i64 = 300;
+
 impl HttpRequest {
-    pub fn verify_slack_request(&self) -> Result<()> {
-        let signing_secret = std::env::var("SLACK_APP_SIGNING_SECRET")
-            .expect("SLACK_APP_SIGNING_SECRET environment variable should exist");
+    //lambda function urls lowercase header names, so they're matched without case
+    fn header(&self, name: &str) -> Option<&String> {
+        self.headers.as_ref()?.iter()
+            .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
 
-        let headers = self.headers.as_ref().ok_or_else(|| anyhow!("No headers provided"))?;
+    pub fn verify_slack_request(&self, signing_secret: &str, now: i64) -> Result<()> {
+        if self.headers.is_none() {
+            return Err(anyhow!("No headers provided"));
+        }
 
-        let timestamp = headers.get("X-Slack-Request-Timestamp")
+        let timestamp = self.header("X-Slack-Request-Timestamp")
             .ok_or_else(|| anyhow!("Timestamp header missing"))?;
 
-        let slack_signature = headers.get("X-Slack-Signature")
+        let request_time: i64 = timestamp.parse()
+            .map_err(|_| anyhow!("Invalid timestamp header: {}", timestamp))?;
+
+        if (now - request_time).abs() > MAX_REQUEST_AGE_SECONDS {
+            return Err(anyhow!("Verification failed. Request timestamp is too old."));
+        }
+
+        let slack_signature = self.header("X-Slack-Signature")
             .ok_or_else(|| anyhow!("Signature header missing"))?;
 
-        let basestring = format!("v0:{}:{}", timestamp, self.body.as_ref().expect("should be parseable"));
+        let signature_bytes = slack_signature.strip_prefix("v0=")
+            .and_then(|signature| hex::decode(signature).ok())
+            .ok_or_else(|| anyhow!("Verification failed. Signatures do not match."))?;
+
+        let basestring = format!("v0:{}:{}", timestamp, self.body);
 
         let mut mac = HmacSha256::new_from_slice(signing_secret.as_bytes())
             .map_err(|_| anyhow!("Invalid key length for HMAC"))?;
 
         mac.update(basestring.as_bytes());
-        let result = mac.finalize();
-        let code_bytes = result.into_bytes();
-        let computed_signature = format!("v0={}", hex::encode(code_bytes));
-
-        if slack_signature != &computed_signature {
-            Err(anyhow!("Verification failed. Signatures do not match."))
-        } else {
-            Ok(())
+
+        //verify_slice compares in constant time, so the signature can't be guessed byte by byte
+        mac.verify_slice(&signature_bytes)
+            .map_err(|_| anyhow!("Verification failed. Signatures do not match."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use super::*;
+
+    const REQUEST_TIME: i64 = 1713257262;
+
+    fn signed_request(signature: &str) -> HttpRequest {
+        HttpRequest {
+            http_method: "POST".to_string(),
+            body: "token=abc&command=%2Fsprint-check-in".to_string(),
+            headers: Some(HashMap::from([
+                ("x-slack-request-timestamp".to_string(), REQUEST_TIME.to_string()),
+                ("x-slack-signature".to_string(), signature.to_string()),
+            ])),
         }
     }
-}
\ No newline at end of file
+
+    fn signature(signing_secret: &str) -> String {
+        let mut mac = HmacSha256::new_from_slice(signing_secret.as_bytes()).unwrap();
+        mac.update(b"v0:1713257262:token=abc&command=%2Fsprint-check-in");
+        format!("v0={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn test_signed_request_is_verified() {
+        let request = signed_request(&signature("signing-secret"));
+
+        assert!(request.verify_slack_request("signing-secret", REQUEST_TIME + 10).is_ok());
+        assert!(request.verify_slack_request("other-secret", REQUEST_TIME + 10).is_err());
+        assert!(signed_request("v0=not-hex").verify_slack_request("signing-secret", REQUEST_TIME).is_err());
+    }
+
+    #[test]
+    fn test_stale_request_is_rejected() {
+        let request = signed_request(&signature("signing-secret"));
+
+        assert!(request.verify_slack_request("signing-secret", REQUEST_TIME + MAX_REQUEST_AGE_SECONDS).is_ok());
+        assert_eq!(request.verify_slack_request("signing-secret", REQUEST_TIME + MAX_REQUEST_AGE_SECONDS + 1).unwrap_err().to_string(), "Verification failed. Request timestamp is too old.");
+    }
+
+    #[test]
+    fn test_unsigned_request_is_rejected() {
+        let request = HttpRequest { headers: None, ..signed_request("") };
+
+        assert_eq!(request.verify_slack_request("signing-secret", REQUEST_TIME).unwrap_err().to_string(), "No headers provided");
+    }
+}
@@ -0,0 +1,734 @@
+use std::collections::{BTreeSet, HashMap};
+use std::env;
+use std::fmt::Display;
+use std::str::FromStr;
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use chrono_tz::{Tz, US::Pacific};
+use crate::sprint_summary::sprint_records::SprintNameMatching;
+use crate::sprint_summary::SummarySection;
+use crate::sprint_summary::ticket_label::{parse_label_sla_days, TicketLabel};
+use crate::sprint_summary::ticket_sources::{CheckRunNameMapping, CheckRunPattern};
+use crate::utils::date::parse_flexible_date;
+use crate::utils::localization::Label;
+use crate::utils::quiet_hours::QuietHours;
+use crate::tracing::warn;
+
+//how the daily summary header shows the date, e.g. "05/01/24", "ends in 3 days" or both
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeaderDateStyle {
+    #[default]
+    Absolute,
+    Relative,
+    Both,
+}
+
+impl FromStr for HeaderDateStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(style: &str) -> Result<Self> {
+        match style {
+            "absolute" => Ok(HeaderDateStyle::Absolute),
+            "relative" => Ok(HeaderDateStyle::Relative),
+            "both" => Ok(HeaderDateStyle::Both),
+            _ => Err(anyhow!("Unknown header date style: {}", style)),
+        }
+    }
+}
+
+//SUMMARY_CANVAS keeps the latest summary in a canvas alongside the channel message, or in place of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryCanvas {
+    Also,
+    Instead,
+}
+
+impl FromStr for SummaryCanvas {
+    type Err = anyhow::Error;
+
+    fn from_str(mode: &str) -> Result<Self> {
+        match mode {
+            "also" => Ok(SummaryCanvas::Also),
+            "instead" => Ok(SummaryCanvas::Instead),
+            _ => Err(anyhow!("Unknown summary canvas mode: {}", mode)),
+        }
+    }
+}
+
+//KICKOFF_PREVIEW_ARCHIVE tidies up the sprint preview once its kickoff is confirmed, by deleting it or marking it as out of date
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewArchive {
+    Delete,
+    Update,
+}
+
+impl FromStr for PreviewArchive {
+    type Err = anyhow::Error;
+
+    fn from_str(mode: &str) -> Result<Self> {
+        match mode {
+            "delete" => Ok(PreviewArchive::Delete),
+            "update" => Ok(PreviewArchive::Update),
+            _ => Err(anyhow!("Unknown kickoff preview archive mode: {}", mode)),
+        }
+    }
+}
+
+//TICKET_SOURCE picks where tickets are read from, the Trello board or a GitHub repository's issues
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TicketSource {
+    #[default]
+    Trello,
+    GithubIssues,
+}
+
+impl FromStr for TicketSource {
+    type Err = anyhow::Error;
+
+    fn from_str(source: &str) -> Result<Self> {
+        match source {
+            "trello" => Ok(TicketSource::Trello),
+            "github_issues" => Ok(TicketSource::GithubIssues),
+            _ => Err(anyhow!("Unknown ticket source: {}", source)),
+        }
+    }
+}
+
+//a comma-separated list like 12/25/24,01/01/2025, where any bad date invalidates the whole list
+fn parse_holidays(dates: &str) -> Option<BTreeSet<NaiveDate>> {
+    dates.split(',')
+        .map(str::trim)
+        .filter(|date| !date.is_empty())
+        .map(|date| parse_flexible_date(date).ok())
+        .collect()
+}
+
+//EventBridge crons have six fields (minutes hours day-of-month month day-of-week year), with ? in exactly one of the day fields
+fn is_valid_cron_expression(expression: &str) -> bool {
+    let Some(fields) = expression.strip_prefix("cron(").and_then(|fields| fields.strip_suffix(')')) else {
+        return false;
+    };
+    let fields: Vec<&str> = fields.split_whitespace().collect();
+
+    fields.len() == 6
+        && ((fields[2] == "?") != (fields[4] == "?"))
+        && fields.iter().all(|field| field.chars().all(|c| c.is_ascii_alphanumeric() || "*?,-/#".contains(c)))
+}
+
+//a malformed trigger time shouldn't stop the bot, the default time is used instead
+fn trigger_time_or_default(name: &str, configured_time: Option<String>, default_time: &str) -> String {
+    match configured_time {
+        Some(time) if is_valid_cron_expression(&time) => time,
+        Some(time) => {
+            warn!("Ignoring {} {:?}, it isn't a valid cron(...) expression, using {}", name, time, default_time);
+            default_time.to_string()
+        },
+        None => default_time.to_string(),
+    }
+}
+
+fn or_off<T: Display>(setting: Option<T>) -> String {
+    setting.map_or("off".to_string(), |setting| setting.to_string())
+}
+
+fn list_or_none(settings: &[String]) -> String {
+    if settings.is_empty() { "none".to_string() } else { settings.join(", ") }
+}
+
+const DEFAULT_TIME_BUDGET_SECONDS: u64 = 25;
+const DEFAULT_TRIGGER_DEDUPE_WINDOW_SECONDS: i64 = 300;
+const DEFAULT_NEW_TICKET_DAYS: i64 = 2;
+const DEFAULT_NEARLY_DONE_CHECKLIST_PERCENT: f64 = 80.0;
+pub const DAILY_SUMMARY_TIME: &str = "cron(0 3 * * ? *)";
+pub const SPRINT_REVIEW_TIME: &str = "cron(0 4 * * ? *)";
+const DEFAULT_SUMMARY_BUCKET: &str = "agilesummary";
+const DEFAULT_GITHUB_IN_PROGRESS_LABEL: &str = "in progress";
+const DEFAULT_SUMMARY_INTRO_COMMANDS: &str = "/daily-trigger";
+const DEFAULT_VELOCITY_WINDOW: usize = 3;
+
+//Every setting the bot reads, loaded and validated once at startup
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub ticket_source: TicketSource,
+    pub trello_board_id: String,
+    pub trello_api_key: Option<String>,
+    pub trello_api_token: Option<String>,
+    pub trello_secret_arn: Option<String>,
+    pub trello_sprint_field: Option<String>,
+    pub trello_archived_as_done: bool,
+    pub github_token: String,
+    pub slack_oauth: String,
+    pub slack_signing_secret: String,
+    pub time_budget_seconds: u64,
+    pub trigger_dedupe_window_seconds: i64,
+    pub summary_locale: Option<String>,
+    pub summary_footer_links: Option<String>,
+    pub check_run_name_mappings: Vec<CheckRunNameMapping>,
+    pub include_member_display_names: bool,
+    pub pause_on_unavailable_channel: bool,
+    pub preview_cache_seconds: Option<i64>,
+    pub hide_empty_carryover: bool,
+    pub review_group_completed_by_assignee: bool,
+    pub scope_change_alert_percent: Option<f64>,
+    pub quiet_hours: Option<QuietHours>,
+    pub include_draft_prs_in_blocked: bool,
+    pub summary_section_order: Vec<SummarySection>,
+    pub nearly_done_checklist_percent: f64,
+    pub sprint_name_matching: SprintNameMatching,
+    pub header_date_style: HeaderDateStyle,
+    pub check_max_age_hours: Option<i64>,
+    pub daily_newly_completed_only: bool,
+    pub required_check_runs: Vec<CheckRunPattern>,
+    pub urgent_last_day_indicator: bool,
+    pub github_graphql: bool,
+    pub show_ticket_legend: bool,
+    pub review_include_goal: bool,
+    pub sprint_cancel_message: Option<String>,
+    pub record_cancelled_sprints: bool,
+    pub completion_chart_url: Option<String>,
+    pub exclude_investigations_from_scope: bool,
+    pub allowed_slack_app_ids: Vec<String>,
+    pub blocked_prs_channel: Option<String>,
+    pub max_failing_checks_shown: Option<usize>,
+    pub holidays: BTreeSet<NaiveDate>,
+    pub summary_timezone: Tz,
+    pub daily_summary_lambda_arn: Option<String>,
+    pub summary_throttle_seconds: Option<i64>,
+    pub daily_summary_time: String,
+    pub sprint_review_time: String,
+    pub dynamodb_table_name: Option<String>,
+    pub summary_canvas: Option<SummaryCanvas>,
+    pub summary_bucket: String,
+    pub github_issues_repo: Option<String>,
+    pub github_in_progress_label: String,
+    pub new_ticket_days: i64,
+    pub summary_intro: Option<String>,
+    pub summary_intro_commands: Vec<String>,
+    pub update_daily_summary_in_place: bool,
+    pub velocity_window: usize,
+    pub exclude_deferred_from_percentage: bool,
+    pub thread_daily_summaries: bool,
+    pub show_assigned_unstarted: bool,
+    pub kickoff_preview_archive: Option<PreviewArchive>,
+    pub label_sla_days: Vec<(TicketLabel, i64)>,
+    pub ephemeral_previews: bool,
+}
+
+struct ConfigReader<'a> {
+    vars: &'a HashMap<String, String>,
+    missing: Vec<&'static str>,
+    invalid: Vec<&'static str>,
+}
+
+impl<'a> ConfigReader<'a> {
+    fn optional(&self, name: &'static str) -> Option<String> {
+        self.vars.get(name).filter(|value| !value.is_empty()).cloned()
+    }
+
+    fn required(&mut self, name: &'static str) -> String {
+        self.optional(name).unwrap_or_else(|| {
+            self.missing.push(name);
+            String::new()
+        })
+    }
+
+    fn flag(&self, name: &'static str) -> bool {
+        self.optional(name).is_some_and(|value| value == "true")
+    }
+
+    fn parsed<T: FromStr>(&mut self, name: &'static str) -> Option<T> {
+        let value = self.optional(name)?;
+        let parsed = value.parse::<T>().ok();
+        if parsed.is_none() {
+            self.invalid.push(name);
+        }
+        parsed
+    }
+
+    fn with<T>(&mut self, name: &'static str, parse: impl FnOnce(&str) -> Option<T>) -> Option<T> {
+        let value = self.optional(name)?;
+        let parsed = parse(&value);
+        if parsed.is_none() {
+            self.invalid.push(name);
+        }
+        parsed
+    }
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self> {
+        Config::from_vars(&env::vars().collect())
+    }
+
+    pub fn from_vars(vars: &HashMap<String, String>) -> Result<Self> {
+        let mut reader = ConfigReader { vars, missing: vec![], invalid: vec![] };
+
+        let ticket_source: TicketSource = reader.parsed("TICKET_SOURCE").unwrap_or_default();
+        let trello_secret_arn = reader.optional("TRELLO_SECRET_ARN");
+        //the trello settings are only required when tickets come from trello
+        let (trello_api_key, trello_api_token) = if ticket_source == TicketSource::Trello && trello_secret_arn.is_none() {
+            (Some(reader.required("TRELLO_API_KEY")), Some(reader.required("TRELLO_API_TOKEN")))
+        } else {
+            (reader.optional("TRELLO_API_KEY"), reader.optional("TRELLO_API_TOKEN"))
+        };
+
+        let config = Config {
+            ticket_source,
+            trello_board_id: if ticket_source == TicketSource::Trello { reader.required("TRELLO_BOARD_ID") } else { reader.optional("TRELLO_BOARD_ID").unwrap_or_default() },
+            trello_api_key,
+            trello_api_token,
+            trello_secret_arn,
+            trello_sprint_field: reader.optional("TRELLO_SPRINT_FIELD"),
+            trello_archived_as_done: reader.flag("TRELLO_ARCHIVED_AS_DONE"),
+            github_token: reader.required("USER_GITHUB_TOKEN"),
+            slack_oauth: reader.required("SLACK_OAUTH"),
+            slack_signing_secret: reader.required("SLACK_APP_SIGNING_SECRET"),
+            time_budget_seconds: reader.parsed("TIME_BUDGET_SECONDS").unwrap_or(DEFAULT_TIME_BUDGET_SECONDS),
+            trigger_dedupe_window_seconds: reader.parsed("TRIGGER_DEDUPE_WINDOW_SECONDS").unwrap_or(DEFAULT_TRIGGER_DEDUPE_WINDOW_SECONDS),
+            summary_locale: reader.optional("SUMMARY_LOCALE"),
+            summary_footer_links: reader.optional("SUMMARY_FOOTER_LINKS"),
+            check_run_name_mappings: reader.with("CHECK_RUN_NAME_MAPPINGS", |mappings| serde_json::from_str(mappings).ok()).unwrap_or_default(),
+            include_member_display_names: reader.flag("INCLUDE_MEMBER_DISPLAY_NAMES"),
+            pause_on_unavailable_channel: reader.flag("PAUSE_ON_UNAVAILABLE_CHANNEL"),
+            preview_cache_seconds: reader.parsed("PREVIEW_CACHE_SECONDS"),
+            hide_empty_carryover: reader.flag("HIDE_EMPTY_CARRYOVER"),
+            review_group_completed_by_assignee: reader.flag("REVIEW_GROUP_COMPLETED_BY_ASSIGNEE"),
+            scope_change_alert_percent: reader.parsed("SCOPE_CHANGE_ALERT_PERCENT"),
+            quiet_hours: reader.with("QUIET_HOURS", QuietHours::parse),
+            include_draft_prs_in_blocked: reader.flag("INCLUDE_DRAFT_PRS_IN_BLOCKED"),
+            summary_section_order: SummarySection::parse_order(&reader.optional("SUMMARY_SECTION_ORDER").unwrap_or_default()),
+            nearly_done_checklist_percent: reader.parsed("NEARLY_DONE_CHECKLIST_PERCENT").unwrap_or(DEFAULT_NEARLY_DONE_CHECKLIST_PERCENT),
+            sprint_name_matching: if reader.flag("CASE_INSENSITIVE_SPRINT_NAMES") { SprintNameMatching::CaseInsensitive } else { SprintNameMatching::Exact },
+            header_date_style: reader.parsed("HEADER_DATE_STYLE").unwrap_or_default(),
+            check_max_age_hours: reader.parsed("CHECK_MAX_AGE_HOURS"),
+            daily_newly_completed_only: reader.flag("DAILY_NEWLY_COMPLETED_ONLY"),
+            required_check_runs: reader.optional("REQUIRED_CHECK_RUNS")
+                .map(|names| names.split(',').map(str::trim).filter(|name| !name.is_empty()).map(CheckRunPattern::new).collect())
+                .unwrap_or_default(),
+            urgent_last_day_indicator: reader.flag("URGENT_LAST_DAY_INDICATOR"),
+            github_graphql: reader.flag("GITHUB_GRAPHQL"),
+            show_ticket_legend: reader.flag("SHOW_TICKET_LEGEND"),
+            review_include_goal: reader.flag("REVIEW_INCLUDE_GOAL"),
+            sprint_cancel_message: reader.optional("SPRINT_CANCEL_MESSAGE"),
+            record_cancelled_sprints: reader.flag("RECORD_CANCELLED_SPRINTS"),
+            completion_chart_url: reader.optional("COMPLETION_CHART_URL"),
+            exclude_investigations_from_scope: reader.flag("EXCLUDE_INVESTIGATIONS_FROM_SCOPE"),
+            allowed_slack_app_ids: reader.optional("ALLOWED_SLACK_APP_IDS")
+                .map(|app_ids| app_ids.split(',').map(|app_id| app_id.trim().to_string()).filter(|app_id| !app_id.is_empty()).collect())
+                .unwrap_or_default(),
+            blocked_prs_channel: reader.optional("BLOCKED_PRS_CHANNEL"),
+            max_failing_checks_shown: reader.parsed("MAX_FAILING_CHECKS_SHOWN").filter(|count| *count > 0),
+            holidays: reader.with("HOLIDAYS", parse_holidays).unwrap_or_default(),
+            //an iana name like America/New_York, summaries are dated and quiet hours checked in this zone
+            summary_timezone: reader.with("SUMMARY_TIMEZONE", |name| name.trim().parse().ok()).unwrap_or(Pacific),
+            daily_summary_lambda_arn: reader.optional("DAILY_SUMMARY_LAMBDA_ARN"),
+            summary_throttle_seconds: reader.parsed("SUMMARY_THROTTLE_SECONDS"),
+            daily_summary_time: trigger_time_or_default("DAILY_SUMMARY_CRON", reader.optional("DAILY_SUMMARY_CRON"), DAILY_SUMMARY_TIME),
+            sprint_review_time: trigger_time_or_default("SPRINT_REVIEW_CRON", reader.optional("SPRINT_REVIEW_CRON"), SPRINT_REVIEW_TIME),
+            dynamodb_table_name: reader.optional("DYNAMODB_TABLE_NAME"),
+            summary_canvas: reader.parsed("SUMMARY_CANVAS"),
+            summary_bucket: reader.optional("SUMMARY_BUCKET").unwrap_or(DEFAULT_SUMMARY_BUCKET.to_string()),
+            github_issues_repo: if ticket_source == TicketSource::GithubIssues { Some(reader.required("GITHUB_ISSUES_REPO")) } else { reader.optional("GITHUB_ISSUES_REPO") },
+            github_in_progress_label: reader.optional("GITHUB_IN_PROGRESS_LABEL").unwrap_or(DEFAULT_GITHUB_IN_PROGRESS_LABEL.to_string()),
+            new_ticket_days: reader.parsed("NEW_TICKET_DAYS").filter(|days| *days >= 0).unwrap_or(DEFAULT_NEW_TICKET_DAYS),
+            summary_intro: reader.optional("SUMMARY_INTRO").filter(|intro| !intro.trim().is_empty()),
+            summary_intro_commands: reader.optional("SUMMARY_INTRO_COMMANDS").unwrap_or(DEFAULT_SUMMARY_INTRO_COMMANDS.to_string())
+                .split(',').map(|command| command.trim().to_string()).filter(|command| !command.is_empty()).collect(),
+            update_daily_summary_in_place: reader.flag("UPDATE_DAILY_SUMMARY_IN_PLACE"),
+            velocity_window: reader.parsed("VELOCITY_WINDOW").filter(|window| *window > 0).unwrap_or(DEFAULT_VELOCITY_WINDOW),
+            exclude_deferred_from_percentage: reader.flag("EXCLUDE_DEFERRED_FROM_PERCENTAGE"),
+            thread_daily_summaries: reader.flag("THREAD_DAILY_SUMMARIES"),
+            show_assigned_unstarted: reader.flag("SHOW_ASSIGNED_UNSTARTED"),
+            kickoff_preview_archive: reader.parsed("KICKOFF_PREVIEW_ARCHIVE"),
+            label_sla_days: parse_label_sla_days(&reader.optional("LABEL_SLA_DAYS").unwrap_or_default()),
+            ephemeral_previews: reader.flag("EPHEMERAL_PREVIEWS"),
+        };
+
+        let mut problems = vec![];
+        if !reader.missing.is_empty() {
+            problems.push(format!("missing {}", reader.missing.join(", ")));
+        }
+        if !reader.invalid.is_empty() {
+            problems.push(format!("invalid {}", reader.invalid.join(", ")));
+        }
+
+        if problems.is_empty() {
+            Ok(config)
+        } else {
+            Err(anyhow!("Invalid configuration: {}", problems.join("; ")))
+        }
+    }
+
+    //where the "View Sprint Board" links point, if the ticket source's board or repo is configured
+    pub fn board_url(&self) -> Option<String> {
+        match self.ticket_source {
+            TicketSource::GithubIssues => self.github_issues_repo.as_ref().map(|repo| format!("https://github.com/{}/issues", repo)),
+            TicketSource::Trello => Some(&self.trello_board_id).filter(|board_id| !board_id.is_empty()).map(|board_id| format!("https://trello.com/b/{}", board_id)),
+        }
+    }
+
+    //what /sprint-config shows, tokens, keys and URLs are left out since the output is posted to the channel
+    pub fn effective_settings(&self) -> Vec<(Label, String)> {
+        let enabled_flags: Vec<String> = [
+            ("INCLUDE_MEMBER_DISPLAY_NAMES", self.include_member_display_names),
+            ("PAUSE_ON_UNAVAILABLE_CHANNEL", self.pause_on_unavailable_channel),
+            ("HIDE_EMPTY_CARRYOVER", self.hide_empty_carryover),
+            ("INCLUDE_DRAFT_PRS_IN_BLOCKED", self.include_draft_prs_in_blocked),
+            ("EXCLUDE_INVESTIGATIONS_FROM_SCOPE", self.exclude_investigations_from_scope),
+            ("REVIEW_GROUP_COMPLETED_BY_ASSIGNEE", self.review_group_completed_by_assignee),
+            ("DAILY_NEWLY_COMPLETED_ONLY", self.daily_newly_completed_only),
+            ("URGENT_LAST_DAY_INDICATOR", self.urgent_last_day_indicator),
+            ("SHOW_TICKET_LEGEND", self.show_ticket_legend),
+            ("REVIEW_INCLUDE_GOAL", self.review_include_goal),
+            ("RECORD_CANCELLED_SPRINTS", self.record_cancelled_sprints),
+            ("TRELLO_ARCHIVED_AS_DONE", self.trello_archived_as_done),
+            ("UPDATE_DAILY_SUMMARY_IN_PLACE", self.update_daily_summary_in_place),
+            ("SHOW_ASSIGNED_UNSTARTED", self.show_assigned_unstarted),
+            ("EXCLUDE_DEFERRED_FROM_PERCENTAGE", self.exclude_deferred_from_percentage),
+            ("THREAD_DAILY_SUMMARIES", self.thread_daily_summaries),
+            ("EPHEMERAL_PREVIEWS", self.ephemeral_previews),
+        ].into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| name.to_string())
+            .collect();
+
+        vec![
+            (Label::ConfigTimezone, self.summary_timezone.name().to_string()),
+            (Label::ConfigDailySummaryTime, self.daily_summary_time.clone()),
+            (Label::ConfigSprintReviewTime, self.sprint_review_time.clone()),
+            (Label::ConfigTicketSource, match self.ticket_source {
+                TicketSource::Trello => format!("Trello board {}", self.trello_board_id),
+                TicketSource::GithubIssues => format!("GitHub issues in {} (in progress label \"{}\")", self.github_issues_repo.as_deref().unwrap_or_default(), self.github_in_progress_label),
+            }),
+            (Label::ConfigTrelloSprintField, or_off(self.trello_sprint_field.as_ref())),
+            (Label::ConfigTrelloCredentials, if self.trello_secret_arn.is_some() { "Secrets Manager" } else { "environment" }.to_string()),
+            (Label::ConfigStorage, self.dynamodb_table_name.as_ref().map_or(format!("S3 bucket {}", self.summary_bucket), |table_name| format!("DynamoDB table {}", table_name))),
+            (Label::ConfigPrDetails, if self.github_graphql { "GitHub GraphQL" } else { "GitHub REST" }.to_string()),
+            (Label::ConfigLocale, self.summary_locale.clone().unwrap_or("default".to_string())),
+            (Label::ConfigHeaderDateStyle, format!("{:?}", self.header_date_style)),
+            (Label::ConfigSprintNameMatching, format!("{:?}", self.sprint_name_matching)),
+            (Label::ConfigSummarySectionOrder, self.summary_section_order.iter().map(|section| format!("{:?}", section)).collect::<Vec<_>>().join(", ")),
+            (Label::ConfigTimeBudget, format!("{}s", self.time_budget_seconds)),
+            (Label::ConfigTriggerDedupeWindow, format!("{}s", self.trigger_dedupe_window_seconds)),
+            (Label::ConfigVelocityWindow, format!("{} sprints", self.velocity_window)),
+            (Label::ConfigNewTicketWindow, format!("{} days", self.new_ticket_days)),
+            (Label::ConfigSummaryThrottle, or_off(self.summary_throttle_seconds.map(|seconds| format!("{}s", seconds)))),
+            (Label::ConfigPreviewCache, or_off(self.preview_cache_seconds.map(|seconds| format!("{}s", seconds)))),
+            (Label::ConfigSummaryCanvas, or_off(self.summary_canvas.map(|summary_canvas| format!("{:?}", summary_canvas)))),
+            (Label::ConfigKickoffPreviewArchive, or_off(self.kickoff_preview_archive.map(|preview_archive| format!("{:?}", preview_archive)))),
+            (Label::ConfigQuietHours, or_off(self.quiet_hours.as_ref())),
+            (Label::ConfigScopeChangeAlert, or_off(self.scope_change_alert_percent.map(|percent| format!("{}%", percent)))),
+            (Label::ConfigNearlyDoneChecklist, format!("over {}%", self.nearly_done_checklist_percent)),
+            (Label::ConfigLabelSlas, list_or_none(&self.label_sla_days.iter().map(|(label, sla_days)| format!("{}={}d", label.name(), sla_days)).collect::<Vec<_>>())),
+            (Label::ConfigCheckMaxAge, or_off(self.check_max_age_hours.map(|hours| format!("{}h", hours)))),
+            (Label::ConfigFailingChecksShown, self.max_failing_checks_shown.map_or("all".to_string(), |count| count.to_string())),
+            (Label::ConfigRequiredCheckRuns, list_or_none(&self.required_check_runs.iter().map(|check_run| check_run.pattern.clone()).collect::<Vec<_>>())),
+            (Label::ConfigBlockedPrsChannel, or_off(self.blocked_prs_channel.as_ref())),
+            (Label::ConfigSummaryIntro, or_off(self.summary_intro.as_ref().map(|_| format!("on {}", list_or_none(&self.summary_intro_commands))))),
+            (Label::ConfigAllowedSlackApps, list_or_none(&self.allowed_slack_app_ids)),
+            (Label::ConfigHolidays, list_or_none(&self.holidays.iter().map(|date| date.format("%m/%d/%y").to_string()).collect::<Vec<_>>())),
+            (Label::ConfigEnabledOptions, list_or_none(&enabled_flags)),
+        ]
+    }
+}
+
+#[cfg(test)]
+pub mod mocks {
+    use std::collections::BTreeSet;
+    use chrono_tz::US::Pacific;
+    use super::{Config, HeaderDateStyle, SprintNameMatching, SummarySection, TicketSource, DAILY_SUMMARY_TIME, DEFAULT_GITHUB_IN_PROGRESS_LABEL, DEFAULT_NEARLY_DONE_CHECKLIST_PERCENT, DEFAULT_NEW_TICKET_DAYS, DEFAULT_SUMMARY_BUCKET, DEFAULT_SUMMARY_INTRO_COMMANDS, DEFAULT_TIME_BUDGET_SECONDS, DEFAULT_TRIGGER_DEDUPE_WINDOW_SECONDS, DEFAULT_VELOCITY_WINDOW, SPRINT_REVIEW_TIME};
+
+    impl Default for Config {
+        fn default() -> Self {
+            Config {
+                ticket_source: TicketSource::Trello,
+                trello_board_id: "TestBoardID".to_string(),
+                trello_api_key: Some("TestTrelloKey".to_string()),
+                trello_api_token: Some("TestTrelloToken".to_string()),
+                trello_secret_arn: None,
+                trello_sprint_field: None,
+                trello_archived_as_done: false,
+                github_token: "TestGithubToken".to_string(),
+                slack_oauth: "TestSlackToken".to_string(),
+                slack_signing_secret: "TestSigningSecret".to_string(),
+                time_budget_seconds: DEFAULT_TIME_BUDGET_SECONDS,
+                trigger_dedupe_window_seconds: DEFAULT_TRIGGER_DEDUPE_WINDOW_SECONDS,
+                summary_locale: None,
+                summary_footer_links: None,
+                check_run_name_mappings: vec![],
+                include_member_display_names: false,
+                pause_on_unavailable_channel: false,
+                preview_cache_seconds: None,
+                hide_empty_carryover: false,
+                review_group_completed_by_assignee: false,
+                scope_change_alert_percent: None,
+                quiet_hours: None,
+                include_draft_prs_in_blocked: false,
+                summary_section_order: SummarySection::parse_order(""),
+                nearly_done_checklist_percent: DEFAULT_NEARLY_DONE_CHECKLIST_PERCENT,
+                sprint_name_matching: SprintNameMatching::Exact,
+                header_date_style: HeaderDateStyle::Absolute,
+                check_max_age_hours: None,
+                daily_newly_completed_only: false,
+                required_check_runs: vec![],
+                urgent_last_day_indicator: false,
+                github_graphql: false,
+                show_ticket_legend: false,
+                review_include_goal: false,
+                sprint_cancel_message: None,
+                record_cancelled_sprints: false,
+                completion_chart_url: None,
+                exclude_investigations_from_scope: false,
+                allowed_slack_app_ids: vec![],
+                blocked_prs_channel: None,
+                max_failing_checks_shown: None,
+                holidays: BTreeSet::new(),
+                summary_timezone: Pacific,
+                daily_summary_lambda_arn: None,
+                summary_throttle_seconds: None,
+                daily_summary_time: DAILY_SUMMARY_TIME.to_string(),
+                sprint_review_time: SPRINT_REVIEW_TIME.to_string(),
+                dynamodb_table_name: None,
+                summary_canvas: None,
+                summary_bucket: DEFAULT_SUMMARY_BUCKET.to_string(),
+                github_issues_repo: None,
+                github_in_progress_label: DEFAULT_GITHUB_IN_PROGRESS_LABEL.to_string(),
+                new_ticket_days: DEFAULT_NEW_TICKET_DAYS,
+                summary_intro: None,
+                summary_intro_commands: vec![DEFAULT_SUMMARY_INTRO_COMMANDS.to_string()],
+                update_daily_summary_in_place: false,
+                velocity_window: DEFAULT_VELOCITY_WINDOW,
+                exclude_deferred_from_percentage: false,
+                thread_daily_summaries: false,
+                show_assigned_unstarted: false,
+                kickoff_preview_archive: None,
+                label_sla_days: vec![],
+                ephemeral_previews: false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn test_fully_populated_config() {
+        let config = Config::from_vars(&vars(&[
+            ("TRELLO_BOARD_ID", "board123"),
+            ("TRELLO_API_KEY", "key"),
+            ("TRELLO_API_TOKEN", "token"),
+            ("USER_GITHUB_TOKEN", "ghp_token"),
+            ("SLACK_OAUTH", "xoxb-token"),
+            ("SLACK_APP_SIGNING_SECRET", "signing-secret"),
+            ("TIME_BUDGET_SECONDS", "40"),
+            ("TRIGGER_DEDUPE_WINDOW_SECONDS", "120"),
+            ("SUMMARY_LOCALE", "es"),
+            ("CHECK_RUN_NAME_MAPPINGS", r#"[{"pattern": "^build", "label": "Build"}]"#),
+            ("INCLUDE_MEMBER_DISPLAY_NAMES", "true"),
+            ("PAUSE_ON_UNAVAILABLE_CHANNEL", "true"),
+            ("PREVIEW_CACHE_SECONDS", "600"),
+            ("HIDE_EMPTY_CARRYOVER", "true"),
+            ("REVIEW_GROUP_COMPLETED_BY_ASSIGNEE", "false"),
+            ("SCOPE_CHANGE_ALERT_PERCENT", "20"),
+            ("QUIET_HOURS", "22-7"),
+            ("INCLUDE_DRAFT_PRS_IN_BLOCKED", "true"),
+            ("SUMMARY_SECTION_ORDER", "blocked_prs,open_prs"),
+            ("NEARLY_DONE_CHECKLIST_PERCENT", "90"),
+            ("CASE_INSENSITIVE_SPRINT_NAMES", "true"),
+            ("HEADER_DATE_STYLE", "both"),
+            ("REQUIRED_CHECK_RUNS", "build, ^test .*$"),
+            ("URGENT_LAST_DAY_INDICATOR", "true"),
+            ("EXCLUDE_INVESTIGATIONS_FROM_SCOPE", "true"),
+            ("ALLOWED_SLACK_APP_IDS", "A_PROD,A_DEV"),
+            ("MAX_FAILING_CHECKS_SHOWN", "3"),
+            ("HOLIDAYS", "12/25/24, 01/01/2025"),
+            ("SUMMARY_TIMEZONE", " Europe/London "),
+            ("DAILY_SUMMARY_LAMBDA_ARN", "arn:aws:lambda:eu-west-1:111122223333:function:sprint_bot"),
+            ("SUMMARY_THROTTLE_SECONDS", "900"),
+            ("DYNAMODB_TABLE_NAME", "sprint-state"),
+            ("SUMMARY_CANVAS", "instead"),
+            ("SUMMARY_BUCKET", "team-summary"),
+            ("NEW_TICKET_DAYS", "3"),
+            ("TRELLO_SPRINT_FIELD", "Sprint"),
+            ("TRELLO_ARCHIVED_AS_DONE", "true"),
+            ("SUMMARY_INTRO", "Standup at 10 in <https://meet.example.com/standup|the usual room>"),
+            ("SUMMARY_INTRO_COMMANDS", "/daily-trigger, /sprint-check-in"),
+            ("UPDATE_DAILY_SUMMARY_IN_PLACE", "true"),
+            ("VELOCITY_WINDOW", "5"),
+            ("EXCLUDE_DEFERRED_FROM_PERCENTAGE", "true"),
+            ("THREAD_DAILY_SUMMARIES", "true"),
+            ("SHOW_ASSIGNED_UNSTARTED", "true"),
+            ("KICKOFF_PREVIEW_ARCHIVE", "update"),
+            ("LABEL_SLA_DAYS", "Bug=3,Front-End=10"),
+            ("EPHEMERAL_PREVIEWS", "true"),
+        ])).unwrap();
+
+        assert_eq!(config.trello_board_id, "board123");
+        assert_eq!(config.trello_api_key.as_deref(), Some("key"));
+        assert_eq!(config.github_token, "ghp_token");
+        assert_eq!(config.slack_oauth, "xoxb-token");
+        assert_eq!(config.slack_signing_secret, "signing-secret");
+        assert_eq!(config.time_budget_seconds, 40);
+        assert_eq!(config.trigger_dedupe_window_seconds, 120);
+        assert_eq!(config.summary_locale.as_deref(), Some("es"));
+        assert_eq!(config.check_run_name_mappings.len(), 1);
+        assert!(config.include_member_display_names);
+        assert!(config.pause_on_unavailable_channel);
+        assert_eq!(config.preview_cache_seconds, Some(600));
+        assert!(config.hide_empty_carryover);
+        assert!(!config.review_group_completed_by_assignee);
+        assert_eq!(config.scope_change_alert_percent, Some(20.0));
+        assert_eq!(config.quiet_hours, QuietHours::parse("22-7"));
+        assert!(config.include_draft_prs_in_blocked);
+        assert_eq!(config.summary_section_order[..2], [SummarySection::BlockedPrs, SummarySection::OpenPrs]);
+        assert_eq!(config.nearly_done_checklist_percent, 90.0);
+        assert_eq!(config.sprint_name_matching, SprintNameMatching::CaseInsensitive);
+        assert_eq!(config.header_date_style, HeaderDateStyle::Both);
+        assert_eq!(config.required_check_runs.iter().map(|check_run| check_run.pattern.as_str()).collect::<Vec<_>>(), vec!["build", "^test .*$"]);
+        assert!(config.urgent_last_day_indicator);
+        assert!(config.exclude_investigations_from_scope);
+        assert_eq!(config.allowed_slack_app_ids, vec!["A_PROD".to_string(), "A_DEV".to_string()]);
+        assert_eq!(config.max_failing_checks_shown, Some(3));
+        assert_eq!(config.holidays, BTreeSet::from([NaiveDate::from_ymd_opt(2024, 12, 25).unwrap(), NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()]));
+        assert_eq!(config.summary_timezone, chrono_tz::Europe::London);
+        assert_eq!(config.daily_summary_lambda_arn.as_deref(), Some("arn:aws:lambda:eu-west-1:111122223333:function:sprint_bot"));
+        assert_eq!(config.summary_throttle_seconds, Some(900));
+        assert_eq!(config.dynamodb_table_name.as_deref(), Some("sprint-state"));
+        assert_eq!(config.summary_canvas, Some(SummaryCanvas::Instead));
+        assert_eq!(config.summary_bucket, "team-summary");
+        assert_eq!(config.new_ticket_days, 3);
+        assert_eq!(config.trello_sprint_field.as_deref(), Some("Sprint"));
+        assert!(config.trello_archived_as_done);
+        assert_eq!(config.summary_intro.as_deref(), Some("Standup at 10 in <https://meet.example.com/standup|the usual room>"));
+        assert_eq!(config.summary_intro_commands, vec!["/daily-trigger".to_string(), "/sprint-check-in".to_string()]);
+        assert!(config.update_daily_summary_in_place);
+        assert_eq!(config.velocity_window, 5);
+        assert!(config.exclude_deferred_from_percentage);
+        assert!(config.thread_daily_summaries);
+        assert!(config.show_assigned_unstarted);
+        assert_eq!(config.kickoff_preview_archive, Some(PreviewArchive::Update));
+        assert_eq!(config.label_sla_days, vec![(TicketLabel::Bug, 3), (TicketLabel::FrontEnd, 10)]);
+        assert!(config.ephemeral_previews);
+    }
+
+    #[test]
+    fn test_minimal_config_uses_defaults() {
+        let config = Config::from_vars(&vars(&[
+            ("TRELLO_BOARD_ID", "board123"),
+            ("TRELLO_SECRET_ARN", "arn:aws:secretsmanager:us-west-2:123:secret:trello"),
+            ("USER_GITHUB_TOKEN", "ghp_token"),
+            ("SLACK_OAUTH", "xoxb-token"),
+            ("SLACK_APP_SIGNING_SECRET", "signing-secret"),
+        ])).unwrap();
+
+        assert_eq!(config.trello_api_key, None);
+        assert_eq!(config.ticket_source, TicketSource::Trello);
+        assert_eq!(config.time_budget_seconds, DEFAULT_TIME_BUDGET_SECONDS);
+        assert_eq!(config.trigger_dedupe_window_seconds, DEFAULT_TRIGGER_DEDUPE_WINDOW_SECONDS);
+        assert_eq!(config.preview_cache_seconds, None);
+        assert_eq!(config.summary_bucket, DEFAULT_SUMMARY_BUCKET);
+        assert!(config.quiet_hours.is_none());
+        assert_eq!(config.nearly_done_checklist_percent, DEFAULT_NEARLY_DONE_CHECKLIST_PERCENT);
+        assert_eq!(config.max_failing_checks_shown, None);
+        assert_eq!(config.daily_summary_lambda_arn, None);
+        assert_eq!(config.daily_summary_time, DAILY_SUMMARY_TIME);
+        assert_eq!(config.sprint_review_time, SPRINT_REVIEW_TIME);
+        assert_eq!(config.new_ticket_days, DEFAULT_NEW_TICKET_DAYS);
+        assert_eq!(config.summary_timezone, Pacific);
+    }
+
+    fn trigger_time_config(daily_summary_cron: &str, sprint_review_cron: &str) -> Config {
+        Config::from_vars(&vars(&[
+            ("TRELLO_BOARD_ID", "board123"),
+            ("TRELLO_SECRET_ARN", "arn:aws:secretsmanager:us-west-2:123:secret:trello"),
+            ("USER_GITHUB_TOKEN", "ghp_token"),
+            ("SLACK_OAUTH", "xoxb-token"),
+            ("SLACK_APP_SIGNING_SECRET", "signing-secret"),
+            ("DAILY_SUMMARY_CRON", daily_summary_cron),
+            ("SPRINT_REVIEW_CRON", sprint_review_cron),
+        ])).unwrap()
+    }
+
+    #[test]
+    fn test_trigger_time_override() {
+        let config = trigger_time_config("cron(0 16 ? * MON-FRI *)", "cron(0 17 * * ? *)");
+
+        assert_eq!(config.daily_summary_time, "cron(0 16 ? * MON-FRI *)");
+        assert_eq!(config.sprint_review_time, "cron(0 17 * * ? *)");
+    }
+
+    #[test]
+    fn test_invalid_trigger_time_override_is_rejected() {
+        assert!(!is_valid_cron_expression("0 16 * * ? *"));
+        assert!(!is_valid_cron_expression("cron(0 16 * * *)"));
+        assert!(!is_valid_cron_expression("cron(0 16 * * * *)"));
+        assert!(!is_valid_cron_expression("cron(0 16 ? * ? *)"));
+        assert!(!is_valid_cron_expression("cron(0 16 * * ? *); rm"));
+        assert!(is_valid_cron_expression(DAILY_SUMMARY_TIME));
+        assert!(is_valid_cron_expression(SPRINT_REVIEW_TIME));
+
+        let config = trigger_time_config("every day at 4", "cron(0 16 ? * ? *)");
+        assert_eq!(config.daily_summary_time, DAILY_SUMMARY_TIME);
+        assert_eq!(config.sprint_review_time, SPRINT_REVIEW_TIME);
+        assert!(config.summary_intro.is_none());
+        assert_eq!(config.summary_intro_commands, vec![DEFAULT_SUMMARY_INTRO_COMMANDS.to_string()]);
+        assert_eq!(config.velocity_window, DEFAULT_VELOCITY_WINDOW);
+    }
+
+    #[test]
+    fn test_github_issues_source_does_not_need_trello() {
+        let config = Config::from_vars(&vars(&[
+            ("TICKET_SOURCE", "github_issues"),
+            ("GITHUB_ISSUES_REPO", "tifapp/FitnessProject"),
+            ("GITHUB_IN_PROGRESS_LABEL", "doing"),
+            ("USER_GITHUB_TOKEN", "ghp_token"),
+            ("SLACK_OAUTH", "xoxb-token"),
+            ("SLACK_APP_SIGNING_SECRET", "signing-secret"),
+        ])).unwrap();
+
+        assert_eq!(config.ticket_source, TicketSource::GithubIssues);
+        assert_eq!(config.trello_api_key, None);
+        assert_eq!(config.github_in_progress_label, "doing");
+        assert_eq!(config.board_url().as_deref(), Some("https://github.com/tifapp/FitnessProject/issues"));
+
+        let error = Config::from_vars(&vars(&[
+            ("TICKET_SOURCE", "github_issues"),
+            ("USER_GITHUB_TOKEN", "ghp_token"),
+            ("SLACK_OAUTH", "xoxb-token"),
+            ("SLACK_APP_SIGNING_SECRET", "signing-secret"),
+        ])).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid configuration: missing GITHUB_ISSUES_REPO");
+    }
+
+    #[test]
+    fn test_config_reports_every_missing_variable() {
+        let error = Config::from_vars(&vars(&[
+            ("TRELLO_API_KEY", "key"),
+            ("SLACK_OAUTH", "xoxb-token"),
+        ])).unwrap_err();
+
+        assert_eq!(error.to_string(), "Invalid configuration: missing TRELLO_API_TOKEN, TRELLO_BOARD_ID, USER_GITHUB_TOKEN, SLACK_APP_SIGNING_SECRET");
+    }
+
+    #[test]
+    fn test_config_reports_invalid_values() {
+        let error = Config::from_vars(&vars(&[
+            ("TRELLO_API_KEY", "key"),
+            ("TRELLO_API_TOKEN", "token"),
+            ("USER_GITHUB_TOKEN", "ghp_token"),
+            ("SLACK_OAUTH", "xoxb-token"),
+            ("SLACK_APP_SIGNING_SECRET", "signing-secret"),
+            ("TIME_BUDGET_SECONDS", "soon"),
+            ("QUIET_HOURS", "late"),
+            ("SUMMARY_TIMEZONE", "Mars/Olympus_Mons"),
+        ])).unwrap_err();
+
+        assert_eq!(error.to_string(), "Invalid configuration: missing TRELLO_BOARD_ID; invalid TIME_BUDGET_SECONDS, QUIET_HOURS, SUMMARY_TIMEZONE");
+    }
+}
@@ -1,20 +1,108 @@
-use chrono::{Local, NaiveDate, format::ParseError};
-use chrono_tz::US::Pacific;
+use anyhow::{Result, anyhow};
+use chrono::{Datelike, Local, NaiveDate, Timelike, Weekday};
+use chrono_tz::Tz;
 
-pub fn print_current_date() -> String {
-    let now = Local::now().with_timezone(&Pacific);
-    now.format("%m/%d/%y").to_string()
+pub fn print_current_date(timezone: Tz) -> String {
+    current_date(timezone).format("%m/%d/%y").to_string()
 }
 
-pub fn days_between(mmddyyy_1: Option<&str>, mmddyyy_2: &str) -> Result<i64, ParseError> {
-    let past_date = match mmddyyy_1 {
-        Some(date_str) => NaiveDate::parse_from_str(date_str, "%m/%d/%y")?,
-        None => NaiveDate::parse_from_str(&print_current_date(), "%m/%d/%y")?,
-    };
+pub fn current_date(timezone: Tz) -> NaiveDate {
+    Local::now().with_timezone(&timezone).date_naive()
+}
+
+//%Y would read the 25 in 09/20/25 as the year 25, so the two digit form is tried first
+pub fn parse_flexible_date(date_str: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(date_str, "%m/%d/%y")
+        .or_else(|_| NaiveDate::parse_from_str(date_str, "%m/%d/%Y"))
+        .map_err(|e| anyhow!("Failed to parse date: {}", e))
+}
 
-    let future_date = NaiveDate::parse_from_str(mmddyyy_2, "%m/%d/%y")?;
+//dates are stored as mm/dd/yy no matter how they were typed
+pub fn normalize_date(date_str: &str) -> Result<String> {
+    Ok(parse_flexible_date(date_str)?.format("%m/%d/%y").to_string())
+}
+
+pub fn days_between(mmddyyy_1: &str, mmddyyy_2: &str) -> Result<i64> {
+    let past_date = parse_flexible_date(mmddyyy_1)?;
+    let future_date = parse_flexible_date(mmddyyy_2)?;
 
     let difference = future_date.signed_duration_since(past_date).num_days();
 
     Ok(difference)
-}
\ No newline at end of file
+}
+
+//counts the weekdays after the first date up to and including the second, so a sprint ending on monday has one business day left on friday
+pub fn business_days_between(mmddyyy_1: &str, mmddyyy_2: &str) -> Result<i64> {
+    let past_date = parse_flexible_date(mmddyyy_1)?;
+    let future_date = parse_flexible_date(mmddyyy_2)?;
+
+    let (from, to, sign) = if past_date <= future_date { (past_date, future_date, 1) } else { (future_date, past_date, -1) };
+    let business_days = from.iter_days()
+        .skip(1)
+        .take_while(|date| *date <= to)
+        .filter(|date| !matches!(date.weekday(), Weekday::Sat | Weekday::Sun))
+        .count() as i64;
+
+    Ok(sign * business_days)
+}
+
+pub fn current_hour(timezone: Tz) -> u32 {
+    Local::now().with_timezone(&timezone).hour()
+}
+
+pub fn current_timestamp() -> i64 {
+    Local::now().timestamp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_two_digit_year() {
+        assert_eq!(parse_flexible_date("09/20/25").unwrap(), NaiveDate::from_ymd_opt(2025, 9, 20).unwrap());
+    }
+
+    #[test]
+    fn test_parse_four_digit_year() {
+        assert_eq!(parse_flexible_date("09/20/2025").unwrap(), NaiveDate::from_ymd_opt(2025, 9, 20).unwrap());
+        assert!(parse_flexible_date("banana").is_err());
+    }
+
+    #[test]
+    fn test_mixed_year_formats_round_trip() {
+        assert_eq!(normalize_date("09/20/2025").unwrap(), "09/20/25");
+        assert_eq!(normalize_date("09/20/25").unwrap(), "09/20/25");
+        assert_eq!(days_between("09/20/25", "09/30/2025").unwrap(), 10);
+        assert_eq!(days_between(&normalize_date("09/20/2025").unwrap(), "09/30/25").unwrap(), 10);
+    }
+
+    #[test]
+    fn test_business_days_across_a_weekend() {
+        //thursday to the following tuesday
+        assert_eq!(days_between("10/09/25", "10/14/25").unwrap(), 5);
+        assert_eq!(business_days_between("10/09/25", "10/14/25").unwrap(), 3);
+        assert_eq!(business_days_between("10/14/25", "10/09/25").unwrap(), -3);
+    }
+
+    #[test]
+    fn test_business_days_friday_to_monday() {
+        assert_eq!(days_between("10/10/25", "10/13/25").unwrap(), 3);
+        assert_eq!(business_days_between("10/10/25", "10/13/25").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_business_days_same_day() {
+        assert_eq!(business_days_between("10/10/25", "10/10/25").unwrap(), 0);
+        assert_eq!(business_days_between("10/11/25", "10/11/2025").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_days_between_consistent_across_timezones() {
+        //zones ahead of pacific can already be on the next day, but never further apart than that
+        let pacific_date = print_current_date(chrono_tz::US::Pacific);
+        let tokyo_date = print_current_date(chrono_tz::Asia::Tokyo);
+
+        assert!((0..=1).contains(&days_between(&pacific_date, &tokyo_date).unwrap()));
+    }
+}
@@ -0,0 +1,263 @@
+use aws_config::meta::region::RegionProviderChain;
+use aws_sdk_dynamodb::{Client, types::AttributeValue};
+use lambda_runtime::tracing::error;
+use serde_json::Value;
+use anyhow::{Result, Context, anyhow};
+use crate::utils::s3::JsonStorageClient;
+
+pub async fn create_dynamo_json_storage_client(table_name: String) -> DynamoJsonStorageClient<Client> {
+    let region_provider = RegionProviderChain::default_provider().or_else("us-west-2");
+    let config = aws_config::from_env().region(region_provider).load().await;
+    DynamoJsonStorageClient::new(aws_sdk_dynamodb::Client::new(&config), table_name)
+}
+
+//each json file is one item, keyed by its file name in the "key" attribute with the json as a string in "json"
+pub trait JsonItemStore {
+    async fn get_item_json(&self, table_name: &str, key: &str) -> Result<Option<String>>;
+    async fn put_item_json(&self, table_name: &str, key: &str, json_data: String) -> Result<()>;
+    async fn delete_item_json(&self, table_name: &str, key: &str) -> Result<()>;
+    //None only puts an item that doesn't exist yet, otherwise the stored json has to still match
+    async fn put_item_json_if(&self, table_name: &str, key: &str, json_data: String, expected: Option<String>) -> Result<bool>;
+    async fn delete_item_json_if(&self, table_name: &str, key: &str, expected: String) -> Result<bool>;
+}
+
+impl JsonItemStore for Client {
+    async fn get_item_json(&self, table_name: &str, key: &str) -> Result<Option<String>> {
+        let output = self.get_item()
+            .table_name(table_name)
+            .key("key", AttributeValue::S(key.to_string()))
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to fetch item from DynamoDB: {}", e))?;
+
+        Ok(output.item()
+            .and_then(|item| item.get("json"))
+            .and_then(|json_data| json_data.as_s().ok())
+            .cloned())
+    }
+
+    async fn put_item_json(&self, table_name: &str, key: &str, json_data: String) -> Result<()> {
+        self.put_item()
+            .table_name(table_name)
+            .item("key", AttributeValue::S(key.to_string()))
+            .item("json", AttributeValue::S(json_data))
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to insert json: {}", e))?;
+
+        Ok(())
+    }
+
+    async fn delete_item_json(&self, table_name: &str, key: &str) -> Result<()> {
+        self.delete_item()
+            .table_name(table_name)
+            .key("key", AttributeValue::S(key.to_string()))
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to delete json: {}", e))?;
+
+        Ok(())
+    }
+
+    async fn put_item_json_if(&self, table_name: &str, key: &str, json_data: String, expected: Option<String>) -> Result<bool> {
+        let request = self.put_item()
+            .table_name(table_name)
+            .item("key", AttributeValue::S(key.to_string()))
+            .item("json", AttributeValue::S(json_data));
+
+        //key is a reserved word in DynamoDB expressions, so the attributes go through names
+        let request = match expected {
+            Some(expected) => request
+                .condition_expression("#json = :expected")
+                .expression_attribute_names("#json", "json")
+                .expression_attribute_values(":expected", AttributeValue::S(expected)),
+            None => request
+                .condition_expression("attribute_not_exists(#key)")
+                .expression_attribute_names("#key", "key"),
+        };
+
+        match request.send().await {
+            Ok(_) => Ok(true),
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_conditional_check_failed_exception()) => Ok(false),
+            Err(e) => Err(anyhow!("Failed to insert json: {}", e)),
+        }
+    }
+
+    async fn delete_item_json_if(&self, table_name: &str, key: &str, expected: String) -> Result<bool> {
+        let request = self.delete_item()
+            .table_name(table_name)
+            .key("key", AttributeValue::S(key.to_string()))
+            .condition_expression("#json = :expected")
+            .expression_attribute_names("#json", "json")
+            .expression_attribute_values(":expected", AttributeValue::S(expected));
+
+        match request.send().await {
+            Ok(_) => Ok(true),
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_conditional_check_failed_exception()) => Ok(false),
+            Err(e) => Err(anyhow!("Failed to delete json: {}", e)),
+        }
+    }
+}
+
+pub struct DynamoJsonStorageClient<T: JsonItemStore> {
+    store: T,
+    table_name: String,
+}
+
+impl<T: JsonItemStore> DynamoJsonStorageClient<T> {
+    pub fn new(store: T, table_name: String) -> Self {
+        DynamoJsonStorageClient { store, table_name }
+    }
+}
+
+impl<T: JsonItemStore> JsonStorageClient for DynamoJsonStorageClient<T> {
+    async fn get_json(&self, key: &str) -> Result<Option<Value>> {
+        let Some(json_data) = self.store.get_item_json(&self.table_name, key).await? else {
+            return Ok(None);
+        };
+
+        match serde_json::from_str::<Value>(&json_data) {
+            Ok(json) => Ok(Some(json)),
+            Err(e) => {
+                error!("Failed to parse JSON data: {}", e);
+                Ok(None)
+            }
+        }
+    }
+
+    async fn put_json(&self, key: &str, json_value: &Value) -> Result<()> {
+        let json_data = serde_json::to_string(json_value)
+            .context("Failed to serialize json data")?;
+
+        self.store.put_item_json(&self.table_name, key, json_data).await
+    }
+
+    async fn delete_json(&self, key: &str) -> Result<()> {
+        self.store.delete_item_json(&self.table_name, key).await
+    }
+
+    async fn put_json_if(&self, key: &str, json_value: &Value, expected: Option<&Value>) -> Result<bool> {
+        let json_data = serde_json::to_string(json_value)
+            .context("Failed to serialize json data")?;
+        let expected = expected.map(serde_json::to_string).transpose()
+            .context("Failed to serialize expected json data")?;
+
+        self.store.put_item_json_if(&self.table_name, key, json_data, expected).await
+    }
+
+    async fn delete_json_if(&self, key: &str, expected: &Value) -> Result<bool> {
+        let expected = serde_json::to_string(expected)
+            .context("Failed to serialize expected json data")?;
+
+        self.store.delete_item_json_if(&self.table_name, key, expected).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::HashMap};
+    use serde_json::json;
+    use super::*;
+
+    #[derive(Default)]
+    struct MockItemStore {
+        items: RefCell<HashMap<(String, String), String>>,
+    }
+
+    impl JsonItemStore for MockItemStore {
+        async fn get_item_json(&self, table_name: &str, key: &str) -> Result<Option<String>> {
+            Ok(self.items.borrow().get(&(table_name.to_string(), key.to_string())).cloned())
+        }
+
+        async fn put_item_json(&self, table_name: &str, key: &str, json_data: String) -> Result<()> {
+            self.items.borrow_mut().insert((table_name.to_string(), key.to_string()), json_data);
+            Ok(())
+        }
+
+        async fn delete_item_json(&self, table_name: &str, key: &str) -> Result<()> {
+            self.items.borrow_mut().remove(&(table_name.to_string(), key.to_string()));
+            Ok(())
+        }
+
+        async fn put_item_json_if(&self, table_name: &str, key: &str, json_data: String, expected: Option<String>) -> Result<bool> {
+            let mut items = self.items.borrow_mut();
+            let item_key = (table_name.to_string(), key.to_string());
+            if items.get(&item_key) != expected.as_ref() {
+                return Ok(false);
+            }
+
+            items.insert(item_key, json_data);
+            Ok(true)
+        }
+
+        async fn delete_item_json_if(&self, table_name: &str, key: &str, expected: String) -> Result<bool> {
+            let mut items = self.items.borrow_mut();
+            let item_key = (table_name.to_string(), key.to_string());
+            if items.get(&item_key) != Some(&expected) {
+                return Ok(false);
+            }
+
+            items.remove(&item_key);
+            Ok(true)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dynamo_storage_round_trip() {
+        let client = DynamoJsonStorageClient::new(MockItemStore::default(), "sprint_data".to_string());
+        let sprint_data = json!({"name": "Sprint 1", "end_date": "05/01/24"});
+
+        client.put_json("sprint_data.json", &sprint_data).await.unwrap();
+
+        assert_eq!(client.get_json("sprint_data.json").await.unwrap(), Some(sprint_data));
+        assert_eq!(client.store.items.borrow().keys().next(), Some(&("sprint_data".to_string(), "sprint_data.json".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_dynamo_storage_delete() {
+        let client = DynamoJsonStorageClient::new(MockItemStore::default(), "sprint_data".to_string());
+
+        client.put_json("processing_lock.json", &json!({"holder": "request1", "acquired_at": 1000})).await.unwrap();
+        client.delete_json("processing_lock.json").await.unwrap();
+
+        assert_eq!(client.get_json("processing_lock.json").await.unwrap(), None);
+        client.delete_json("processing_lock.json").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_dynamo_storage_conditional_put() {
+        let client = DynamoJsonStorageClient::new(MockItemStore::default(), "sprint_data".to_string());
+        let first_lock = json!({"holder": "request1", "acquired_at": 1000});
+        let second_lock = json!({"holder": "request2", "acquired_at": 1001});
+
+        assert!(client.put_json_if("processing_lock.json", &first_lock, None).await.unwrap());
+        assert!(!client.put_json_if("processing_lock.json", &second_lock, None).await.unwrap());
+        assert!(!client.put_json_if("processing_lock.json", &second_lock, Some(&second_lock)).await.unwrap());
+        assert!(client.put_json_if("processing_lock.json", &second_lock, Some(&first_lock)).await.unwrap());
+
+        assert_eq!(client.get_json("processing_lock.json").await.unwrap(), Some(second_lock));
+    }
+
+    #[tokio::test]
+    async fn test_dynamo_storage_conditional_delete() {
+        let client = DynamoJsonStorageClient::new(MockItemStore::default(), "sprint_data".to_string());
+        let first_lock = json!({"holder": "request1", "acquired_at": 1000});
+        let second_lock = json!({"holder": "request2", "acquired_at": 1001});
+
+        client.put_json("processing_lock.json", &second_lock).await.unwrap();
+
+        assert!(!client.delete_json_if("processing_lock.json", &first_lock).await.unwrap());
+        assert_eq!(client.get_json("processing_lock.json").await.unwrap(), Some(second_lock.clone()));
+        assert!(client.delete_json_if("processing_lock.json", &second_lock).await.unwrap());
+        assert_eq!(client.get_json("processing_lock.json").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_dynamo_storage_missing_or_malformed_item() {
+        let client = DynamoJsonStorageClient::new(MockItemStore::default(), "sprint_data".to_string());
+        client.store.put_item_json("sprint_data", "ticket_data.json", "{not json".to_string()).await.unwrap();
+
+        assert_eq!(client.get_json("historical_data.json").await.unwrap(), None);
+        assert_eq!(client.get_json("ticket_data.json").await.unwrap(), None);
+    }
+}
@@ -3,11 +3,58 @@ use aws_config::meta::region::RegionProviderChain;
 use aws_sdk_eventbridge::{types::{RuleState, Target}, Client};
 use anyhow::{Result, anyhow};
 use crate::tracing::{error, info};
+use crate::utils::config::Config;
 
-pub async fn create_eventbridge_client() -> Client {
+pub async fn create_eventbridge_client(config: &Config) -> EventBridgeClient {
     let region_provider = RegionProviderChain::default_provider().or_else("us-west-2");
-    let config = aws_config::from_env().region(region_provider).load().await;
-    aws_sdk_eventbridge::Client::new(&config)
+    let aws_config = aws_config::from_env().region(region_provider).load().await;
+    EventBridgeClient::new(aws_sdk_eventbridge::Client::new(&aws_config), lambda_target_arn(config.daily_summary_lambda_arn.clone()))
+}
+
+//e.g. "Triggers sprint Pascal's summary daily at 03:00 UTC" for cron(0 3 * * ? *)
+pub fn trigger_rule_description(rule_name: &str, cron_expression: &str) -> String {
+    let daily_time = cron_expression.strip_prefix("cron(")
+        .and_then(|expression| expression.strip_suffix(')'))
+        .map(|expression| expression.split_whitespace().collect::<Vec<&str>>())
+        .and_then(|fields| match fields.as_slice() {
+            [minute, hour, "*", "*", "?", "*"] => Some((minute.parse::<u32>().ok()?, hour.parse::<u32>().ok()?)),
+            _ => None,
+        });
+
+    match daily_time {
+        Some((minute, hour)) => format!("Triggers sprint {}'s summary daily at {:02}:{:02} UTC", rule_name, hour, minute),
+        None => format!("Triggers sprint {}'s summary on schedule {}", rule_name, cron_expression),
+    }
+}
+
+const DEFAULT_LAMBDA_ARN: &str = "arn:aws:lambda:us-west-2:213277979580:function:daily_summary_bot";
+
+//DAILY_SUMMARY_LAMBDA_ARN points the rules at a deployment in another account or region
+fn lambda_target_arn(configured_arn: Option<String>) -> String {
+    configured_arn.filter(|arn| !arn.is_empty()).unwrap_or_else(|| DEFAULT_LAMBDA_ARN.to_string())
+}
+
+//the target is identified by its function name, e.g. daily_summary_bot in arn:aws:lambda:us-west-2:123:function:daily_summary_bot
+fn lambda_target_id(arn: &str) -> Result<String> {
+    arn.split(':')
+        .nth(6)
+        .filter(|function_name| !function_name.is_empty())
+        .map(str::to_string)
+        .ok_or(anyhow!("{} is not a Lambda function ARN", arn))
+}
+
+fn lambda_target(arn: &str) -> Result<Target> {
+    Ok(Target::builder()
+        .id(lambda_target_id(arn)?)
+        .arn(arn)
+        .build()?)
+}
+
+//rule names only allow letters, digits, dots, dashes and underscores, so multi-word sprint names are dashed
+pub fn trigger_rule_name(sprint_name: &str) -> String {
+    sprint_name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || ".-_".contains(c) { c } else { '-' })
+        .collect()
 }
 
 #[async_trait(?Send)]
@@ -15,42 +62,51 @@ pub trait NotificationClient {
     async fn create_daily_trigger_rule(&self, rule_name: &str, cron_expression: &str) -> Result<()>;
     async fn change_daily_trigger_rule(&self, rule_name: &str, cron_expression: &str) -> Result<()>;
     async fn delete_daily_trigger_rule(&self, rule_name: &str) -> Result<()>;
+    async fn disable_daily_trigger_rule(&self, rule_name: &str) -> Result<()>;
+}
+
+pub struct EventBridgeClient {
+    client: Client,
+    lambda_arn: String,
+}
+
+impl EventBridgeClient {
+    pub fn new(client: Client, lambda_arn: String) -> Self {
+        EventBridgeClient { client, lambda_arn }
+    }
 }
 
 #[async_trait(?Send)]
-impl NotificationClient for Client {
-    async fn create_daily_trigger_rule(&self, rule_name: &str, cron_expression: &str) -> Result<()> {
-        self.put_rule()
+impl NotificationClient for EventBridgeClient {
+    async fn create_daily_trigger_rule(&self, sprint_name: &str, cron_expression: &str) -> Result<()> {
+        let rule_name = &trigger_rule_name(sprint_name);
+        self.client.put_rule()
             .name(rule_name)
             .schedule_expression(cron_expression)
             .state(RuleState::Enabled)
-            .description("Triggers a Lambda daily at 7 PM UTC")
+            .description(trigger_rule_description(sprint_name, cron_expression))
             .send()
             .await
             .map_err(|e| anyhow!("Failed to create rule: {}", e))?;
 
-        let target = Target::builder()
-            .arn("arn:aws:lambda:us-west-2:213277979580:function:daily_summary_bot")
-            .id("daily_summary_bot")
-            .build()?;
-
-        self.put_targets()
+        self.client.put_targets()
             .rule(rule_name)
-            .targets(target)
+            .targets(lambda_target(&self.lambda_arn)?)
             .send().await.map_err(|e| anyhow!("Failed to set target for rule: {}", e))?;
 
         Ok(())
     }
     
-    async fn change_daily_trigger_rule(&self, rule_name: &str, cron_expression: &str) -> Result<()> {
-        let rule_description = self.describe_rule().name(rule_name).send().await
+    async fn change_daily_trigger_rule(&self, sprint_name: &str, cron_expression: &str) -> Result<()> {
+        let rule_name = &trigger_rule_name(sprint_name);
+        let rule_description = self.client.describe_rule().name(rule_name).send().await
             .map_err(|e| anyhow!("Failed to find rule: {}", e))?;
 
-        self.put_rule()
+        self.client.put_rule()
             .name(rule_name)
             .schedule_expression(cron_expression)
             .state(rule_description.state().unwrap_or(&RuleState::Enabled).clone())
-            .description(rule_description.description().unwrap_or("Updating cron expression"))
+            .description(trigger_rule_description(sprint_name, cron_expression))
             .send()
             .await
             .map_err(|e| anyhow!("Failed to update rule: {}", e))?;
@@ -58,25 +114,36 @@ impl NotificationClient for Client {
         Ok(())
     }
 
-    async fn delete_daily_trigger_rule(&self, rule_name: &str) -> Result<()> {
-        info!("{:?}", self.describe_rule().name(rule_name).send().await.map_err(|e| anyhow!("Failed to describe rule: {}", e))?);
+    async fn delete_daily_trigger_rule(&self, sprint_name: &str) -> Result<()> {
+        let rule_name = &trigger_rule_name(sprint_name);
+        info!("{:?}", self.client.describe_rule().name(rule_name).send().await.map_err(|e| anyhow!("Failed to describe rule: {}", e))?);
 
-        self.remove_targets()
+        self.client.remove_targets()
             .rule(rule_name)
-            .ids("daily_summary_bot")
+            .ids(lambda_target_id(&self.lambda_arn)?)
             .send().await
             .map_err(|e| anyhow!("Failed to remove target: {}", e))?;
 
-        self.delete_rule()
+        self.client.delete_rule()
             .name(rule_name)
             .send().await
             .map_err(|e| anyhow!("Failed to delete rule: {}", e))?;
 
         Ok(())
     }
-}
 
+    async fn disable_daily_trigger_rule(&self, sprint_name: &str) -> Result<()> {
+        let rule_name = &trigger_rule_name(sprint_name);
+        self.client.disable_rule()
+            .name(rule_name)
+            .send().await
+            .map_err(|e| anyhow!("Failed to disable rule: {}", e))?;
 
+        Ok(())
+    }
+}
+
+#[cfg(test)]
 pub mod eventbridge_mocks {
     use std::{collections::HashMap, sync::Arc};
     use tokio::sync::Mutex;
@@ -85,6 +152,7 @@ pub mod eventbridge_mocks {
     pub struct MockEventBridgeClient {
         pub rules_created: Arc<Mutex<HashMap<String, String>>>,
         pub rules_deleted: Arc<Mutex<Vec<String>>>,
+        pub rules_disabled: Arc<Mutex<Vec<String>>>,
     }
 
     impl MockEventBridgeClient {
@@ -92,6 +160,7 @@ pub mod eventbridge_mocks {
             Self {
                 rules_created: Arc::new(Mutex::new(HashMap::new())),
                 rules_deleted: Arc::new(Mutex::new(Vec::new())),
+                rules_disabled: Arc::new(Mutex::new(Vec::new())),
             }
         }
     }
@@ -99,7 +168,6 @@ pub mod eventbridge_mocks {
     #[async_trait(?Send)]
     impl NotificationClient for MockEventBridgeClient {
         async fn create_daily_trigger_rule(&self, rule_name: &str, cron_expression: &str) -> Result<()> {
-            let cron_expression = "cron(0 19 * * ? *)";
             let mut rules_created = self.rules_created.lock().await;
             rules_created.insert(rule_name.to_string(), cron_expression.to_string());
             Ok(())
@@ -127,5 +195,56 @@ pub mod eventbridge_mocks {
                 Err(anyhow!("Rule not found: {}", rule_name))
             }
         }
+
+        async fn disable_daily_trigger_rule(&self, rule_name: &str) -> Result<()> {
+            let rules_created = self.rules_created.lock().await;
+            if rules_created.contains_key(rule_name) {
+                let mut rules_disabled = self.rules_disabled.lock().await;
+                rules_disabled.push(rule_name.to_string());
+                Ok(())
+            } else {
+                Err(anyhow!("Rule not found: {}", rule_name))
+            }
+        }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_description_reflects_daily_schedule() {
+        assert_eq!(trigger_rule_description("Pascal", "cron(0 3 * * ? *)"), "Triggers sprint Pascal's summary daily at 03:00 UTC");
+        assert_eq!(trigger_rule_description("Pascal", "cron(30 19 * * ? *)"), "Triggers sprint Pascal's summary daily at 19:30 UTC");
+    }
+
+    #[test]
+    fn test_description_falls_back_to_schedule_expression() {
+        assert_eq!(trigger_rule_description("Pascal", "cron(0 3 ? * MON-FRI *)"), "Triggers sprint Pascal's summary on schedule cron(0 3 ? * MON-FRI *)");
+        assert_eq!(trigger_rule_description("Pascal", "rate(1 day)"), "Triggers sprint Pascal's summary on schedule rate(1 day)");
+    }
+
+    #[test]
+    fn test_rule_name_dashes_multi_word_sprint_names() {
+        assert_eq!(trigger_rule_name("Pascal"), "Pascal");
+        assert_eq!(trigger_rule_name("Q3 Payments Push"), "Q3-Payments-Push");
+        assert_eq!(trigger_rule_name("Rocket 🚀 v1.2_b"), "Rocket---v1.2_b");
+    }
+
+    #[test]
+    fn test_lambda_target_uses_configured_arn() {
+        let arn = lambda_target_arn(Some("arn:aws:lambda:eu-west-1:111122223333:function:sprint_bot".to_string()));
+
+        assert_eq!(arn, "arn:aws:lambda:eu-west-1:111122223333:function:sprint_bot");
+        assert_eq!(lambda_target_id(&arn).unwrap(), "sprint_bot");
+    }
+
+    #[test]
+    fn test_lambda_target_falls_back_to_default_arn() {
+        assert_eq!(lambda_target_arn(None), DEFAULT_LAMBDA_ARN);
+        assert_eq!(lambda_target_arn(Some(String::new())), DEFAULT_LAMBDA_ARN);
+        assert_eq!(lambda_target_id(DEFAULT_LAMBDA_ARN).unwrap(), "daily_summary_bot");
+        assert!(lambda_target_id("daily_summary_bot").is_err());
+    }
+}
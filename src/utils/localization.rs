@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::from_value;
+use crate::utils::s3::JsonStorageClient;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Label {
+    OpenPrsHeader,
+    BlockedPrsHeader,
+    DemoesHeader,
+    OpenTicketsHeader,
+    CompletedTicketsHeader,
+    DeferredTicketsHeader,
+    Unassigned,
+    PreviousSprintsHeader,
+    ProjectScope,
+    ViewSprintBoard,
+    PreviewHeader,
+    PlannedTicketsAndDays,
+    CarriedOverTickets,
+    OneTicketCarriedOver,
+    NoTicketsCarriedOver,
+    OverCapacity,
+    KickOffButton,
+    KickoffHeader,
+    SprintStartsNow,
+    CheckInHeader,
+    TicketsOpen,
+    ScopeCompleted,
+    CancelledHeader,
+    TicketsCompleted,
+    ProgressNotSaved,
+    ReviewHeader,
+    EndedEarlyHeader,
+    ReviewPreviewHeader,
+    ReviewPreviewNotSaved,
+    StatusReadOnly,
+    TierLeagueEntrants,
+    TierLocalCompetitors,
+    TierPlayoffContenders,
+    TierDivisionLeaders,
+    TierConferenceFinalists,
+    TierWorldSeriesElites,
+    TierSprintChampions,
+    TierDisqualified,
+    SummaryInProgress,
+    TicketsAddedTo,
+    TicketsRemovedFrom,
+    NoTicketsAddedTo,
+    ThisSprint,
+    ProjectScopeChange,
+    DailySummaryHeader,
+    ScopeChangeAlert,
+    FallbackSummary,
+    PrDetailsSkipped,
+    PrDetailsUnavailable,
+    TicketDeferred,
+    MemberMapped,
+    HygieneHeader,
+    HygieneClean,
+    MissingDescriptionWarning,
+    MissingLabelsWarning,
+    MissingAssigneesWarning,
+    MissingPrWarning,
+    UnmergedPrWarning,
+    SlaExceededWarning,
+    PartOf,
+    EndsToday,
+    EndsTomorrow,
+    EndsInDays,
+    EndedYesterday,
+    EndedDaysAgo,
+    TicketLegend,
+    VelocityTrend,
+    VelocityTrendFromZero,
+    VelocityHeader,
+    VelocityAverage,
+    VelocityLimitedHistory,
+    NoVelocityHistory,
+    BackfillHeader,
+    BackfillFinished,
+    SprintGoal,
+    GoalAchievedPrompt,
+    CancelledSprintRecorded,
+    CancelledSprintTag,
+    CompletionChartTitle,
+    CompletionChartAltText,
+    InvestigationsHeader,
+    AssignedUnstartedHeader,
+    SprintExtended,
+    BlockedPrsAlertHeader,
+    ConfigHeader,
+    ConfigTimezone,
+    ConfigDailySummaryTime,
+    ConfigSprintReviewTime,
+    ConfigTicketSource,
+    ConfigTrelloSprintField,
+    ConfigTrelloCredentials,
+    ConfigStorage,
+    ConfigPrDetails,
+    ConfigLocale,
+    ConfigHeaderDateStyle,
+    ConfigSprintNameMatching,
+    ConfigSummarySectionOrder,
+    ConfigTimeBudget,
+    ConfigTriggerDedupeWindow,
+    ConfigVelocityWindow,
+    ConfigNewTicketWindow,
+    ConfigSummaryThrottle,
+    ConfigPreviewCache,
+    ConfigSummaryCanvas,
+    ConfigKickoffPreviewArchive,
+    ConfigQuietHours,
+    ConfigScopeChangeAlert,
+    ConfigNearlyDoneChecklist,
+    ConfigLabelSlas,
+    ConfigCheckMaxAge,
+    ConfigFailingChecksShown,
+    ConfigRequiredCheckRuns,
+    ConfigBlockedPrsChannel,
+    ConfigSummaryIntro,
+    ConfigAllowedSlackApps,
+    ConfigHolidays,
+    ConfigEnabledOptions,
+    CanvasTitle,
+    GoalsHeader,
+    PreviewArchived,
+    PointsCompleted,
+}
+
+impl Label {
+    fn english(&self) -> &'static str {
+        match self {
+            Label::OpenPrsHeader => "📢 Open PRs",
+            Label::BlockedPrsHeader => "🚨 Blocked PRs",
+            Label::DemoesHeader => "🎥 Demo Available",
+            Label::OpenTicketsHeader => "Open Tickets",
+            Label::CompletedTicketsHeader => "✅ Completed Tickets",
+            Label::DeferredTicketsHeader => "Deferred Tickets",
+            Label::Unassigned => "Unassigned",
+            Label::PreviousSprintsHeader => "Previous Sprints:",
+            Label::ProjectScope => "{0} tickets left in project scope.",
+            Label::ViewSprintBoard => "View sprint board",
+            Label::PreviewHeader => "🔭 Sprint {0} Preview: {1} - {2}",
+            Label::PlannedTicketsAndDays => "*{0} Tickets*\n*{1} Days*",
+            Label::CarriedOverTickets => "{0} tickets will be carried over from last sprint.",
+            Label::OneTicketCarriedOver => "1 ticket will be carried over from last sprint.",
+            Label::NoTicketsCarriedOver => "No tickets will be carried over from last sprint.",
+            Label::OverCapacity => "⚠️ *Over capacity:* {0} tickets planned for a capacity of {1}.",
+            Label::KickOffButton => "Kick Off",
+            Label::KickoffHeader => "🚀 Sprint {0} Kickoff: {1} - {2}",
+            Label::SprintStartsNow => "Sprint starts now!",
+            Label::CheckInHeader => "🛰️ Sprint {0} Check-In: {1}",
+            Label::TicketsOpen => "*{0} tickets open* out of {1}.\n*{2} working days* remain in sprint ({3} calendar days).",
+            Label::ScopeCompleted => "*{0}% of sprint scope completed.*",
+            Label::CancelledHeader => "🔴 Sprint {0} is cancelled.",
+            Label::TicketsCompleted => "*{0}/{1} tickets completed in {2} days.*",
+            Label::ProgressNotSaved => "Progress will not be saved.",
+            Label::ReviewHeader => "🎆 Sprint {0} Review: {1} - {2}",
+            Label::EndedEarlyHeader => "💥 Sprint {0} ended early.",
+            Label::ReviewPreviewHeader => "👀 Sprint {0} Review Preview: {1} - {2}",
+            Label::ReviewPreviewNotSaved => "This is a preview of the sprint review. Nothing has been saved and the sprint is still active.",
+            Label::StatusReadOnly => "🔍 Sprint status (read-only). Nothing has been saved.",
+            Label::TierLeagueEntrants => "🐢 League Entrants",
+            Label::TierLocalCompetitors => "🥉 Local Competitors",
+            Label::TierPlayoffContenders => "🥈 Playoff Contenders",
+            Label::TierDivisionLeaders => "🥇 Division Leaders",
+            Label::TierConferenceFinalists => "🏅 Conference Finalists",
+            Label::TierWorldSeriesElites => "🎖️ World Series Elites",
+            Label::TierSprintChampions => "🏆 Sprint Champions!",
+            Label::TierDisqualified => "⁉️ Disqualified?",
+            Label::SummaryInProgress => "⏳ A summary is already in progress, try again once it's posted.",
+            Label::TicketsAddedTo => "{0} tickets added to",
+            Label::TicketsRemovedFrom => "{0} tickets removed from",
+            Label::NoTicketsAddedTo => "No tickets added to",
+            Label::ThisSprint => "{0} this sprint.",
+            Label::ProjectScopeChange => "{0} project scope.",
+            Label::DailySummaryHeader => "{0} Daily Summary: {1}",
+            Label::ScopeChangeAlert => "🚨 Sprint scope has grown {0}% since kickoff ({1} → {2} tickets).",
+            Label::FallbackSummary => "The full sprint summary couldn't be displayed. {0} tickets open out of {1}, {2}% of sprint scope completed. {3}",
+            Label::PrDetailsSkipped => "⏱️ PR details were skipped to post this summary in time.",
+            Label::PrDetailsUnavailable => "⚠️ Some PR details couldn't be fetched from GitHub, those tickets link their PR without its status.",
+            Label::TicketDeferred => "Ticket {0} will be treated as deferred from Sprint {1}.",
+            Label::MemberMapped => "Trello member {0} is now mapped to <@{1}>.",
+            Label::HygieneHeader => "🧹 Board Hygiene: {0}",
+            Label::HygieneClean => "✨ Every ticket has the information it needs.",
+            Label::MissingDescriptionWarning => "Missing Description",
+            Label::MissingLabelsWarning => "Missing Labels",
+            Label::MissingAssigneesWarning => "Missing Assignees",
+            Label::MissingPrWarning => "Missing PR",
+            Label::UnmergedPrWarning => "PR not merged",
+            Label::SlaExceededWarning => "⏱ SLA exceeded",
+            Label::PartOf => "Part of",
+            Label::EndsToday => "ends today",
+            Label::EndsTomorrow => "ends tomorrow",
+            Label::EndsInDays => "ends in {0} days",
+            Label::EndedYesterday => "ended yesterday",
+            Label::EndedDaysAgo => "ended {0} days ago",
+            Label::BackfillHeader => "♻️ Sprint History Backfill",
+            Label::BackfillFinished => "Recomputed completion for {0} of {1} past sprints from their archives. Sprints without an archive keep the percentage recorded when they ended.",
+            Label::SprintGoal => "🎯 *Sprint goal:* {0}",
+            Label::GoalAchievedPrompt => "Was the goal achieved?",
+            Label::CancelledSprintRecorded => "This sprint will be recorded in the sprint history as cancelled.",
+            Label::CancelledSprintTag => "_cancelled_",
+            Label::CompletionChartTitle => "Completion by sprint",
+            Label::CompletionChartAltText => "Sprint {0} is {1}% complete",
+            Label::InvestigationsHeader => "Investigations",
+            Label::AssignedUnstartedHeader => "Assigned, Not Started",
+            Label::SprintExtended => "📅 Sprint {0} now ends {2} instead of {1}, with {3} days remaining.",
+            Label::BlockedPrsAlertHeader => "🚨 Blocked PRs in {0}",
+            Label::ConfigHeader => "⚙️ Bot Configuration",
+            Label::ConfigTimezone => "Timezone",
+            Label::ConfigDailySummaryTime => "Daily summary time",
+            Label::ConfigSprintReviewTime => "Sprint review time",
+            Label::ConfigTicketSource => "Ticket source",
+            Label::ConfigTrelloSprintField => "Trello sprint field",
+            Label::ConfigTrelloCredentials => "Trello credentials",
+            Label::ConfigStorage => "Storage",
+            Label::ConfigPrDetails => "PR details",
+            Label::ConfigLocale => "Locale",
+            Label::ConfigHeaderDateStyle => "Header date style",
+            Label::ConfigSprintNameMatching => "Sprint name matching",
+            Label::ConfigSummarySectionOrder => "Summary section order",
+            Label::ConfigTimeBudget => "Time budget",
+            Label::ConfigTriggerDedupeWindow => "Trigger dedupe window",
+            Label::ConfigVelocityWindow => "Velocity window",
+            Label::ConfigNewTicketWindow => "New ticket window",
+            Label::ConfigSummaryThrottle => "Summary throttle",
+            Label::ConfigPreviewCache => "Preview cache",
+            Label::ConfigSummaryCanvas => "Summary canvas",
+            Label::ConfigKickoffPreviewArchive => "Kickoff preview archive",
+            Label::ConfigQuietHours => "Quiet hours",
+            Label::ConfigScopeChangeAlert => "Scope change alert",
+            Label::ConfigNearlyDoneChecklist => "Nearly done checklist",
+            Label::ConfigLabelSlas => "Label SLAs",
+            Label::ConfigCheckMaxAge => "Check max age",
+            Label::ConfigFailingChecksShown => "Failing checks shown",
+            Label::ConfigRequiredCheckRuns => "Required check runs",
+            Label::ConfigBlockedPrsChannel => "Blocked PRs channel",
+            Label::ConfigSummaryIntro => "Summary intro",
+            Label::ConfigAllowedSlackApps => "Allowed Slack apps",
+            Label::ConfigHolidays => "Holidays",
+            Label::ConfigEnabledOptions => "Enabled options",
+            Label::CanvasTitle => "Sprint {0} Summary",
+            Label::GoalsHeader => "*Goals:*",
+            Label::TicketLegend => "🆕 added in the last {0} days · 🔁 reopened · 🐌 carried over, one per previous sprint · 🏁 sprint goal",
+            Label::VelocityTrend => "{0} {1} completed (avg {2} over last {3}, {4}%)",
+            Label::VelocityTrendFromZero => "📈 {0} completed (avg 0 over last {1})",
+            Label::VelocityHeader => "📊 Sprint Velocity",
+            Label::VelocityAverage => "*{0} tickets completed* per sprint on average over the last {1} sprints.",
+            Label::VelocityLimitedHistory => "Only {1} of the {0} requested sprints are recorded.",
+            Label::NoVelocityHistory => "No finished sprints are recorded yet.",
+            Label::PointsCompleted => "*{0}/{1} points completed.*",
+            Label::PreviewArchived => "✅ This sprint has been kicked off, this preview is no longer current.",
+        }
+    }
+}
+
+//Label overrides for a locale, falling back to English for anything not overridden
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct Localization {
+    labels: HashMap<Label, String>,
+}
+
+impl Localization {
+    pub fn new(labels: HashMap<Label, String>) -> Self {
+        Localization { labels }
+    }
+
+    pub fn text(&self, label: Label) -> String {
+        self.labels.get(&label)
+            .cloned()
+            .unwrap_or_else(|| label.english().to_string())
+    }
+
+    pub fn format(&self, label: Label, args: &[&dyn Display]) -> String {
+        args.iter()
+            .enumerate()
+            .fold(self.text(label), |text, (index, arg)| text.replace(&format!("{{{}}}", index), &arg.to_string()))
+    }
+}
+
+#[async_trait(?Send)]
+pub trait LocalizationClient {
+    async fn get_localization(&self, locale: &str) -> Result<Option<Localization>>;
+}
+
+#[async_trait(?Send)]
+impl<T> LocalizationClient for T where T: JsonStorageClient, {
+    async fn get_localization(&self, locale: &str) -> Result<Option<Localization>> {
+        self.get_json(&format!("locales/{}.json", locale)).await?
+            .map(|json_value| {
+                from_value::<HashMap<Label, String>>(json_value)
+                    .map(Localization::new)
+                    .context("Failed to deserialize localization data")
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_defaults_to_english() {
+        let localization = Localization::default();
+        assert_eq!(localization.text(Label::OpenTicketsHeader), "Open Tickets");
+    }
+
+    #[test]
+    fn test_text_uses_override() {
+        let localization = Localization::new(HashMap::from([
+            (Label::OpenTicketsHeader, "Tickets abiertos".to_string()),
+        ]));
+        assert_eq!(localization.text(Label::OpenTicketsHeader), "Tickets abiertos");
+        assert_eq!(localization.text(Label::DeferredTicketsHeader), "Deferred Tickets");
+    }
+
+    #[test]
+    fn test_format_replaces_positional_arguments() {
+        let localization = Localization::new(HashMap::from([
+            (Label::TicketsOpen, "*{0}* de {1} tickets abiertos.\n*{2} días* restantes.".to_string()),
+        ]));
+        assert_eq!(localization.format(Label::TicketsOpen, &[&3, &10, &5]), "*3* de 10 tickets abiertos.\n*5 días* restantes.");
+    }
+
+    #[test]
+    fn test_deserialize_label_overrides() {
+        let labels: HashMap<Label, String> = serde_json::from_str(r#"{"open_prs_header": "📢 PRs abiertos"}"#).unwrap();
+        assert_eq!(Localization::new(labels).text(Label::OpenPrsHeader), "📢 PRs abiertos");
+    }
+}
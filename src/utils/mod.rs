@@ -1,6 +1,15 @@
+pub mod config;
 pub mod date;
+pub mod dynamodb;
+pub mod slack_canvas;
 pub mod slack_components;
 pub mod s3;
+pub mod secrets;
 pub mod eventbridge;
 pub mod http;
-pub mod slack_output;
\ No newline at end of file
+pub mod localization;
+pub mod quiet_hours;
+pub mod retry;
+pub mod slack_output;
+pub mod slack_users;
+pub mod time_budget;
\ No newline at end of file
@@ -0,0 +1,135 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use regex::{Captures, Regex};
+use serde_json::Value;
+use crate::utils::slack_components::text_element;
+
+//hours are in the sprint timezone, and a window like 22-7 wraps past midnight
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuietHours {
+    start_hour: u32,
+    end_hour: u32,
+}
+
+impl QuietHours {
+    pub fn parse(window: &str) -> Option<Self> {
+        let (start_hour, end_hour) = window.split_once('-')?;
+        let start_hour = start_hour.trim().parse::<u32>().ok().filter(|hour| *hour < 24)?;
+        let end_hour = end_hour.trim().parse::<u32>().ok().filter(|hour| *hour < 24)?;
+
+        Some(QuietHours { start_hour, end_hour })
+    }
+
+    pub fn contains(&self, hour: u32) -> bool {
+        if self.start_hour <= self.end_hour {
+            (self.start_hour..self.end_hour).contains(&hour)
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+
+    pub fn apply(&self, hour: u32, blocks: Vec<Value>, display_names: &BTreeMap<String, String>) -> Vec<Value> {
+        if self.contains(hour) {
+            let mention_pattern = Regex::new(r"<@(U\w+)(?:\|[^>]*)?>").unwrap();
+            blocks.into_iter().map(|block| without_mentions(block, display_names, &mention_pattern)).collect()
+        } else {
+            blocks
+        }
+    }
+}
+
+impl fmt::Display for QuietHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start_hour, self.end_hour)
+    }
+}
+
+fn display_name<'a>(user_id: &'a str, display_names: &'a BTreeMap<String, String>) -> &'a str {
+    display_names.get(user_id).map(String::as_str).unwrap_or(user_id)
+}
+
+//swaps user mentions for plain names so the message doesn't ping anyone, both user elements and <@U123> in mrkdwn text
+fn without_mentions(block: Value, display_names: &BTreeMap<String, String>, mention_pattern: &Regex) -> Value {
+    match block {
+        Value::Object(object) if object.get("type").and_then(Value::as_str) == Some("user") => {
+            let user_id = object.get("user_id").and_then(Value::as_str).unwrap_or_default();
+            text_element(&format!("@{}", display_name(user_id, display_names)), None)
+        },
+        Value::Object(mut object) if object.get("type").and_then(Value::as_str) == Some("mrkdwn") => {
+            if let Some(Value::String(text)) = object.get_mut("text") {
+                *text = mention_pattern.replace_all(text, |captures: &Captures| format!("@{}", display_name(&captures[1], display_names))).into_owned();
+            }
+            Value::Object(object)
+        },
+        Value::Object(object) => Value::Object(object.into_iter().map(|(key, value)| (key, without_mentions(value, display_names, mention_pattern))).collect()),
+        Value::Array(values) => Value::Array(values.into_iter().map(|value| without_mentions(value, display_names, mention_pattern)).collect()),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use crate::utils::slack_components::{section_block, user_element};
+    use super::*;
+
+    fn ticket_blocks() -> Vec<Value> {
+        vec![json!({
+            "type": "rich_text",
+            "elements": [{
+                "type": "rich_text_section",
+                "elements": [text_element("Ticket", None), user_element("U123"), user_element("U456")]
+            }]
+        })]
+    }
+
+    #[test]
+    fn test_parse_quiet_hours() {
+        assert_eq!(QuietHours::parse("22-7"), Some(QuietHours { start_hour: 22, end_hour: 7 }));
+        assert_eq!(QuietHours::parse("12-13"), Some(QuietHours { start_hour: 12, end_hour: 13 }));
+        assert_eq!(QuietHours::parse("25-7"), None);
+        assert_eq!(QuietHours::parse("late"), None);
+    }
+
+    #[test]
+    fn test_quiet_hours_wrap_past_midnight() {
+        let quiet_hours = QuietHours::parse("22-7").unwrap();
+
+        assert!(quiet_hours.contains(23));
+        assert!(quiet_hours.contains(3));
+        assert!(!quiet_hours.contains(7));
+        assert!(!quiet_hours.contains(12));
+    }
+
+    #[test]
+    fn test_command_during_quiet_hours_suppresses_mentions() {
+        let quiet_hours = QuietHours::parse("22-7").unwrap();
+        let display_names = BTreeMap::from([("U123".to_string(), "Jay".to_string())]);
+
+        let blocks = quiet_hours.apply(23, ticket_blocks(), &display_names);
+
+        let elements = &blocks[0]["elements"][0]["elements"];
+        assert_eq!(elements[1], text_element("@Jay", None));
+        assert_eq!(elements[2], text_element("@U456", None));
+        assert!(!blocks[0].to_string().contains("\"type\":\"user\""));
+    }
+
+    #[test]
+    fn test_quiet_hours_suppresses_mentions_in_mrkdwn_text() {
+        let quiet_hours = QuietHours::parse("22-7").unwrap();
+        let display_names = BTreeMap::from([("U123".to_string(), "Jay".to_string())]);
+
+        let blocks = quiet_hours.apply(23, vec![section_block("<@U123> and <@U456> finished <https://example.com|the ticket>")], &display_names);
+
+        assert_eq!(blocks, vec![section_block("@Jay and @U456 finished <https://example.com|the ticket>")]);
+    }
+
+    #[test]
+    fn test_command_outside_quiet_hours_mentions_normally() {
+        let quiet_hours = QuietHours::parse("22-7").unwrap();
+
+        let blocks = quiet_hours.apply(10, ticket_blocks(), &BTreeMap::new());
+
+        assert_eq!(blocks, ticket_blocks());
+    }
+}
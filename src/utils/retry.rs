@@ -0,0 +1,155 @@
+use std::future::Future;
+use std::time::Duration;
+use reqwest::{header::RETRY_AFTER, Response, StatusCode};
+use crate::tracing::warn;
+
+pub trait RetryableResponse {
+    fn status(&self) -> StatusCode;
+    fn retry_after(&self) -> Option<Duration>;
+}
+
+impl RetryableResponse for Response {
+    fn status(&self) -> StatusCode {
+        self.status()
+    }
+
+    //only the delay-seconds form is read, an http date falls back to the backoff
+    fn retry_after(&self) -> Option<Duration> {
+        self.headers().get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs)
+    }
+}
+
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy { max_attempts: 3, base_delay: Duration::from_millis(500), max_delay: Duration::from_secs(5) }
+    }
+}
+
+impl RetryPolicy {
+    //rate limits and server errors are worth another try, any other response is returned as is
+    fn retry_delay(&self, status: StatusCode, retry_after: Option<Duration>, attempt: u32) -> Option<Duration> {
+        if attempt >= self.max_attempts || !(status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()) {
+            return None;
+        }
+
+        Some(retry_after.unwrap_or(self.base_delay * 2u32.pow(attempt - 1)).min(self.max_delay))
+    }
+}
+
+pub async fn send_with_retry<R, E, F, Fut>(policy: &RetryPolicy, mut send: F) -> Result<R, E>
+where
+    R: RetryableResponse,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<R, E>>,
+{
+    let mut attempt = 1;
+
+    loop {
+        let response = send().await?;
+
+        match policy.retry_delay(response.status(), response.retry_after(), attempt) {
+            Some(delay) => {
+                warn!("Request failed with {} on attempt {}, retrying in {:?}", response.status(), attempt, delay);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            },
+            None => return Ok(response),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+    use std::collections::VecDeque;
+    use anyhow::{Result, anyhow};
+    use super::*;
+
+    struct MockResponse {
+        status: StatusCode,
+        retry_after: Option<Duration>,
+    }
+
+    impl RetryableResponse for MockResponse {
+        fn status(&self) -> StatusCode {
+            self.status
+        }
+
+        fn retry_after(&self) -> Option<Duration> {
+            self.retry_after
+        }
+    }
+
+    struct MockHttp {
+        responses: RefCell<VecDeque<MockResponse>>,
+        calls: Cell<u32>,
+    }
+
+    impl MockHttp {
+        fn new(statuses: &[StatusCode]) -> Self {
+            MockHttp {
+                responses: RefCell::new(statuses.iter().map(|status| MockResponse { status: *status, retry_after: None }).collect()),
+                calls: Cell::new(0),
+            }
+        }
+
+        async fn send(&self) -> Result<MockResponse> {
+            self.calls.set(self.calls.get() + 1);
+            self.responses.borrow_mut().pop_front().ok_or(anyhow!("No more responses"))
+        }
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy { max_attempts: 3, base_delay: Duration::from_millis(1), max_delay: Duration::from_millis(10) }
+    }
+
+    #[tokio::test]
+    async fn test_retries_on_503_then_succeeds() {
+        let http = MockHttp::new(&[StatusCode::SERVICE_UNAVAILABLE, StatusCode::OK]);
+
+        let response = send_with_retry(&fast_policy(), || http.send()).await.unwrap();
+
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(http.calls.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_on_404() {
+        let http = MockHttp::new(&[StatusCode::NOT_FOUND, StatusCode::OK]);
+
+        let response = send_with_retry(&fast_policy(), || http.send()).await.unwrap();
+
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+        assert_eq!(http.calls.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let http = MockHttp::new(&[StatusCode::BAD_GATEWAY, StatusCode::TOO_MANY_REQUESTS, StatusCode::BAD_GATEWAY, StatusCode::OK]);
+
+        let response = send_with_retry(&fast_policy(), || http.send()).await.unwrap();
+
+        assert_eq!(response.status, StatusCode::BAD_GATEWAY);
+        assert_eq!(http.calls.get(), 3);
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_and_honors_retry_after() {
+        let policy = RetryPolicy::default();
+
+        assert_eq!(policy.retry_delay(StatusCode::BAD_GATEWAY, None, 1), Some(Duration::from_millis(500)));
+        assert_eq!(policy.retry_delay(StatusCode::BAD_GATEWAY, None, 2), Some(Duration::from_secs(1)));
+        assert_eq!(policy.retry_delay(StatusCode::TOO_MANY_REQUESTS, Some(Duration::from_secs(2)), 1), Some(Duration::from_secs(2)));
+        assert_eq!(policy.retry_delay(StatusCode::TOO_MANY_REQUESTS, Some(Duration::from_secs(60)), 1), Some(Duration::from_secs(5)));
+        assert_eq!(policy.retry_delay(StatusCode::FORBIDDEN, None, 1), None);
+    }
+}
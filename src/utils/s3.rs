@@ -1,26 +1,118 @@
 use aws_config::meta::region::RegionProviderChain;
 use aws_sdk_s3::{Client, primitives::ByteStream};
+use aws_sdk_s3::operation::{delete_object::builders::DeleteObjectFluentBuilder, get_object::builders::GetObjectFluentBuilder, put_object::builders::PutObjectFluentBuilder};
 use lambda_runtime::tracing::error;
 use serde_json::Value;
 use anyhow::{Result, Context, anyhow};
+use crate::utils::config::Config;
+use crate::utils::dynamodb::{create_dynamo_json_storage_client, DynamoJsonStorageClient};
 
-pub async fn create_json_storage_client() -> Client {
-    let region_provider = RegionProviderChain::default_provider().or_else("us-west-2");
-    let config = aws_config::from_env().region(region_provider).load().await;
-    aws_sdk_s3::Client::new(&config)
+pub async fn create_json_storage_client(config: &Config) -> JsonStorage {
+    match &config.dynamodb_table_name {
+        Some(table_name) => JsonStorage::Dynamo(create_dynamo_json_storage_client(table_name.clone()).await),
+        None => {
+            let region_provider = RegionProviderChain::default_provider().or_else("us-west-2");
+            let aws_config = aws_config::from_env().region(region_provider).load().await;
+            JsonStorage::S3(S3JsonStorageClient::new(aws_sdk_s3::Client::new(&aws_config), config.summary_bucket.clone()))
+        },
+    }
 }
 
 pub trait JsonStorageClient {
     async fn get_json(&self, key: &str) -> Result<Option<Value>>;
     async fn put_json(&self, key: &str, json_value: &Value) -> Result<()>;
     async fn delete_json(&self, key: &str) -> Result<()>;
+    //only writes while the stored value is still expected, nothing stored when expected is None, and resolves to whether it wrote
+    async fn put_json_if(&self, key: &str, json_value: &Value, expected: Option<&Value>) -> Result<bool>;
+    //only deletes while the stored value is still expected, and resolves to whether it deleted
+    async fn delete_json_if(&self, key: &str, expected: &Value) -> Result<bool>;
+}
+
+//sprint state lives in the SUMMARY_BUCKET bucket unless DYNAMODB_TABLE_NAME is set
+pub enum JsonStorage {
+    S3(S3JsonStorageClient),
+    Dynamo(DynamoJsonStorageClient<aws_sdk_dynamodb::Client>),
+}
+
+impl JsonStorageClient for JsonStorage {
+    async fn get_json(&self, key: &str) -> Result<Option<Value>> {
+        match self {
+            JsonStorage::S3(client) => client.get_json(key).await,
+            JsonStorage::Dynamo(client) => client.get_json(key).await,
+        }
+    }
+
+    async fn put_json(&self, key: &str, json_value: &Value) -> Result<()> {
+        match self {
+            JsonStorage::S3(client) => client.put_json(key, json_value).await,
+            JsonStorage::Dynamo(client) => client.put_json(key, json_value).await,
+        }
+    }
+
+    async fn delete_json(&self, key: &str) -> Result<()> {
+        match self {
+            JsonStorage::S3(client) => client.delete_json(key).await,
+            JsonStorage::Dynamo(client) => client.delete_json(key).await,
+        }
+    }
+
+    async fn put_json_if(&self, key: &str, json_value: &Value, expected: Option<&Value>) -> Result<bool> {
+        match self {
+            JsonStorage::S3(client) => client.put_json_if(key, json_value, expected).await,
+            JsonStorage::Dynamo(client) => client.put_json_if(key, json_value, expected).await,
+        }
+    }
+
+    async fn delete_json_if(&self, key: &str, expected: &Value) -> Result<bool> {
+        match self {
+            JsonStorage::S3(client) => client.delete_json_if(key, expected).await,
+            JsonStorage::Dynamo(client) => client.delete_json_if(key, expected).await,
+        }
+    }
+}
+
+pub struct S3JsonStorageClient {
+    client: Client,
+    bucket: String,
 }
 
-impl JsonStorageClient for Client {
+impl S3JsonStorageClient {
+    pub fn new(client: Client, bucket: String) -> Self {
+        S3JsonStorageClient { client, bucket }
+    }
+
+    fn get_object_request(&self, key: &str) -> GetObjectFluentBuilder {
+        self.client.get_object().bucket(&self.bucket).key(key)
+    }
+
+    fn put_object_request(&self, key: &str) -> PutObjectFluentBuilder {
+        self.client.put_object().bucket(&self.bucket).key(key)
+    }
+
+    fn delete_object_request(&self, key: &str) -> DeleteObjectFluentBuilder {
+        self.client.delete_object().bucket(&self.bucket).key(key)
+    }
+
+    //S3 rejects the put if the object changed since e_tag was read, or already exists when there's no e_tag
+    fn conditional_put_object_request(&self, key: &str, e_tag: Option<&str>) -> PutObjectFluentBuilder {
+        match e_tag {
+            Some(e_tag) => self.put_object_request(key).if_match(e_tag),
+            None => self.put_object_request(key).if_none_match("*"),
+        }
+    }
+
+    async fn get_json_with_e_tag(&self, key: &str) -> Option<(Value, String)> {
+        let object = self.get_object_request(key).send().await.ok()?;
+        let e_tag = object.e_tag()?.to_string();
+        let data = object.body.collect().await.ok()?;
+
+        Some((serde_json::from_slice::<Value>(&data.into_bytes()).ok()?, e_tag))
+    }
+}
+
+impl JsonStorageClient for S3JsonStorageClient {
     async fn delete_json(&self, key: &str) -> Result<()> {
-        let resp = self.delete_object()
-            .bucket("agilesummary")
-            .key(key)
+        let resp = self.delete_object_request(key)
             .send()
             .await;
     
@@ -34,9 +126,7 @@ impl JsonStorageClient for Client {
         let json_data = serde_json::to_string(json_value)
             .context("Failed to serialize json data")?;
     
-        let resp = self.put_object()
-            .bucket("agilesummary")
-            .key(key)
+        let resp = self.put_object_request(key)
             .body(ByteStream::from(json_data.into_bytes()))
             .send()
             .await;
@@ -47,10 +137,51 @@ impl JsonStorageClient for Client {
         }
     }
 
+    async fn put_json_if(&self, key: &str, json_value: &Value, expected: Option<&Value>) -> Result<bool> {
+        let json_data = serde_json::to_string(json_value)
+            .context("Failed to serialize json data")?;
+
+        let e_tag = match expected {
+            Some(expected) => match self.get_json_with_e_tag(key).await {
+                Some((current, e_tag)) if &current == expected => Some(e_tag),
+                _ => return Ok(false),
+            },
+            None => None,
+        };
+
+        let resp = self.conditional_put_object_request(key, e_tag.as_deref())
+            .body(ByteStream::from(json_data.into_bytes()))
+            .send()
+            .await;
+
+        match resp {
+            Ok(_) => Ok(true),
+            //412 when the condition failed, 409 when another conditional write to the key was in flight
+            Err(e) if e.raw_response().is_some_and(|response| matches!(response.status().as_u16(), 409 | 412)) => Ok(false),
+            Err(e) => Err(anyhow!("Failed to insert json: {}", e)),
+        }
+    }
+
+    async fn delete_json_if(&self, key: &str, expected: &Value) -> Result<bool> {
+        let e_tag = match self.get_json_with_e_tag(key).await {
+            Some((current, e_tag)) if &current == expected => e_tag,
+            _ => return Ok(false),
+        };
+
+        let resp = self.delete_object_request(key)
+            .if_match(e_tag)
+            .send()
+            .await;
+
+        match resp {
+            Ok(_) => Ok(true),
+            Err(e) if e.raw_response().is_some_and(|response| matches!(response.status().as_u16(), 409 | 412)) => Ok(false),
+            Err(e) => Err(anyhow!("Failed to delete json: {}", e)),
+        }
+    }
+
     async fn get_json(&self, key: &str) -> Result<Option<Value>> {
-        let object = match self.get_object()
-            .bucket("agilesummary")
-            .key(key)
+        let object = match self.get_object_request(key)
             .send()
             .await {
             Ok(data) => data,
@@ -76,4 +207,91 @@ impl JsonStorageClient for Client {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+pub mod mocks {
+    use std::{cell::RefCell, collections::HashMap};
+    use anyhow::Result;
+    use serde_json::Value;
+    use super::JsonStorageClient;
+
+    //keeps put values so storage round trips can be tested without S3
+    #[derive(Default)]
+    pub struct InMemoryJsonStorage {
+        objects: RefCell<HashMap<String, Value>>,
+    }
+
+    impl JsonStorageClient for InMemoryJsonStorage {
+        async fn get_json(&self, key: &str) -> Result<Option<Value>> {
+            Ok(self.objects.borrow().get(key).cloned())
+        }
+
+        async fn put_json(&self, key: &str, json_value: &Value) -> Result<()> {
+            self.objects.borrow_mut().insert(key.to_string(), json_value.clone());
+            Ok(())
+        }
+
+        async fn delete_json(&self, key: &str) -> Result<()> {
+            self.objects.borrow_mut().remove(key);
+            Ok(())
+        }
+
+        async fn put_json_if(&self, key: &str, json_value: &Value, expected: Option<&Value>) -> Result<bool> {
+            let mut objects = self.objects.borrow_mut();
+            if objects.get(key) != expected {
+                return Ok(false);
+            }
+
+            objects.insert(key.to_string(), json_value.clone());
+            Ok(true)
+        }
+
+        async fn delete_json_if(&self, key: &str, expected: &Value) -> Result<bool> {
+            let mut objects = self.objects.borrow_mut();
+            if objects.get(key) != Some(expected) {
+                return Ok(false);
+            }
+
+            objects.remove(key);
+            Ok(true)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_s3::config::{BehaviorVersion, Region};
+    use super::*;
+
+    #[test]
+    fn test_requests_use_configured_bucket() {
+        let client = Client::from_conf(aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-west-2"))
+            .build());
+        let storage_client = S3JsonStorageClient::new(client, "team-summary".to_string());
+
+        assert_eq!(storage_client.get_object_request("sprint_data.json").get_bucket().as_deref(), Some("team-summary"));
+        assert_eq!(storage_client.put_object_request("sprint_data.json").get_bucket().as_deref(), Some("team-summary"));
+        assert_eq!(storage_client.delete_object_request("sprint_data.json").get_bucket().as_deref(), Some("team-summary"));
+        assert_eq!(storage_client.get_object_request("sprint_data.json").get_key().as_deref(), Some("sprint_data.json"));
+    }
+
+    #[test]
+    fn test_conditional_put_requests() {
+        let client = Client::from_conf(aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-west-2"))
+            .build());
+        let storage_client = S3JsonStorageClient::new(client, "team-summary".to_string());
+
+        let create_request = storage_client.conditional_put_object_request("processing_lock.json", None);
+        assert_eq!(create_request.get_if_none_match().as_deref(), Some("*"));
+        assert_eq!(create_request.get_if_match().as_deref(), None);
+
+        let replace_request = storage_client.conditional_put_object_request("processing_lock.json", Some("\"abc123\""));
+        assert_eq!(replace_request.get_if_match().as_deref(), Some("\"abc123\""));
+        assert_eq!(replace_request.get_if_none_match().as_deref(), None);
+    }
+}
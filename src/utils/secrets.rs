@@ -0,0 +1,30 @@
+use aws_config::meta::region::RegionProviderChain;
+use aws_sdk_secretsmanager::Client;
+use anyhow::{Result, anyhow};
+use crate::tracing::info;
+
+pub async fn create_secrets_client() -> Client {
+    let region_provider = RegionProviderChain::default_provider().or_else("us-west-2");
+    let config = aws_config::from_env().region(region_provider).load().await;
+    aws_sdk_secretsmanager::Client::new(&config)
+}
+
+pub trait SecretsClient {
+    async fn get_secret_string(&self, secret_id: &str) -> Result<String>;
+}
+
+impl SecretsClient for Client {
+    async fn get_secret_string(&self, secret_id: &str) -> Result<String> {
+        info!("Fetching secret: {}", secret_id);
+
+        let response = self.get_secret_value()
+            .secret_id(secret_id)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to fetch secret {}: {}", secret_id, e))?;
+
+        response.secret_string()
+            .map(str::to_string)
+            .ok_or(anyhow!("Secret {} has no string value", secret_id))
+    }
+}
@@ -0,0 +1,174 @@
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use anyhow::{Result, anyhow};
+use crate::tracing::info;
+use crate::utils::slack_output::{SlackApiError, SlackClient};
+
+#[derive(Deserialize)]
+struct CanvasResponse {
+    ok: bool,
+    error: Option<String>,
+    canvas_id: Option<String>,
+}
+
+impl CanvasResponse {
+    fn into_result(self) -> Result<Option<String>> {
+        if self.ok {
+            Ok(self.canvas_id)
+        } else {
+            Err(SlackApiError { error: self.error.unwrap_or_else(|| "Unknown error".to_string()) }.into())
+        }
+    }
+}
+
+fn canvas_create_request(channel_id: &str, title: &str, markdown: &str) -> Value {
+    json!({
+        "channel_id": channel_id,
+        "title": title,
+        "document_content": { "type": "markdown", "markdown": markdown }
+    })
+}
+
+//replacing without a section id swaps out the whole document, so the canvas only ever holds the latest summary
+fn canvas_edit_request(canvas_id: &str, markdown: &str) -> Value {
+    json!({
+        "canvas_id": canvas_id,
+        "changes": [{
+            "operation": "replace",
+            "document_content": { "type": "markdown", "markdown": markdown }
+        }]
+    })
+}
+
+pub trait CanvasClient {
+    async fn create_canvas(&self, channel_id: &str, title: &str, markdown: &str) -> Result<String>;
+    async fn edit_canvas(&self, canvas_id: &str, markdown: &str) -> Result<()>;
+
+    async fn upsert_canvas(&self, canvas_id: Option<&str>, channel_id: &str, title: &str, markdown: &str) -> Result<String> {
+        match canvas_id {
+            Some(canvas_id) => {
+                self.edit_canvas(canvas_id, markdown).await?;
+                Ok(canvas_id.to_string())
+            },
+            None => self.create_canvas(channel_id, title, markdown).await,
+        }
+    }
+}
+
+impl SlackClient<'_> {
+    async fn post_canvas_request(&self, method: &str, request: Value) -> Result<Option<String>> {
+        info!("Canvas request to Slack {}: {}", method, request);
+
+        let response: CanvasResponse = self.client.post(format!("https://slack.com/api/{}", method))
+            .bearer_auth(self.oauth_token)
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        response.into_result()
+    }
+}
+
+impl CanvasClient for SlackClient<'_> {
+    async fn create_canvas(&self, channel_id: &str, title: &str, markdown: &str) -> Result<String> {
+        self.post_canvas_request("canvases.create", canvas_create_request(channel_id, title, markdown)).await?
+            .ok_or(anyhow!("Slack canvases.create response is missing the canvas id"))
+    }
+
+    async fn edit_canvas(&self, canvas_id: &str, markdown: &str) -> Result<()> {
+        self.post_canvas_request("canvases.edit", canvas_edit_request(canvas_id, markdown)).await?;
+        Ok(())
+    }
+}
+
+//canvases take markdown rather than blocks, so mrkdwn links and mentions are rewritten along the way
+fn mrkdwn_to_markdown(text: &str) -> String {
+    let mention = Regex::new(r"<@([A-Z0-9]+)>").unwrap();
+    let link = Regex::new(r"<([^<>|]+)\|([^<>]+)>").unwrap();
+
+    link.replace_all(&mention.replace_all(text, "![](@$1)"), "[$2]($1)").to_string()
+}
+
+fn rich_text_markdown(elements: &Value) -> String {
+    elements.as_array().into_iter().flatten().map(|element| match element["type"].as_str() {
+        Some("text") => element["text"].as_str().unwrap_or_default().to_string(),
+        Some("link") => format!("[{}]({})", element["text"].as_str().unwrap_or_default(), element["url"].as_str().unwrap_or_default()),
+        Some("user") => format!("![](@{})", element["user_id"].as_str().unwrap_or_default()),
+        Some("rich_text_list") => element["elements"].as_array().into_iter().flatten()
+            .map(|item| format!("- {}", rich_text_markdown(&item["elements"]).trim()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => rich_text_markdown(&element["elements"]),
+    }).collect()
+}
+
+pub fn blocks_to_markdown(blocks: &[Value]) -> String {
+    blocks.iter().filter_map(|block| match block["type"].as_str()? {
+        "header" => Some(format!("# {}", block["text"]["text"].as_str()?)),
+        "section" => Some(mrkdwn_to_markdown(block["text"]["text"].as_str()?)),
+        "context" => Some(block["elements"].as_array()?.iter()
+            .filter_map(|element| element["text"].as_str())
+            .map(mrkdwn_to_markdown)
+            .collect::<Vec<_>>()
+            .join(" ")),
+        "rich_text" => Some(rich_text_markdown(&block["elements"])),
+        "image" => Some(format!("![{}]({})", block["alt_text"].as_str().unwrap_or_default(), block["image_url"].as_str()?)),
+        "divider" => Some("---".to_string()),
+        _ => None,
+    }).collect::<Vec<_>>().join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::slack_components::{context_block, header_block, link_element, list_block, section_block, text_element, user_element};
+    use super::*;
+
+    #[test]
+    fn test_canvas_edit_request_replaces_document() {
+        assert_eq!(canvas_edit_request("F123", "# Sprint 1"), json!({
+            "canvas_id": "F123",
+            "changes": [{
+                "operation": "replace",
+                "document_content": { "type": "markdown", "markdown": "# Sprint 1" }
+            }]
+        }));
+    }
+
+    #[test]
+    fn test_canvas_create_request_is_tabbed_in_channel() {
+        let request = canvas_create_request("C123", "Sprint 1 Summary", "# Sprint 1");
+
+        assert_eq!(request["channel_id"], "C123");
+        assert_eq!(request["title"], "Sprint 1 Summary");
+        assert_eq!(request["document_content"]["markdown"], "# Sprint 1");
+    }
+
+    #[test]
+    fn test_canvas_error_response() {
+        let response: CanvasResponse = serde_json::from_str(r#"{"ok": false, "error": "canvas_not_found"}"#).unwrap();
+        let error = response.into_result().unwrap_err();
+
+        assert_eq!(error.downcast_ref::<SlackApiError>(), Some(&SlackApiError { error: "canvas_not_found".to_string() }));
+    }
+
+    #[test]
+    fn test_blocks_to_markdown() {
+        let blocks = vec![
+            header_block("Sprint 1 Daily Summary: 05/01/24"),
+            section_block("*3 tickets open* out of 10."),
+            list_block(vec![json!([link_element("https://trello.com/c/1", "Ticket", None), text_element(" | In Progress", None), user_element("U123")])]),
+            context_block("<https://trello.com/b/board|View Sprint Board> <@U456>"),
+        ];
+
+        assert_eq!(blocks_to_markdown(&blocks), [
+            "# Sprint 1 Daily Summary: 05/01/24",
+            "*3 tickets open* out of 10.",
+            "- [Ticket](https://trello.com/c/1) | In Progress![](@U123)",
+            "[View Sprint Board](https://trello.com/b/board) ![](@U456)",
+        ].join("\n\n"));
+    }
+}
@@ -40,6 +40,25 @@ pub fn context_block(string: &str) -> Value {
     )
 }
 
+pub fn image_block(image_url: &str, alt_text: &str, title: Option<&str>) -> Value {
+    let mut block = json!(
+        {
+            "type": "image",
+            "image_url": image_url,
+            "alt_text": alt_text
+        }
+    );
+    if let Some(title) = title {
+        block["title"] = json!({
+            "type": "plain_text",
+            "text": title,
+            "emoji": true
+        });
+    }
+
+    block
+}
+
 pub fn divider_block() -> Value {
     json!(
         {
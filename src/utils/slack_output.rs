@@ -1,7 +1,7 @@
-use std::env;
+use std::fmt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{json, Value};
 use crate::tracing::info;
 use anyhow::{Result, anyhow};
 
@@ -9,42 +9,379 @@ use anyhow::{Result, anyhow};
 struct SlackResponse {
     ok: bool,
     error: Option<String>,
+    ts: Option<String>,
 }
 
+impl SlackResponse {
+    fn into_result(self) -> Result<Option<String>> {
+        if self.ok {
+            Ok(self.ts)
+        } else {
+            Err(SlackApiError { error: self.error.unwrap_or_else(|| "Unknown error".to_string()) }.into())
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SlackApiError {
+    pub error: String,
+}
+
+impl SlackApiError {
+    pub fn is_channel_unavailable(&self) -> bool {
+        matches!(self.error.as_str(), "is_archived" | "channel_not_found")
+    }
+
+    pub fn is_invalid_blocks(&self) -> bool {
+        matches!(self.error.as_str(), "invalid_blocks" | "invalid_blocks_format")
+    }
+
+    pub fn is_message_not_found(&self) -> bool {
+        self.error == "message_not_found"
+    }
+}
+
+impl fmt::Display for SlackApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Slack API error: {}", self.error)
+    }
+}
+
+impl std::error::Error for SlackApiError {}
+
 pub trait TeamCommunicationClient {
-    async fn send_teams_message<T: Serialize>(&self, channel_id: &str, blocks: &T, response_url: Option<String>) -> Result<()>;
+    //resolves to the posted message's ts, which replies through a response_url don't have
+    async fn send_teams_message<T: Serialize>(&self, channel_id: &str, blocks: &T, response_url: Option<String>) -> Result<Option<String>>;
+    async fn send_text_message(&self, channel_id: &str, text: &str, response_url: Option<String>) -> Result<Option<String>>;
+    async fn post_teams_message<T: Serialize>(&self, channel_id: &str, blocks: &T, thread_ts: Option<&str>) -> Result<String>;
+    async fn update_teams_message<T: Serialize>(&self, channel_id: &str, ts: &str, blocks: &T) -> Result<()>;
+    async fn delete_teams_message(&self, channel_id: &str, ts: &str) -> Result<()>;
+    async fn post_ephemeral_message<T: Serialize>(&self, channel_id: &str, user_id: &str, blocks: &T) -> Result<()>;
+
+    //edits the message at ts when there is one, and posts a new one (in thread_ts's thread, if given) if there isn't or it was deleted
+    async fn upsert_teams_message<T: Serialize>(&self, channel_id: &str, ts: Option<&str>, thread_ts: Option<&str>, blocks: &T) -> Result<String> {
+        if let Some(ts) = ts {
+            match self.update_teams_message(channel_id, ts, blocks).await {
+                Ok(()) => return Ok(ts.to_string()),
+                Err(e) if e.downcast_ref::<SlackApiError>().is_some_and(SlackApiError::is_message_not_found) => {
+                    info!("Message {} is gone, posting a new one: {}", ts, e);
+                },
+                Err(e) => return Err(e),
+            }
+        }
+
+        self.post_teams_message(channel_id, blocks, thread_ts).await
+    }
+
+    //posts the plain text instead if Slack rejects the blocks, so the channel still gets something
+    async fn send_teams_message_or_fallback<T: Serialize>(&self, channel_id: &str, blocks: &T, fallback_text: &str, response_url: Option<String>) -> Result<Option<String>> {
+        match self.send_teams_message(channel_id, blocks, response_url.clone()).await {
+            Err(e) if e.downcast_ref::<SlackApiError>().is_some_and(SlackApiError::is_invalid_blocks) => {
+                info!("Slack rejected the message blocks, sending plain text instead: {}", e);
+                self.send_text_message(channel_id, fallback_text, response_url).await
+            },
+            result => result,
+        }
+    }
+}
+
+pub struct SlackClient<'a> {
+    pub client: &'a Client,
+    pub oauth_token: &'a str,
 }
 
-impl TeamCommunicationClient for Client {
-    async fn send_teams_message<T: Serialize>(&self, channel_id: &str, blocks: &T, response_url: Option<String>) -> Result<()> {
-        let slack_token = env::var("SLACK_OAUTH").expect("SLACK_OAUTH environment variable should exist");
-        
-        let message = json!({
+impl<'a> SlackClient<'a> {
+    pub fn new(client: &'a Client, oauth_token: &'a str) -> Self {
+        SlackClient { client, oauth_token }
+    }
+}
+
+impl TeamCommunicationClient for SlackClient<'_> {
+    async fn send_teams_message<T: Serialize>(&self, channel_id: &str, blocks: &T, response_url: Option<String>) -> Result<Option<String>> {
+        post_slack_message(self, blocks_message(channel_id, blocks, None), response_url).await
+    }
+
+    async fn send_text_message(&self, channel_id: &str, text: &str, response_url: Option<String>) -> Result<Option<String>> {
+        post_slack_message(self, json!({
+            "channel": channel_id,
+            "text": text
+        }), response_url).await
+    }
+
+    async fn post_teams_message<T: Serialize>(&self, channel_id: &str, blocks: &T, thread_ts: Option<&str>) -> Result<String> {
+        post_slack_message(self, blocks_message(channel_id, blocks, thread_ts), None).await?
+            .ok_or(anyhow!("Slack chat.postMessage response is missing the message ts"))
+    }
+
+    async fn update_teams_message<T: Serialize>(&self, channel_id: &str, ts: &str, blocks: &T) -> Result<()> {
+        send_slack_request(self, "https://slack.com/api/chat.update", update_message(channel_id, ts, blocks)).await?;
+        Ok(())
+    }
+
+    async fn delete_teams_message(&self, channel_id: &str, ts: &str) -> Result<()> {
+        send_slack_request(self, "https://slack.com/api/chat.delete", json!({
             "channel": channel_id,
-            "blocks": blocks
-        });
-    
-        info!("Message to Slack: {}", message);
-    
-        let response = self.post(response_url.unwrap_or("https://slack.com/api/chat.postMessage".to_string()))
-            .bearer_auth(slack_token)
-            .json(&message)
-            .send()
-            .await?;
-    
-        if response.status().is_success() {
-            let response_body = response.text().await?;
-            let slack_response: SlackResponse = serde_json::from_str(&response_body)
-                .map_err(|e| anyhow!("Failed to deserialize Slack response: {}", e))?;
-    
-            info!("Response from Slack: {}", response_body);
-            if slack_response.ok {
-                Ok(())
-            } else {
-                Err(anyhow!("Slack API error: {}", slack_response.error.unwrap_or_else(|| "Unknown error".to_string())))
+            "ts": ts
+        })).await?;
+        Ok(())
+    }
+
+    async fn post_ephemeral_message<T: Serialize>(&self, channel_id: &str, user_id: &str, blocks: &T) -> Result<()> {
+        send_slack_request(self, "https://slack.com/api/chat.postEphemeral", ephemeral_message(channel_id, user_id, blocks)).await?;
+        Ok(())
+    }
+}
+
+//a thread_ts posts the message as a reply in that message's thread
+fn blocks_message<T: Serialize>(channel_id: &str, blocks: &T, thread_ts: Option<&str>) -> Value {
+    let mut message = json!({
+        "channel": channel_id,
+        "blocks": blocks
+    });
+    if let Some(thread_ts) = thread_ts {
+        message["thread_ts"] = json!(thread_ts);
+    }
+    message
+}
+
+//only the given user sees an ephemeral message, and it's gone once their Slack client reloads
+fn ephemeral_message<T: Serialize>(channel_id: &str, user_id: &str, blocks: &T) -> Value {
+    json!({
+        "channel": channel_id,
+        "user": user_id,
+        "blocks": blocks
+    })
+}
+
+//chat.update replaces the blocks of the message at ts, replies stay in their thread
+fn update_message<T: Serialize>(channel_id: &str, ts: &str, blocks: &T) -> Value {
+    json!({
+        "channel": channel_id,
+        "ts": ts,
+        "blocks": blocks
+    })
+}
+
+//only Slack's own response URLs get the bearer token, anything else is posted with chat.postMessage instead
+fn trusted_response_url(response_url: Option<String>) -> Option<String> {
+    let response_url = response_url?;
+    let is_slack_hook = reqwest::Url::parse(&response_url)
+        .is_ok_and(|url| url.scheme() == "https" && url.host_str() == Some("hooks.slack.com"));
+
+    if is_slack_hook {
+        Some(response_url)
+    } else {
+        info!("Ignoring response_url that isn't a Slack hook: {}", response_url);
+        None
+    }
+}
+
+async fn post_slack_message(slack_client: &SlackClient<'_>, message: Value, response_url: Option<String>) -> Result<Option<String>> {
+    send_slack_request(slack_client, &trusted_response_url(response_url).unwrap_or("https://slack.com/api/chat.postMessage".to_string()), message).await
+}
+
+fn parse_slack_response(response_body: &str) -> Result<Option<String>> {
+    let slack_response: SlackResponse = serde_json::from_str(response_body)
+        .map_err(|e| anyhow!("Failed to deserialize Slack response: {}", e))?;
+
+    slack_response.into_result()
+}
+
+//resolves to the ts of the message Slack posted or updated, if the response has one
+async fn send_slack_request(slack_client: &SlackClient<'_>, url: &str, message: Value) -> Result<Option<String>> {
+    info!("Message to Slack: {}", message);
+
+    let response = slack_client.client.post(url)
+        .bearer_auth(slack_client.oauth_token)
+        .json(&message)
+        .send()
+        .await?;
+
+    if response.status().is_success() {
+        let response_body = response.text().await?;
+
+        info!("Response from Slack: {}", response_body);
+        parse_slack_response(&response_body)
+    } else {
+        Err(anyhow!("Failed to send message to Slack with status: {}", response.status()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use super::*;
+
+    struct MockTeamCommunicationClient {
+        blocks_error: Option<String>,
+        sent_messages: RefCell<Vec<Value>>,
+    }
+
+    impl MockTeamCommunicationClient {
+        fn new(blocks_error: Option<&str>) -> Self {
+            MockTeamCommunicationClient { blocks_error: blocks_error.map(str::to_string), sent_messages: RefCell::new(vec![]) }
+        }
+    }
+
+    impl TeamCommunicationClient for MockTeamCommunicationClient {
+        async fn send_teams_message<T: Serialize>(&self, channel_id: &str, blocks: &T, _response_url: Option<String>) -> Result<Option<String>> {
+            match &self.blocks_error {
+                Some(error) => Err(SlackApiError { error: error.clone() }.into()),
+                None => {
+                    self.sent_messages.borrow_mut().push(json!({ "channel": channel_id, "blocks": blocks }));
+                    Ok(Some("1700000002.000200".to_string()))
+                },
             }
-        } else {
-            Err(anyhow!("Failed to send message to Slack with status: {}", response.status()))
         }
+
+        async fn send_text_message(&self, channel_id: &str, text: &str, _response_url: Option<String>) -> Result<Option<String>> {
+            self.sent_messages.borrow_mut().push(json!({ "channel": channel_id, "text": text }));
+            Ok(Some("1700000003.000300".to_string()))
+        }
+
+        async fn post_teams_message<T: Serialize>(&self, channel_id: &str, blocks: &T, thread_ts: Option<&str>) -> Result<String> {
+            self.sent_messages.borrow_mut().push(blocks_message(channel_id, blocks, thread_ts));
+            Ok("1700000002.000200".to_string())
+        }
+
+        //the blocks error stands in for whatever chat.update answers
+        async fn update_teams_message<T: Serialize>(&self, channel_id: &str, ts: &str, blocks: &T) -> Result<()> {
+            match &self.blocks_error {
+                Some(error) => Err(SlackApiError { error: error.clone() }.into()),
+                None => {
+                    self.sent_messages.borrow_mut().push(update_message(channel_id, ts, blocks));
+                    Ok(())
+                },
+            }
+        }
+
+        async fn delete_teams_message(&self, channel_id: &str, ts: &str) -> Result<()> {
+            self.sent_messages.borrow_mut().push(json!({ "channel": channel_id, "ts": ts }));
+            Ok(())
+        }
+
+        async fn post_ephemeral_message<T: Serialize>(&self, channel_id: &str, user_id: &str, blocks: &T) -> Result<()> {
+            self.sent_messages.borrow_mut().push(ephemeral_message(channel_id, user_id, blocks));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_archived_channel_is_unavailable() {
+        assert!(SlackApiError { error: "is_archived".to_string() }.is_channel_unavailable());
+        assert!(SlackApiError { error: "channel_not_found".to_string() }.is_channel_unavailable());
+        assert!(!SlackApiError { error: "invalid_blocks".to_string() }.is_channel_unavailable());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_blocks_falls_back_to_plain_text() {
+        let client = MockTeamCommunicationClient::new(Some("invalid_blocks"));
+
+        let ts = client.send_teams_message_or_fallback("C123", &json!([{"type": "bogus"}]), "3 tickets open", None).await.unwrap();
+
+        assert_eq!(ts.as_deref(), Some("1700000003.000300"));
+        assert_eq!(*client.sent_messages.borrow(), vec![json!({ "channel": "C123", "text": "3 tickets open" })]);
+    }
+
+    #[tokio::test]
+    async fn test_other_slack_errors_do_not_fall_back() {
+        let client = MockTeamCommunicationClient::new(Some("is_archived"));
+
+        let result = client.send_teams_message_or_fallback("C123", &json!([]), "3 tickets open", None).await;
+
+        assert!(result.is_err());
+        assert!(client.sent_messages.borrow().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_upsert_updates_stored_message() {
+        let client = MockTeamCommunicationClient::new(None);
+
+        let ts = client.upsert_teams_message("C123", Some("1700000001.000100"), None, &json!([{"type": "divider"}])).await.unwrap();
+
+        assert_eq!(ts, "1700000001.000100");
+        assert_eq!(*client.sent_messages.borrow(), vec![json!({ "channel": "C123", "ts": "1700000001.000100", "blocks": [{"type": "divider"}] })]);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_posts_new_message_when_stored_one_is_gone() {
+        let client = MockTeamCommunicationClient::new(Some("message_not_found"));
+
+        let ts = client.upsert_teams_message("C123", Some("1700000001.000100"), None, &json!([])).await.unwrap();
+
+        assert_eq!(ts, "1700000002.000200");
+        assert_eq!(*client.sent_messages.borrow(), vec![json!({ "channel": "C123", "blocks": [] })]);
+
+        let client = MockTeamCommunicationClient::new(Some("is_archived"));
+        assert!(client.upsert_teams_message("C123", Some("1700000001.000100"), None, &json!([])).await.is_err());
+        assert!(client.sent_messages.borrow().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_upsert_without_stored_message_posts_in_thread() {
+        let client = MockTeamCommunicationClient::new(None);
+
+        let ts = client.upsert_teams_message("C123", None, Some("1690000000.000001"), &json!([])).await.unwrap();
+
+        assert_eq!(ts, "1700000002.000200");
+        assert_eq!(*client.sent_messages.borrow(), vec![json!({ "channel": "C123", "blocks": [], "thread_ts": "1690000000.000001" })]);
+    }
+
+    #[test]
+    fn test_update_message_payload() {
+        assert_eq!(update_message("C123", "1700000001.000100", &json!([{"type": "divider"}])), json!({
+            "channel": "C123",
+            "ts": "1700000001.000100",
+            "blocks": [{"type": "divider"}]
+        }));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_ephemeral_message_payload() {
+        assert_eq!(ephemeral_message("C123", "U123", &json!([{"type": "divider"}])), json!({
+            "channel": "C123",
+            "user": "U123",
+            "blocks": [{"type": "divider"}]
+        }));
+    }
+
+    #[test]
+    fn test_threaded_message_payload() {
+        assert_eq!(blocks_message("C123", &json!([]), Some("1700000001.000100")), json!({ "channel": "C123", "blocks": [], "thread_ts": "1700000001.000100" }));
+        assert_eq!(blocks_message("C123", &json!([]), None), json!({ "channel": "C123", "blocks": [] }));
+    }
+
+    #[test]
+    fn test_slack_response_ts() {
+        let response: SlackResponse = serde_json::from_str(r#"{"ok": true, "channel": "C123", "ts": "1700000001.000100", "message": {"type": "message"}}"#).unwrap();
+        assert_eq!(response.into_result().unwrap().as_deref(), Some("1700000001.000100"));
+
+        let response: SlackResponse = serde_json::from_str(r#"{"ok": false, "error": "message_not_found"}"#).unwrap();
+        assert!(response.into_result().unwrap_err().downcast_ref::<SlackApiError>().unwrap().is_message_not_found());
+    }
+
+    #[test]
+    fn test_posted_message_ts_from_response_body() {
+        let body = r#"{"ok": true, "channel": "C123", "ts": "1700000004.000400", "message": {"type": "message", "text": "", "blocks": []}}"#;
+        assert_eq!(parse_slack_response(body).unwrap().as_deref(), Some("1700000004.000400"));
+
+        //replies through a response_url don't say which message they became
+        assert_eq!(parse_slack_response(r#"{"ok": true}"#).unwrap(), None);
+        assert!(parse_slack_response("ok").is_err());
+    }
+
+    #[test]
+    fn test_slack_response_url_is_trusted() {
+        let response_url = "https://hooks.slack.com/commands/T01BFE465AN/6981624816737/DjjVeStvAKmQlbIXoPvXymce".to_string();
+        assert_eq!(trusted_response_url(Some(response_url.clone())), Some(response_url));
+        assert_eq!(trusted_response_url(None), None);
+    }
+
+    #[test]
+    fn test_foreign_response_url_is_rejected() {
+        assert_eq!(trusted_response_url(Some("https://example.com/commands/T01BFE465AN".to_string())), None);
+        assert_eq!(trusted_response_url(Some("https://hooks.slack.com.example.com/commands".to_string())), None);
+        assert_eq!(trusted_response_url(Some("http://hooks.slack.com/commands".to_string())), None);
+        assert_eq!(trusted_response_url(Some("not a url".to_string())), None);
+    }
+}
@@ -0,0 +1,150 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use serde::Deserialize;
+use anyhow::{Result, anyhow};
+use crate::tracing::info;
+use crate::utils::slack_output::SlackClient;
+
+#[derive(Deserialize)]
+struct SlackUserProfile {
+    display_name: Option<String>,
+    real_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SlackUser {
+    name: String,
+    profile: SlackUserProfile,
+}
+
+#[derive(Deserialize)]
+struct SlackUserInfoResponse {
+    ok: bool,
+    error: Option<String>,
+    user: Option<SlackUser>,
+}
+
+impl SlackUserInfoResponse {
+    fn into_display_name(self) -> Result<String> {
+        if !self.ok {
+            return Err(anyhow!("Slack API error: {}", self.error.unwrap_or_else(|| "Unknown error".to_string())));
+        }
+
+        let user = self.user.ok_or(anyhow!("Slack users.info response is missing the user"))?;
+
+        Ok([user.profile.display_name, user.profile.real_name].into_iter()
+            .flatten()
+            .find(|name| !name.is_empty())
+            .unwrap_or(user.name))
+    }
+}
+
+pub trait SlackUserClient {
+    async fn fetch_display_name(&self, user_id: &str) -> Result<String>;
+}
+
+impl SlackUserClient for SlackClient<'_> {
+    async fn fetch_display_name(&self, user_id: &str) -> Result<String> {
+        info!("Fetching Slack user info for: {}", user_id);
+
+        let response: SlackUserInfoResponse = self.client.get("https://slack.com/api/users.info")
+            .bearer_auth(self.oauth_token)
+            .query(&[("user", user_id)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        response.into_display_name()
+    }
+}
+
+//caches display names for the lifetime of a single invocation
+pub struct DisplayNameResolver<'a, T: SlackUserClient> {
+    client: &'a T,
+    cache: RefCell<HashMap<String, String>>,
+}
+
+impl<'a, T: SlackUserClient> DisplayNameResolver<'a, T> {
+    pub fn new(client: &'a T) -> Self {
+        DisplayNameResolver { client, cache: RefCell::new(HashMap::new()) }
+    }
+
+    pub async fn resolve(&self, user_id: &str) -> Result<String> {
+        if let Some(display_name) = self.cache.borrow().get(user_id) {
+            return Ok(display_name.clone());
+        }
+
+        let display_name = self.client.fetch_display_name(user_id).await?;
+        self.cache.borrow_mut().insert(user_id.to_string(), display_name.clone());
+
+        Ok(display_name)
+    }
+
+    pub async fn resolve_all<'b>(&self, user_ids: impl IntoIterator<Item = &'b str>) -> Result<BTreeMap<String, String>> {
+        let mut display_names = BTreeMap::new();
+
+        for user_id in user_ids {
+            display_names.insert(user_id.to_string(), self.resolve(user_id).await?);
+        }
+
+        Ok(display_names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use super::*;
+
+    struct MockSlackUserClient {
+        names: HashMap<String, String>,
+        calls: Cell<u32>,
+    }
+
+    impl SlackUserClient for MockSlackUserClient {
+        async fn fetch_display_name(&self, user_id: &str) -> Result<String> {
+            self.calls.set(self.calls.get() + 1);
+            self.names.get(user_id).cloned().ok_or(anyhow!("user_not_found"))
+        }
+    }
+
+    #[test]
+    fn test_display_name_prefers_profile_display_name() {
+        let response: SlackUserInfoResponse = serde_json::from_str(r#"{"ok": true, "user": {"name": "jdoe", "profile": {"display_name": "Jay", "real_name": "Jay Doe"}}}"#).unwrap();
+        assert_eq!(response.into_display_name().unwrap(), "Jay");
+    }
+
+    #[test]
+    fn test_display_name_falls_back_to_real_name_then_handle() {
+        let response: SlackUserInfoResponse = serde_json::from_str(r#"{"ok": true, "user": {"name": "jdoe", "profile": {"display_name": "", "real_name": "Jay Doe"}}}"#).unwrap();
+        assert_eq!(response.into_display_name().unwrap(), "Jay Doe");
+
+        let response: SlackUserInfoResponse = serde_json::from_str(r#"{"ok": true, "user": {"name": "jdoe", "profile": {}}}"#).unwrap();
+        assert_eq!(response.into_display_name().unwrap(), "jdoe");
+    }
+
+    #[test]
+    fn test_display_name_error_response() {
+        let response: SlackUserInfoResponse = serde_json::from_str(r#"{"ok": false, "error": "user_not_found"}"#).unwrap();
+        assert!(response.into_display_name().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolver_caches_display_names() {
+        let client = MockSlackUserClient {
+            names: HashMap::from([("U1".to_string(), "Jay".to_string()), ("U2".to_string(), "Sam".to_string())]),
+            calls: Cell::new(0),
+        };
+        let resolver = DisplayNameResolver::new(&client);
+
+        assert_eq!(resolver.resolve("U1").await.unwrap(), "Jay");
+        assert_eq!(resolver.resolve("U1").await.unwrap(), "Jay");
+        assert_eq!(client.calls.get(), 1);
+
+        let display_names = resolver.resolve_all(["U1", "U2"]).await.unwrap();
+        assert_eq!(display_names, BTreeMap::from([("U1".to_string(), "Jay".to_string()), ("U2".to_string(), "Sam".to_string())]));
+        assert_eq!(client.calls.get(), 2);
+    }
+}
@@ -0,0 +1,93 @@
+use std::time::{Duration, Instant};
+
+pub trait Clock {
+    fn elapsed(&self) -> Duration;
+}
+
+pub struct SystemClock {
+    started_at: Instant,
+}
+
+impl SystemClock {
+    pub fn start() -> Self {
+        SystemClock { started_at: Instant::now() }
+    }
+}
+
+impl Clock for SystemClock {
+    fn elapsed(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+}
+
+pub struct TimeBudget {
+    clock: Box<dyn Clock>,
+    limit: Duration,
+    reserve: Duration,
+}
+
+impl TimeBudget {
+    pub fn new(clock: Box<dyn Clock>, limit: Duration, reserve: Duration) -> Self {
+        TimeBudget { clock, limit, reserve }
+    }
+
+    //true once there is only enough time left to post the summary
+    pub fn is_nearly_exceeded(&self) -> bool {
+        self.clock.elapsed() + self.reserve >= self.limit
+    }
+}
+
+#[cfg(test)]
+pub mod mocks {
+    use std::{cell::Cell, rc::Rc, time::Duration};
+    use super::{Clock, TimeBudget};
+
+    #[derive(Clone, Default)]
+    pub struct MockClock {
+        pub elapsed: Rc<Cell<Duration>>,
+    }
+
+    impl MockClock {
+        pub fn advance(&self, duration: Duration) {
+            self.elapsed.set(self.elapsed.get() + duration);
+        }
+    }
+
+    impl Clock for MockClock {
+        fn elapsed(&self) -> Duration {
+            self.elapsed.get()
+        }
+    }
+
+    impl Default for TimeBudget {
+        fn default() -> Self {
+            TimeBudget::new(Box::new(MockClock::default()), Duration::from_secs(25), Duration::from_secs(5))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::mocks::MockClock;
+
+    #[test]
+    fn test_budget_not_exceeded_early() {
+        let clock = MockClock::default();
+        let budget = TimeBudget::new(Box::new(clock.clone()), Duration::from_secs(25), Duration::from_secs(5));
+
+        clock.advance(Duration::from_secs(10));
+
+        assert!(!budget.is_nearly_exceeded());
+    }
+
+    #[test]
+    fn test_budget_nearly_exceeded_within_reserve() {
+        let clock = MockClock::default();
+        let budget = TimeBudget::new(Box::new(clock.clone()), Duration::from_secs(25), Duration::from_secs(5));
+
+        clock.advance(Duration::from_secs(20));
+
+        assert!(budget.is_nearly_exceeded());
+    }
+}